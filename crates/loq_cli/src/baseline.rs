@@ -1,13 +1,16 @@
 //! Baseline command implementation.
 
 use std::collections::HashMap;
+use std::path::Path;
 
 use anyhow::{Context, Result};
 use termcolor::WriteColor;
-use toml_edit::DocumentMut;
+use toml_edit::Table;
 
 use crate::cli::BaselineArgs;
-use crate::config_edit::{config_path_and_root, line_threshold, load_doc_or_default, persist_doc};
+use crate::config_edit::{
+    config_path_and_root, line_threshold, load_doc_or_default, loq_table_mut, persist_doc,
+};
 use crate::exact_limits::{self, ExactLimit, ExactLimits};
 use crate::line_violations::scan_line_violations;
 use crate::output::{
@@ -22,10 +25,11 @@ struct BaselineReport {
 
 pub fn run_baseline<W1: WriteColor, W2: WriteColor>(
     args: &BaselineArgs,
+    config: Option<&Path>,
     stdout: &mut W1,
     stderr: &mut W2,
 ) -> ExitStatus {
-    match run_baseline_inner(args) {
+    match run_baseline_inner(args, config) {
         Ok(report) if report.changes.is_empty() => {
             let _ = writeln!(stdout, "✔ No changes needed");
             ExitStatus::Success
@@ -38,18 +42,19 @@ pub fn run_baseline<W1: WriteColor, W2: WriteColor>(
     }
 }
 
-fn run_baseline_inner(args: &BaselineArgs) -> Result<BaselineReport> {
+fn run_baseline_inner(args: &BaselineArgs, config: Option<&Path>) -> Result<BaselineReport> {
     let cwd = std::env::current_dir().context("failed to get current directory")?;
-    let (config_path, root) = config_path_and_root(&cwd)?;
+    let (config_path, root) = config_path_and_root(&cwd, config)?;
 
     let (mut doc, config_exists) = load_doc_or_default(&config_path)?;
     let config = loq_core::parse_config(&config_path, &doc.to_string())?;
     let threshold = line_threshold(&config, args.threshold);
     let violations = scan_line_violations(&root, &cwd, &config_path, config, threshold)
         .context("baseline check failed")?;
-    let existing_rules = ExactLimits::collect(&doc);
+    let table = loq_table_mut(&mut doc, &config_path)?;
+    let existing_rules = ExactLimits::collect(table);
     let scope = loq_fs::PathIdentity::new(&cwd, &root, &root).match_key;
-    let report = apply_baseline_changes(&mut doc, &violations, &existing_rules, &scope);
+    let report = apply_baseline_changes(table, &violations, &existing_rules, &scope);
 
     persist_doc(&root, &config_path, &doc, config_exists)?;
 
@@ -57,7 +62,7 @@ fn run_baseline_inner(args: &BaselineArgs) -> Result<BaselineReport> {
 }
 
 fn apply_baseline_changes(
-    doc: &mut DocumentMut,
+    doc: &mut Table,
     violations: &HashMap<String, usize>,
     existing_rules: &ExactLimits,
     scope: &str,
@@ -1,16 +1,20 @@
 //! Check command implementation.
 
 use std::io::{Read, Write};
+use std::path::Path;
 
-use loq_core::report::{build_report, Finding, FindingKind, Report, SkipReason};
+use loq_core::report::{
+    build_report, FileOutcome, Finding, FindingKind, OutcomeKind, Report, SkipReason,
+};
+use loq_core::{ConfigError, ConfigLayer, Limit, Severity};
 use loq_fs::{CheckConfig, CheckOptions, CheckOutput, FsError};
 use termcolor::{Color, WriteColor};
 
 use self::input_scope::resolve_check_inputs;
 use crate::cli::{CheckArgs, OutputFormat};
 use crate::output::{
-    print_error, write_block, write_finding, write_guidance, write_json, write_summary,
-    write_walk_errors,
+    print_error, write_block, write_config_errors_json, write_finding, write_guidance, write_json,
+    write_summary, write_walk_errors,
 };
 use crate::Cli;
 use crate::ExitStatus;
@@ -33,6 +37,7 @@ pub const fn output_mode(cli: &Cli) -> OutputMode {
 
 pub fn run_check<R: Read, W1: WriteColor + Write, W2: WriteColor>(
     args: &CheckArgs,
+    config: Option<&Path>,
     stdin: &mut R,
     stdout: &mut W1,
     stderr: &mut W2,
@@ -47,22 +52,60 @@ pub fn run_check<R: Read, W1: WriteColor + Write, W2: WriteColor>(
         Err(err) => return print_error(stderr, &format!("{err:#}")),
     };
 
+    let overrides = match config_overrides(args) {
+        Ok(overrides) => overrides,
+        Err(err) => return print_error(stderr, &err.to_string()),
+    };
+
     let options = CheckOptions {
-        config: resolved
-            .config_path
-            .map_or(CheckConfig::Discover, CheckConfig::File),
+        config: config.map_or(CheckConfig::Discover, |path| {
+            CheckConfig::Explicit(cwd.join(path))
+        }),
         cwd: cwd.clone(),
         use_cache: !args.no_cache,
+        overrides,
     };
 
-    let output = match loq_fs::run_check(resolved.paths, options) {
+    let mut output = match loq_fs::run_check(resolved.paths, options) {
         Ok(output) => output,
+        Err(FsError::Config(ConfigError::Diagnostics {
+            path, diagnostics, ..
+        })) if args.output_format == OutputFormat::Json => {
+            let _ = write_config_errors_json(stdout, &path, &diagnostics);
+            return ExitStatus::Error;
+        }
         Err(err) => return handle_fs_error(&err, stderr),
     };
+    if args.warnings_as_errors {
+        promote_warnings(&mut output.outcomes);
+    }
 
     handle_check_output(output, stdout, mode, args.output_format)
 }
 
+/// Raises every over-budget outcome to `error` severity (`--warnings-as-errors`).
+fn promote_warnings(outcomes: &mut [FileOutcome]) {
+    for outcome in outcomes {
+        if let OutcomeKind::Violation { severity, .. } = &mut outcome.kind {
+            *severity = Severity::Error;
+        }
+    }
+}
+
+/// Collects `--set`, `--extend-exclude` and `--default-max-lines` into one layer.
+fn config_overrides(args: &CheckArgs) -> Result<ConfigLayer, ConfigError> {
+    let mut overrides = loq_core::parse_overrides(&args.set)?;
+    for pattern in &args.extend_exclude {
+        if !overrides.exclude.contains(pattern) {
+            overrides.exclude.push(pattern.clone());
+        }
+    }
+    if let Some(max_lines) = args.default_max_lines {
+        overrides.default_limit = Some(Limit::lines(max_lines));
+    }
+    Ok(overrides)
+}
+
 fn handle_fs_error<W: WriteColor>(err: &FsError, stderr: &mut W) -> ExitStatus {
     let message = format!("error: {err}");
     let _ = write_block(stderr, Some(Color::Red), &message);
@@ -77,10 +120,17 @@ fn handle_check_output<W: WriteColor + Write>(
 ) -> ExitStatus {
     let CheckOutput {
         outcomes,
+        directory_violations,
+        bundle_violations,
         walk_errors,
         fix_guidance,
     } = output;
-    let report = build_report(&outcomes, fix_guidance);
+    let report = build_report(
+        &outcomes,
+        &directory_violations,
+        &bundle_violations,
+        fix_guidance,
+    );
 
     match format {
         OutputFormat::Json => {
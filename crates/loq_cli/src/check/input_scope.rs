@@ -45,7 +45,6 @@ fn git_filter_from_args(args: &CheckArgs) -> Option<GitFilter> {
 
 pub(super) struct ResolvedCheckInputs {
     pub paths: Vec<PathBuf>,
-    pub config_path: Option<PathBuf>,
 }
 
 pub(super) fn resolve_check_inputs<R: Read>(
@@ -57,7 +56,6 @@ pub(super) fn resolve_check_inputs<R: Read>(
         Some(filter) => resolve_git_inputs(&filter, cwd),
         None => Ok(ResolvedCheckInputs {
             paths: collect_inputs(args.paths.clone(), args.stdin, stdin, cwd)?,
-            config_path: None,
         }),
     }
 }
@@ -130,9 +128,8 @@ fn resolve_git_inputs(filter: &GitFilter, cwd: &Path) -> Result<ResolvedCheckInp
     let not_repo_message = filter.not_repo_message();
     let repo_root = git_repo_root(cwd, &unavailable_message, &not_repo_message)?;
     let paths = list_git_paths(filter, &repo_root, &unavailable_message)?;
-    let config_path = loq_fs::discover::find_config(&repo_root);
 
-    Ok(ResolvedCheckInputs { paths, config_path })
+    Ok(ResolvedCheckInputs { paths })
 }
 
 fn list_git_paths(
@@ -42,6 +42,10 @@ fn check_args(staged: bool, diff: Option<&str>) -> CheckArgs {
         diff: diff.map(str::to_owned),
         no_cache: false,
         output_format: crate::cli::OutputFormat::Text,
+        set: vec![],
+        extend_exclude: vec![],
+        default_max_lines: None,
+        warnings_as_errors: false,
     }
 }
 
@@ -99,12 +103,15 @@ fn resolve_check_inputs_without_git_filter_uses_collect_inputs_behavior() {
         diff: None,
         no_cache: false,
         output_format: crate::cli::OutputFormat::Text,
+        set: vec![],
+        extend_exclude: vec![],
+        default_max_lines: None,
+        warnings_as_errors: false,
     };
     let mut empty_stdin: &[u8] = b"";
 
     let result = resolve_check_inputs(&args, &mut empty_stdin, Path::new("/repo")).unwrap();
     assert_eq!(result.paths, vec![PathBuf::from(".")]);
-    assert_eq!(result.config_path, None);
 }
 
 #[test]
@@ -249,7 +256,7 @@ fn git_diff_args_match_expected_flags() {
 }
 
 #[test]
-fn resolve_git_inputs_uses_repo_root_config() {
+fn resolve_git_inputs_lists_repo_wide_paths_from_subdir() {
     let temp = TempDir::new().unwrap();
     init_test_repo(temp.path());
     let sub = temp.path().join("sub");
@@ -264,9 +271,6 @@ fn resolve_git_inputs_uses_repo_root_config() {
     exec_git(temp.path(), &["add", "outside.txt"]);
 
     let resolved = resolve_git_inputs(&GitFilter::Staged, &sub).unwrap();
-    let expected_config = dunce::canonicalize(temp.path().join("loq.toml"))
-        .unwrap_or_else(|_| temp.path().join("loq.toml"));
-    assert_eq!(resolved.config_path, Some(expected_config));
     assert!(resolved
         .paths
         .iter()
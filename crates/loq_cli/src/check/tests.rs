@@ -24,16 +24,24 @@ fn handle_check_output_default_mode_shows_missing_but_skips_other_warnings() {
             FileOutcome {
                 display_path: "missing.txt".into(),
                 match_key: "missing.txt".into(),
+                config_path: None,
                 kind: OutcomeKind::Missing,
+                lines: None,
+                bundle_tokens: None,
             },
             FileOutcome {
                 display_path: "skipped.bin".into(),
                 match_key: "skipped.bin".into(),
+                config_path: None,
                 kind: OutcomeKind::Binary,
+                lines: None,
+                bundle_tokens: None,
             },
         ],
         walk_errors: vec![],
         fix_guidance: None,
+        directory_violations: Vec::new(),
+        bundle_violations: Vec::new(),
     };
 
     let status = handle_check_output(output, &mut stdout, OutputMode::Default, OutputFormat::Text);
@@ -55,10 +63,15 @@ fn handle_check_output_verbose_mode_shows_skip_warnings() {
         outcomes: vec![FileOutcome {
             display_path: "missing.txt".into(),
             match_key: "missing.txt".into(),
+            config_path: None,
             kind: OutcomeKind::Missing,
+            lines: None,
+            bundle_tokens: None,
         }],
         walk_errors: vec![],
         fix_guidance: None,
+        directory_violations: Vec::new(),
+        bundle_violations: Vec::new(),
     };
 
     let status = handle_check_output(output, &mut stdout, OutputMode::Verbose, OutputFormat::Text);
@@ -80,6 +93,8 @@ fn handle_check_output_with_walk_errors() {
             message: "permission denied".into(),
         }],
         fix_guidance: None,
+        directory_violations: Vec::new(),
+        bundle_violations: Vec::new(),
     };
 
     let _status = handle_check_output(output, &mut stdout, OutputMode::Default, OutputFormat::Text);
@@ -89,7 +104,7 @@ fn handle_check_output_with_walk_errors() {
 
 #[test]
 fn handle_check_output_json_format() {
-    use loq_core::report::{FileOutcome, OutcomeKind};
+    use loq_core::report::{FileOutcome, Measurement, OutcomeKind};
     use loq_core::MatchBy;
     use loq_fs::walk::WalkError;
     use termcolor::NoColor;
@@ -100,22 +115,30 @@ fn handle_check_output_json_format() {
             FileOutcome {
                 display_path: "big.rs".into(),
                 match_key: "big.rs".into(),
+                config_path: None,
                 kind: OutcomeKind::Violation {
-                    limit: loq_core::Limit::lines(100),
-                    actual: 150,
+                    measurements: vec![Measurement::new(loq_core::Limit::lines(100), 150)],
                     matched_by: MatchBy::Default,
+                    severity: loq_core::Severity::Error,
                 },
+                lines: None,
+                bundle_tokens: None,
             },
             FileOutcome {
                 display_path: "skipped.bin".into(),
                 match_key: "skipped.bin".into(),
+                config_path: None,
                 kind: OutcomeKind::Binary,
+                lines: None,
+                bundle_tokens: None,
             },
         ],
         walk_errors: vec![WalkError {
             message: "permission denied".into(),
         }],
         fix_guidance: Some("Split large files.".to_string()),
+        directory_violations: Vec::new(),
+        bundle_violations: Vec::new(),
     };
 
     let status = handle_check_output(output, &mut stdout, OutputMode::Default, OutputFormat::Json);
@@ -124,8 +147,8 @@ fn handle_check_output_json_format() {
     let output_str = String::from_utf8(stdout.into_inner()).unwrap();
     let parsed: serde_json::Value = serde_json::from_str(&output_str).unwrap();
     assert_eq!(parsed["violations"][0]["path"], "big.rs");
-    assert_eq!(parsed["violations"][0]["lines"], 150);
-    assert_eq!(parsed["violations"][0]["max_lines"], 100);
+    assert_eq!(parsed["violations"][0]["measurements"][0]["actual"], 150);
+    assert_eq!(parsed["violations"][0]["measurements"][0]["max"], 100);
     assert_eq!(parsed["summary"]["violations"], 1);
     assert_eq!(parsed["summary"]["skipped"], 1);
     assert_eq!(parsed["summary"]["walk_errors"], 1);
@@ -15,6 +15,16 @@ pub struct Cli {
     /// Show extra information.
     #[arg(short = 'v', long = "verbose", global = true)]
     pub verbose: bool,
+
+    /// Use this config file instead of discovering one; its paths are
+    /// relative to the current directory.
+    #[arg(
+        long = "config",
+        value_name = "PATH",
+        env = "LOQ_CONFIG",
+        global = true
+    )]
+    pub config: Option<PathBuf>,
 }
 
 /// Available commands.
@@ -30,6 +40,10 @@ pub enum Command {
     Tighten(TightenArgs),
     /// Allow violations by raising their limits.
     Relax(RelaxArgs),
+    /// Show which rule governs a file and why.
+    Explain(ExplainArgs),
+    /// Inspect the loq config.
+    Config(ConfigArgs),
 }
 
 /// Output format for check results.
@@ -44,6 +58,7 @@ pub enum OutputFormat {
 
 /// Arguments for the check command.
 #[derive(Args, Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct CheckArgs {
     /// Paths to check (files or directories).
     #[arg(value_name = "PATH", conflicts_with_all = ["staged", "diff"])]
@@ -68,6 +83,22 @@ pub struct CheckArgs {
     /// Output format.
     #[arg(long = "output-format", value_enum, default_value_t = OutputFormat::Text)]
     pub output_format: OutputFormat,
+
+    /// Override a config setting with a TOML value (repeatable).
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub set: Vec<String>,
+
+    /// Exclude additional glob patterns (repeatable).
+    #[arg(long = "extend-exclude", value_name = "GLOB")]
+    pub extend_exclude: Vec<String>,
+
+    /// Override `default_max_lines`.
+    #[arg(long = "default-max-lines", value_name = "N")]
+    pub default_max_lines: Option<usize>,
+
+    /// Treat limits with `warning` severity as errors.
+    #[arg(long = "warnings-as-errors")]
+    pub warnings_as_errors: bool,
 }
 
 /// Arguments for the init command.
@@ -101,3 +132,40 @@ pub struct RelaxArgs {
     #[arg(long = "extra", visible_alias = "buffer", default_value_t = 0)]
     pub extra: usize,
 }
+
+/// Arguments for the explain command.
+#[derive(Args, Debug, Clone)]
+pub struct ExplainArgs {
+    /// File to explain.
+    #[arg(value_name = "PATH")]
+    pub path: PathBuf,
+
+    /// Output format.
+    #[arg(long = "output-format", value_enum, default_value_t = OutputFormat::Text)]
+    pub output_format: OutputFormat,
+}
+
+/// Arguments for the config command.
+#[derive(Args, Debug, Clone)]
+pub struct ConfigArgs {
+    /// Config subcommand to run.
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+/// Available config subcommands.
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    /// Check the config for errors and for rules and excludes that do nothing.
+    Validate(ValidateArgs),
+    /// Print the JSON Schema for loq config files.
+    Schema,
+}
+
+/// Arguments for the config validate command.
+#[derive(Args, Debug, Clone)]
+pub struct ValidateArgs {
+    /// Output format.
+    #[arg(long = "output-format", value_enum, default_value_t = OutputFormat::Text)]
+    pub output_format: OutputFormat,
+}
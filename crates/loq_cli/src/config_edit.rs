@@ -1,16 +1,31 @@
 //! `loq.toml` editing.
+//!
+//! Edits go to the loq table wherever the config file keeps it, so host
+//! manifests like `pyproject.toml` keep their other content untouched.
 
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use loq_core::config::{LoqConfig, DEFAULT_MAX_LINES, DEFAULT_RESPECT_GITIGNORE};
-use loq_core::Metric;
-use toml_edit::{DocumentMut, Item};
+use loq_core::{ConfigFormat, Metric};
+use toml_edit::{DocumentMut, Item, Table};
 
 use crate::init::add_to_gitignore;
 
 /// Returns the governing config path and its root directory.
-pub(crate) fn config_path_and_root(cwd: &Path) -> Result<(PathBuf, PathBuf)> {
+///
+/// An explicit config (from `--config`) is rooted at `cwd`; a discovered one
+/// is rooted at its own directory.
+pub(crate) fn config_path_and_root(
+    cwd: &Path,
+    explicit: Option<&Path>,
+) -> Result<(PathBuf, PathBuf)> {
+    if let Some(path) = explicit {
+        let root = cwd
+            .canonicalize()
+            .context("failed to resolve config root")?;
+        return Ok((cwd.join(path), root));
+    }
     let path = loq_fs::discover::find_config(cwd).unwrap_or_else(|| cwd.join("loq.toml"));
     let root = path
         .parent()
@@ -46,6 +61,33 @@ pub(crate) fn load_doc_or_default(config_path: &Path) -> Result<(DocumentMut, bo
     }
 }
 
+/// Returns the table holding loq settings inside `doc`.
+pub(crate) fn loq_table_mut<'a>(
+    doc: &'a mut DocumentMut,
+    config_path: &Path,
+) -> Result<&'a mut Table> {
+    let format = ConfigFormat::from_path(config_path);
+    let text = doc.to_string();
+    let keys = format
+        .table_path(&text)
+        .with_context(|| format!("no loq table in {}", config_path.display()))?;
+
+    let mut table = doc.as_table_mut();
+    for key in keys {
+        table = table
+            .get_mut(key)
+            .and_then(Item::as_table_mut)
+            .with_context(|| {
+                format!(
+                    "[{}] in {} must be a standard table to be edited",
+                    keys.join("."),
+                    config_path.display()
+                )
+            })?;
+    }
+    Ok(table)
+}
+
 pub(crate) fn write_doc(config_path: &Path, doc: &DocumentMut) -> Result<()> {
     std::fs::write(config_path, doc.to_string())
         .with_context(|| format!("failed to write {}", config_path.display()))?;
@@ -76,6 +118,44 @@ pub(crate) fn line_threshold(config: &LoqConfig, explicit: Option<usize>) -> usi
 mod tests {
     use super::*;
 
+    #[test]
+    fn explicit_config_is_rooted_at_cwd() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cwd = temp.path().join("repo");
+        std::fs::create_dir_all(&cwd).unwrap();
+        std::fs::write(cwd.join("loq.toml"), "").unwrap();
+
+        let (path, root) = config_path_and_root(&cwd, Some(Path::new("../ci.toml"))).unwrap();
+        assert_eq!(path, cwd.join("../ci.toml"));
+        assert_eq!(root, cwd.canonicalize().unwrap());
+
+        let (path, root) = config_path_and_root(&cwd, None).unwrap();
+        assert_eq!(path, cwd.join("loq.toml"));
+        assert_eq!(root, cwd.canonicalize().unwrap());
+    }
+
+    #[test]
+    fn loq_table_mut_finds_embedded_table() {
+        let mut doc: DocumentMut =
+            "[project]\nname = \"demo\"\n\n[tool.loq]\ndefault_max_lines = 5\n"
+                .parse()
+                .unwrap();
+        let table = loq_table_mut(&mut doc, Path::new("pyproject.toml")).unwrap();
+        assert_eq!(
+            table.get("default_max_lines").and_then(Item::as_integer),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn loq_table_mut_rejects_inline_table() {
+        let mut doc: DocumentMut = "tool = { loq = { default_max_lines = 5 } }\n"
+            .parse()
+            .unwrap();
+        let err = loq_table_mut(&mut doc, Path::new("pyproject.toml")).unwrap_err();
+        assert!(err.to_string().contains("must be a standard table"));
+    }
+
     #[test]
     fn default_document_has_expected_defaults() {
         let doc = default_document();
@@ -0,0 +1,201 @@
+//! Config lints: rules, includes and excludes that no longer do anything.
+//!
+//! Lints run against the files a config governs, so they reflect the tree as
+//! it is now rather than what the config looked like when it was written.
+
+use std::path::Path;
+
+use loq_core::config::CompiledConfig;
+use loq_core::{Limit, PatternList};
+
+use crate::exact_limits::exact_rule_path;
+
+/// One problem found in a config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Lint {
+    /// An include pattern matches no files.
+    UnmatchedInclude { pattern: String },
+    /// An exclude pattern matches no files.
+    UnmatchedExclude { pattern: String },
+    /// A rule's patterns match no files.
+    UnmatchedRule { index: usize, pattern: String },
+    /// Every file a rule matches is governed by a later rule.
+    ShadowedRule {
+        index: usize,
+        pattern: String,
+        /// A single later rule matching all of this rule's files, if any.
+        by: Option<(usize, String)>,
+    },
+    /// An exact-path rule names a file that no longer exists.
+    MissingExactPath { index: usize, pattern: String },
+    /// An exact-path rule allows no more than the default limit.
+    RedundantExactPath {
+        index: usize,
+        pattern: String,
+        limit: Limit,
+        default: Limit,
+    },
+}
+
+impl Lint {
+    /// Returns the lint as used in machine-readable output.
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::UnmatchedInclude { .. } => "unmatched_include",
+            Self::UnmatchedExclude { .. } => "unmatched_exclude",
+            Self::UnmatchedRule { .. } => "unmatched_rule",
+            Self::ShadowedRule { .. } => "shadowed_rule",
+            Self::MissingExactPath { .. } => "missing_exact_path",
+            Self::RedundantExactPath { .. } => "redundant_exact_path",
+        }
+    }
+
+    /// Returns the index of the rule the lint is about, if any.
+    pub const fn rule_index(&self) -> Option<usize> {
+        match self {
+            Self::UnmatchedInclude { .. } | Self::UnmatchedExclude { .. } => None,
+            Self::UnmatchedRule { index, .. }
+            | Self::ShadowedRule { index, .. }
+            | Self::MissingExactPath { index, .. }
+            | Self::RedundantExactPath { index, .. } => Some(*index),
+        }
+    }
+
+    /// Returns the pattern the lint is about.
+    pub fn pattern(&self) -> &str {
+        match self {
+            Self::UnmatchedInclude { pattern }
+            | Self::UnmatchedExclude { pattern }
+            | Self::UnmatchedRule { pattern, .. }
+            | Self::ShadowedRule { pattern, .. }
+            | Self::MissingExactPath { pattern, .. }
+            | Self::RedundantExactPath { pattern, .. } => pattern,
+        }
+    }
+
+    /// Describes the problem, without the rule or pattern it is about.
+    pub fn message(&self) -> String {
+        match self {
+            Self::UnmatchedInclude { .. }
+            | Self::UnmatchedExclude { .. }
+            | Self::UnmatchedRule { .. } => "matches no files".to_string(),
+            Self::ShadowedRule {
+                by: Some((by, pattern)),
+                ..
+            } => {
+                format!("shadowed by rules[{by}] '{pattern}', which matches all of its files")
+            }
+            Self::ShadowedRule { by: None, .. } => {
+                "shadowed: later rules govern all of its files".to_string()
+            }
+            Self::MissingExactPath { .. } => "file no longer exists".to_string(),
+            Self::RedundantExactPath { limit, default, .. } => format!(
+                "allows {} {}, no more than the default of {}",
+                limit.max,
+                limit.metric.label(),
+                default.max
+            ),
+        }
+    }
+}
+
+/// Lints `compiled` against the match keys of files under its root, before
+/// `include` and `exclude` are applied.
+///
+/// Includes and excludes filter every `walked` file, including those governed
+/// by nested configs, while rules only apply to the files the config `governs`.
+pub(crate) fn lint_config(
+    compiled: &CompiledConfig,
+    walked: &[String],
+    governs: &[String],
+) -> Vec<Lint> {
+    let includes = compiled.include_patterns();
+    let excludes = compiled.exclude_patterns();
+    let mut lints = unmatched_patterns(includes, walked)
+        .map(|pattern| Lint::UnmatchedInclude { pattern })
+        .chain(
+            unmatched_patterns(excludes, walked).map(|pattern| Lint::UnmatchedExclude { pattern }),
+        )
+        .collect::<Vec<_>>();
+
+    let checked = governs
+        .iter()
+        .filter(|file| compiled.includes(file) && excludes.matches(file).is_none())
+        .collect::<Vec<_>>();
+    let rules = compiled.rules();
+    // For each rule, the files it matches and how many of them it governs.
+    let mut matched = vec![Vec::new(); rules.len()];
+    let mut governed = vec![0usize; rules.len()];
+    for file in &checked {
+        let mut winner = None;
+        for (index, rule) in rules.iter().enumerate() {
+            if rule.matches(file).is_some() {
+                matched[index].push(file.as_str());
+                winner = Some(index);
+            }
+        }
+        if let Some(winner) = winner {
+            governed[winner] += 1;
+        }
+    }
+
+    for (index, rule) in rules.iter().enumerate() {
+        let pattern = rule.patterns.join(", ");
+        let exact = match rule.patterns.as_slice() {
+            [only] => exact_rule_path(only, compiled.pattern_syntax),
+            _ => None,
+        };
+
+        if let Some(exact) = &exact {
+            if !compiled.root_dir.join(Path::new(exact)).exists() {
+                lints.push(Lint::MissingExactPath { index, pattern });
+                continue;
+            }
+            let overrides_rule = rules[..index]
+                .iter()
+                .any(|earlier| earlier.matches(exact).is_some());
+            if let (Some(default), [limit]) = (compiled.default_limit, rule.limits.as_slice()) {
+                if !overrides_rule && default.metric == limit.metric && limit.max <= default.max {
+                    lints.push(Lint::RedundantExactPath {
+                        index,
+                        pattern,
+                        limit: *limit,
+                        default,
+                    });
+                    continue;
+                }
+            }
+        }
+
+        if matched[index].is_empty() {
+            lints.push(Lint::UnmatchedRule { index, pattern });
+        } else if governed[index] == 0 {
+            let by = (index + 1..rules.len())
+                .find(|later| {
+                    matched[index]
+                        .iter()
+                        .all(|file| rules[*later].matches(file).is_some())
+                })
+                .map(|later| (later, rules[later].patterns.join(", ")));
+            lints.push(Lint::ShadowedRule { index, pattern, by });
+        }
+    }
+    lints
+}
+
+/// Returns the patterns in `list` that match none of `files`.
+fn unmatched_patterns<'a>(
+    list: &'a PatternList,
+    files: &'a [String],
+) -> impl Iterator<Item = String> + 'a {
+    list.patterns()
+        .filter(|pattern| {
+            !files
+                .iter()
+                .any(|file| list.matching(file).any(|matched| matched == *pattern))
+        })
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,216 @@
+use super::*;
+use loq_core::config::{compile_config, LoqConfig, Rule};
+use loq_core::Severity;
+use tempfile::TempDir;
+
+fn rule(path: &str, max_lines: usize) -> Rule {
+    Rule {
+        paths: vec![path.to_string()],
+        limits: vec![Limit::lines(max_lines)],
+        severity: Severity::Error,
+        warn_at: None,
+        fix_guidance: None,
+        exclude: vec![],
+        token_estimate: None,
+        follow_imports: false,
+    }
+}
+
+fn lint(root: &TempDir, exclude: &[&str], rules: Vec<Rule>, files: &[&str]) -> Vec<Lint> {
+    for file in files {
+        let path = root.path().join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "a\n").unwrap();
+    }
+    let config = LoqConfig {
+        exclude: exclude.iter().map(ToString::to_string).collect(),
+        rules,
+        ..LoqConfig::default()
+    };
+    let compiled = compile_config(root.path().to_path_buf(), config, None).unwrap();
+    let files = files.iter().map(ToString::to_string).collect::<Vec<_>>();
+    lint_config(&compiled, &files, &files)
+}
+
+#[test]
+fn clean_config_has_no_lints() {
+    let temp = TempDir::new().unwrap();
+    let lints = lint(
+        &temp,
+        &["gen/**"],
+        vec![rule("**/*.rs", 800), rule("src/big.rs", 900)],
+        &["gen/out.rs", "src/lib.rs", "src/big.rs"],
+    );
+    assert_eq!(lints, vec![]);
+}
+
+#[test]
+fn unmatched_excludes_and_rules_are_flagged() {
+    let temp = TempDir::new().unwrap();
+    let lints = lint(
+        &temp,
+        &["vendor/**"],
+        vec![rule("docs/**", 100)],
+        &["src/lib.rs"],
+    );
+    assert_eq!(
+        lints,
+        vec![
+            Lint::UnmatchedExclude {
+                pattern: "vendor/**".to_string()
+            },
+            Lint::UnmatchedRule {
+                index: 0,
+                pattern: "docs/**".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn exclude_matching_only_files_of_an_earlier_exclude_is_not_flagged() {
+    let temp = TempDir::new().unwrap();
+    let lints = lint(&temp, &["gen/**", "gen/*.rs"], vec![], &["gen/a.rs"]);
+    assert_eq!(lints, vec![]);
+}
+
+#[test]
+fn rules_matching_only_excluded_files_are_unmatched() {
+    let temp = TempDir::new().unwrap();
+    let lints = lint(&temp, &["gen/**"], vec![rule("gen/**", 100)], &["gen/a.rs"]);
+    assert!(matches!(lints[..], [Lint::UnmatchedRule { index: 0, .. }]));
+}
+
+#[test]
+fn shadowed_rule_names_the_covering_rule() {
+    let temp = TempDir::new().unwrap();
+    let lints = lint(
+        &temp,
+        &[],
+        vec![rule("docs/*.md", 100), rule("**/*.md", 200)],
+        &["docs/a.md", "b.md"],
+    );
+    assert_eq!(
+        lints,
+        vec![Lint::ShadowedRule {
+            index: 0,
+            pattern: "docs/*.md".to_string(),
+            by: Some((1, "**/*.md".to_string())),
+        }]
+    );
+}
+
+#[test]
+fn rule_shadowed_by_several_rules_has_no_single_cover() {
+    let temp = TempDir::new().unwrap();
+    let lints = lint(
+        &temp,
+        &[],
+        vec![
+            rule("docs/**", 100),
+            rule("**/*.md", 200),
+            rule("**/*.txt", 200),
+        ],
+        &["docs/a.md", "docs/b.txt"],
+    );
+    assert!(matches!(
+        lints[..],
+        [Lint::ShadowedRule {
+            index: 0,
+            by: None,
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn exact_path_to_deleted_file_is_flagged() {
+    let temp = TempDir::new().unwrap();
+    let lints = lint(&temp, &[], vec![rule("src/gone.rs", 900)], &["src/lib.rs"]);
+    assert_eq!(
+        lints,
+        vec![Lint::MissingExactPath {
+            index: 0,
+            pattern: "src/gone.rs".to_string()
+        }]
+    );
+}
+
+#[test]
+fn exact_path_at_or_below_default_is_flagged() {
+    let temp = TempDir::new().unwrap();
+    let lints = lint(
+        &temp,
+        &[],
+        vec![rule("src/a.rs", 500), rule("src/b.rs", 501)],
+        &["src/a.rs", "src/b.rs"],
+    );
+    assert_eq!(
+        lints,
+        vec![Lint::RedundantExactPath {
+            index: 0,
+            pattern: "src/a.rs".to_string(),
+            limit: Limit::lines(500),
+            default: Limit::lines(500),
+        }]
+    );
+    assert_eq!(
+        lints[0].message(),
+        "allows 500 lines, no more than the default of 500"
+    );
+}
+
+#[test]
+fn exact_path_overriding_an_earlier_rule_is_not_redundant() {
+    let temp = TempDir::new().unwrap();
+    let lints = lint(
+        &temp,
+        &[],
+        vec![rule("**/*.rs", 300), rule("src/a.rs", 400)],
+        &["src/a.rs", "src/b.rs"],
+    );
+    assert_eq!(lints, vec![]);
+}
+
+#[test]
+fn excludes_count_files_governed_by_nested_configs() {
+    let temp = TempDir::new().unwrap();
+    let config = LoqConfig {
+        exclude: vec!["fixtures/**".to_string()],
+        ..LoqConfig::default()
+    };
+    let compiled = compile_config(temp.path().to_path_buf(), config, None).unwrap();
+    let walked = vec!["fixtures/a.rs".to_string(), "src/lib.rs".to_string()];
+    let governs = vec!["src/lib.rs".to_string()];
+    assert_eq!(lint_config(&compiled, &walked, &governs), vec![]);
+}
+
+#[test]
+fn unmatched_include_is_flagged_and_rules_only_see_included_files() {
+    let temp = TempDir::new().unwrap();
+    std::fs::create_dir_all(temp.path().join("src")).unwrap();
+    std::fs::write(temp.path().join("src/lib.rs"), "a\n").unwrap();
+    std::fs::write(temp.path().join("build.rs"), "a\n").unwrap();
+    let config = LoqConfig {
+        include: vec!["src/**".to_string(), "prompts/**".to_string()],
+        rules: vec![rule("build.rs", 800)],
+        ..LoqConfig::default()
+    };
+    let compiled = compile_config(temp.path().to_path_buf(), config, None).unwrap();
+    let files = vec!["src/lib.rs".to_string(), "build.rs".to_string()];
+
+    let lints = lint_config(&compiled, &files, &files);
+
+    assert_eq!(
+        lints,
+        vec![
+            Lint::UnmatchedInclude {
+                pattern: "prompts/**".to_string()
+            },
+            Lint::UnmatchedRule {
+                index: 0,
+                pattern: "build.rs".to_string()
+            },
+        ]
+    );
+}
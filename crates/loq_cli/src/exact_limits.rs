@@ -1,13 +1,17 @@
 //! Managed exact-path limits in `loq.toml`.
 //!
+//! Functions take the loq table itself, which is the document root for
+//! `loq.toml` and an embedded table for host manifests.
+//!
 //! Owns the TOML rule shape for literal file paths, including glob escaping,
 //! rule discovery, and safe updates/removal by rule index.
 
 use std::collections::HashMap;
 use std::path::Path;
 
+use loq_core::PatternSyntax;
 use loq_fs::path_identity::normalize_key;
-use toml_edit::{DocumentMut, Item, Table};
+use toml_edit::{Item, Table};
 
 const GLOB_ESCAPED_LITERALS: [(&str, char); 6] = [
     ("[*]", '*'),
@@ -18,12 +22,18 @@ const GLOB_ESCAPED_LITERALS: [(&str, char); 6] = [
     ("[}]", '}'),
 ];
 
+/// The only keys of a managed line limit. A rule with any other key, such as
+/// another limit or a severity, is left alone.
+const MANAGED_KEYS: [&str; 2] = ["path", "max_lines"];
+
 /// One exact-path limit rule in `loq.toml`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) struct ExactLimit {
     /// Maximum allowed lines.
     pub max_lines: usize,
     rule_index: usize,
+    /// Whether the rule sets nothing else, so loq may tighten or remove it.
+    managed: bool,
 }
 
 /// Exact-path limits collected from `loq.toml`.
@@ -33,24 +43,34 @@ pub(crate) struct ExactLimits {
 }
 
 impl ExactLimits {
-    /// Collect existing exact-path rules.
+    /// Collect existing exact-path rules that set `max_lines`.
+    ///
+    /// Rules with other keys are kept so no duplicate rule is added for their
+    /// path, but only their `max_lines` is ever raised.
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    pub fn collect(doc: &DocumentMut) -> Self {
+    pub fn collect(doc: &Table) -> Self {
         let mut rules = HashMap::new();
+        let syntax = pattern_syntax(doc);
 
         if let Some(rules_array) = doc.get("rules").and_then(Item::as_array_of_tables) {
             for (idx, rule) in rules_array.iter().enumerate() {
                 if let Some(path_value) = rule.get("path") {
                     let paths = extract_paths(path_value);
-                    if paths.len() == 1 && is_exact_path(&paths[0]) {
+                    let exact = match paths.as_slice() {
+                        [only] => exact_rule_path(only, syntax),
+                        _ => None,
+                    };
+                    if let Some(exact) = exact {
                         if let Some(max_lines) = rule.get("max_lines").and_then(Item::as_integer) {
-                            let unescaped = unescape_glob(&paths[0]);
-                            let normalized = normalize_key(&unescaped);
+                            let normalized = normalize_key(&exact);
                             rules.insert(
                                 normalized,
                                 ExactLimit {
                                     max_lines: max_lines as usize,
                                     rule_index: idx,
+                                    managed: rule
+                                        .iter()
+                                        .all(|(key, _)| MANAGED_KEYS.contains(&key)),
                                 },
                             );
                         }
@@ -72,14 +92,15 @@ impl ExactLimits {
         self.rules.get(path).copied()
     }
 
-    /// Iterates over collected exact-path limits.
+    /// Iterates over collected limits that loq manages.
     pub fn iter(&self) -> impl Iterator<Item = (&str, ExactLimit)> {
         self.rules
             .iter()
+            .filter(|(_, limit)| limit.managed)
             .map(|(path, limit)| (path.as_str(), *limit))
     }
 
-    /// Iterates over limits inside a config-relative path scope.
+    /// Iterates over managed limits inside a config-relative path scope.
     pub fn within<'a>(
         &'a self,
         scope: &'a str,
@@ -90,7 +111,7 @@ impl ExactLimits {
 }
 
 /// Adds or updates an exact-path rule.
-pub(crate) fn set_limit(doc: &mut DocumentMut, limits: &ExactLimits, path: &str, max_lines: usize) {
+pub(crate) fn set_limit(doc: &mut Table, limits: &ExactLimits, path: &str, max_lines: usize) {
     if let Some(limit) = limits.get(path) {
         update_limit(doc, limit, max_lines);
     } else {
@@ -100,14 +121,14 @@ pub(crate) fn set_limit(doc: &mut DocumentMut, limits: &ExactLimits, path: &str,
 
 /// Updates `max_lines` for an existing exact-path rule.
 #[allow(clippy::cast_possible_wrap)]
-pub(crate) fn update_limit(doc: &mut DocumentMut, limit: ExactLimit, new_max: usize) {
+pub(crate) fn update_limit(doc: &mut Table, limit: ExactLimit, new_max: usize) {
     if let Some(rule) = rule_mut(doc, limit.rule_index) {
         rule["max_lines"] = toml_edit::value(new_max as i64);
     }
 }
 
 /// Removes exact-path rules, preserving index correctness.
-pub(crate) fn remove_limits(doc: &mut DocumentMut, limits: impl IntoIterator<Item = ExactLimit>) {
+pub(crate) fn remove_limits(doc: &mut Table, limits: impl IntoIterator<Item = ExactLimit>) {
     let mut indices = limits
         .into_iter()
         .map(|limit| limit.rule_index)
@@ -131,6 +152,41 @@ pub(crate) fn extract_paths(value: &Item) -> Vec<String> {
     }
 }
 
+/// Returns the pattern syntax a loq table declares.
+fn pattern_syntax(doc: &Table) -> PatternSyntax {
+    match doc.get("pattern_syntax").and_then(Item::as_str) {
+        Some("gitignore") => PatternSyntax::Gitignore,
+        _ => PatternSyntax::Glob,
+    }
+}
+
+/// Returns the file an exact-path rule pattern names, if it names exactly one.
+///
+/// Under gitignore syntax a name without a `/` matches at any depth, so only
+/// anchored patterns name a single file.
+pub(crate) fn exact_rule_path(pattern: &str, syntax: PatternSyntax) -> Option<String> {
+    let pattern = match syntax {
+        PatternSyntax::Glob => pattern,
+        PatternSyntax::Gitignore => {
+            let body = pattern.strip_prefix('/').unwrap_or(pattern);
+            if body.ends_with('/') || !pattern.contains('/') {
+                return None;
+            }
+            body
+        }
+    };
+    is_exact_path(pattern).then(|| unescape_glob(pattern))
+}
+
+/// Writes `path` as an exact-path rule pattern.
+fn exact_rule_pattern(path: &str, syntax: PatternSyntax) -> String {
+    let escaped = escape_exact_path(path);
+    match syntax {
+        PatternSyntax::Gitignore if !path.contains('/') => format!("/{escaped}"),
+        _ => escaped,
+    }
+}
+
 /// Check if a path is an exact path (no unescaped glob metacharacters).
 ///
 /// Exact paths written by `add_limit` are escaped with `globset::escape`.
@@ -180,7 +236,8 @@ fn escape_exact_path(path: &str) -> String {
 }
 
 #[allow(clippy::cast_possible_wrap)]
-fn add_limit(doc: &mut DocumentMut, path: &str, max_lines: usize) {
+fn add_limit(doc: &mut Table, path: &str, max_lines: usize) {
+    let syntax = pattern_syntax(doc);
     if doc.get("rules").is_none() {
         doc["rules"] = Item::ArrayOfTables(toml_edit::ArrayOfTables::new());
     }
@@ -190,13 +247,13 @@ fn add_limit(doc: &mut DocumentMut, path: &str, max_lines: usize) {
         .and_then(|item| item.as_array_of_tables_mut())
     {
         let mut rule = Table::new();
-        rule["path"] = toml_edit::value(escape_exact_path(path));
+        rule["path"] = toml_edit::value(exact_rule_pattern(path, syntax));
         rule["max_lines"] = toml_edit::value(max_lines as i64);
         rules.push(rule);
     }
 }
 
-fn remove_rule(doc: &mut DocumentMut, idx: usize) {
+fn remove_rule(doc: &mut Table, idx: usize) {
     if let Some(rules) = doc
         .get_mut("rules")
         .and_then(|item| item.as_array_of_tables_mut())
@@ -205,7 +262,7 @@ fn remove_rule(doc: &mut DocumentMut, idx: usize) {
     }
 }
 
-fn rule_mut(doc: &mut DocumentMut, idx: usize) -> Option<&mut Table> {
+fn rule_mut(doc: &mut Table, idx: usize) -> Option<&mut Table> {
     doc.get_mut("rules")
         .and_then(|item| item.as_array_of_tables_mut())
         .and_then(|rules| rules.get_mut(idx))
@@ -214,7 +271,7 @@ fn rule_mut(doc: &mut DocumentMut, idx: usize) -> Option<&mut Table> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use toml_edit::{Array, Formatted, Value};
+    use toml_edit::{Array, DocumentMut, Formatted, Value};
 
     #[test]
     fn is_exact_path_detects_globs() {
@@ -278,13 +335,26 @@ max_lines = 20
 [[rules]]
 path = "**/*.rs"
 max_lines = 30
+
+[[rules]]
+path = "src/d.rs"
+max_lines = 40
+max_tokens = 400
+
+[[rules]]
+path = "src/e.rs"
+max_lines = 50
+severity = "warning"
 "#
         .parse()
         .unwrap();
 
         let limits = ExactLimits::collect(&doc);
-        assert_eq!(limits.rules.len(), 1);
+        assert_eq!(limits.iter().count(), 1);
         assert_eq!(limits.get("src/a.rs").unwrap().max_lines, 10);
+        // Rules with other keys are known but not managed.
+        assert!(limits.contains_path("src/d.rs"));
+        assert!(limits.contains_path("src/e.rs"));
     }
 
     #[test]
@@ -375,4 +445,38 @@ max_lines = 100
             100
         );
     }
+
+    #[test]
+    fn gitignore_syntax_anchors_root_level_exact_paths() {
+        let syntax = PatternSyntax::Gitignore;
+        assert_eq!(
+            exact_rule_path("/build.rs", syntax).as_deref(),
+            Some("build.rs")
+        );
+        assert_eq!(
+            exact_rule_path("src/main.rs", syntax).as_deref(),
+            Some("src/main.rs")
+        );
+        assert_eq!(exact_rule_path("build.rs", syntax), None);
+        assert_eq!(exact_rule_path("/build/", syntax), None);
+        assert_eq!(
+            exact_rule_path("/build.rs", PatternSyntax::Glob),
+            Some("/build.rs".into())
+        );
+    }
+
+    #[test]
+    fn set_writes_anchored_paths_under_gitignore_syntax() {
+        let mut doc: DocumentMut = "pattern_syntax = \"gitignore\"\n".parse().unwrap();
+        let limits = ExactLimits::collect(&doc);
+        set_limit(&mut doc, &limits, "build.rs", 600);
+        set_limit(&mut doc, &limits, "src/main.rs", 700);
+
+        let text = doc.to_string();
+        assert!(text.contains("path = \"/build.rs\""), "{text}");
+        assert!(text.contains("path = \"src/main.rs\""), "{text}");
+        let limits = ExactLimits::collect(&doc);
+        assert_eq!(limits.get("build.rs").unwrap().max_lines, 600);
+        assert_eq!(limits.get("src/main.rs").unwrap().max_lines, 700);
+    }
 }
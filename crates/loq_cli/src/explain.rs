@@ -0,0 +1,48 @@
+//! Explain command implementation.
+
+use std::io::Write;
+use std::path::Path;
+
+use loq_core::ConfigError;
+use loq_fs::{CheckConfig, FsError};
+use termcolor::{Color, WriteColor};
+
+use crate::cli::{ExplainArgs, OutputFormat};
+use crate::output::{
+    print_error, write_block, write_config_errors_json, write_explanation, write_explanation_json,
+};
+use crate::ExitStatus;
+
+pub fn run_explain<W1: WriteColor + Write, W2: WriteColor>(
+    args: &ExplainArgs,
+    config: Option<&Path>,
+    stdout: &mut W1,
+    stderr: &mut W2,
+) -> ExitStatus {
+    let Ok(cwd) = std::env::current_dir().and_then(dunce::canonicalize) else {
+        return print_error(stderr, "failed to get current directory");
+    };
+    let config = config.map_or(CheckConfig::Discover, |path| {
+        CheckConfig::Explicit(cwd.join(path))
+    });
+
+    match loq_fs::explain::explain(&args.path, config, &cwd) {
+        Ok(explanation) => {
+            let _ = match args.output_format {
+                OutputFormat::Json => write_explanation_json(stdout, &explanation, &cwd),
+                OutputFormat::Text => write_explanation(stdout, &explanation, &cwd),
+            };
+            ExitStatus::Success
+        }
+        Err(FsError::Config(ConfigError::Diagnostics {
+            path, diagnostics, ..
+        })) if args.output_format == OutputFormat::Json => {
+            let _ = write_config_errors_json(stdout, &path, &diagnostics);
+            ExitStatus::Error
+        }
+        Err(err) => {
+            let _ = write_block(stderr, Some(Color::Red), &format!("error: {err}"));
+            ExitStatus::Error
+        }
+    }
+}
@@ -13,25 +13,28 @@ use crate::ExitStatus;
 
 pub fn run_init<W1: WriteColor, W2: WriteColor>(
     _args: &InitArgs,
+    config: Option<&Path>,
     stdout: &mut W1,
     stderr: &mut W2,
 ) -> ExitStatus {
     let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-    let path = cwd.join("loq.toml");
+    let name = config.unwrap_or_else(|| Path::new("loq.toml"));
+    let path = cwd.join(name);
+    let name = name.display();
     if path.exists() {
-        return print_error(stderr, "loq.toml already exists");
+        return print_error(stderr, &format!("{name} already exists"));
     }
 
     let content = default_config_text();
 
     if let Err(err) = std::fs::write(&path, content) {
-        return print_error(stderr, &format!("failed to write loq.toml: {err}"));
+        return print_error(stderr, &format!("failed to write {name}: {err}"));
     }
 
     // Add .loq_cache to .gitignore if not already present
     add_to_gitignore(&cwd);
 
-    let _ = writeln!(stdout, "✔ Created loq.toml");
+    let _ = writeln!(stdout, "✔ Created {name}");
     let _ = std::io::Write::flush(stdout);
     ExitStatus::Success
 }
@@ -9,12 +9,15 @@ mod baseline;
 mod check;
 mod cli;
 mod config_edit;
+mod config_lint;
 mod exact_limits;
+mod explain;
 mod init;
 mod line_violations;
 mod output;
 mod relax;
 mod tighten;
+mod validate;
 
 use std::ffi::OsString;
 use std::io::{self, Read, Write};
@@ -25,9 +28,11 @@ use termcolor::{ColorChoice, StandardStream, WriteColor};
 
 use baseline::run_baseline;
 use check::{output_mode, run_check};
+use explain::run_explain;
 use init::run_init;
 use relax::run_relax;
 use tighten::run_tighten;
+use validate::{run_schema, run_validate};
 
 pub use cli::{Cli, Command};
 
@@ -62,6 +67,9 @@ pub fn run_env() -> ExitStatus {
     run_with(args, stdin.lock(), &mut stdout, &mut stderr)
 }
 
+/// Global flags whose value is a separate argument.
+const GLOBAL_VALUE_FLAGS: [&str; 1] = ["--config"];
+
 fn normalize_args<I>(args: I) -> Vec<OsString>
 where
     I: IntoIterator<Item = OsString>,
@@ -76,8 +84,15 @@ where
     let mut subcommand_seen = false;
     let mut in_check = false;
     let mut rewrite_enabled = true;
+    let mut expects_value = false;
 
     for arg in iter {
+        if expects_value {
+            expects_value = false;
+            normalized.push(arg);
+            continue;
+        }
+
         if arg.as_os_str() == "--" {
             rewrite_enabled = false;
             normalized.push(arg);
@@ -85,6 +100,11 @@ where
         }
 
         let arg_str = arg.to_string_lossy();
+        if GLOBAL_VALUE_FLAGS.contains(&arg_str.as_ref()) {
+            expects_value = true;
+            normalized.push(arg);
+            continue;
+        }
         if !subcommand_seen {
             if arg_str == "check" {
                 subcommand_seen = true;
@@ -124,13 +144,23 @@ where
         diff: None,
         no_cache: false,
         output_format: cli::OutputFormat::Text,
+        set: vec![],
+        extend_exclude: vec![],
+        default_max_lines: None,
+        warnings_as_errors: false,
     });
+    let config = cli.config.as_deref();
     match cli.command.as_ref().unwrap_or(&default_check) {
-        Command::Check(args) => run_check(args, &mut stdin, stdout, stderr, mode),
-        Command::Init(args) => run_init(args, stdout, stderr),
-        Command::Baseline(args) => run_baseline(args, stdout, stderr),
-        Command::Tighten(args) => run_tighten(args, stdout, stderr),
-        Command::Relax(args) => run_relax(args, stdout, stderr),
+        Command::Check(args) => run_check(args, config, &mut stdin, stdout, stderr, mode),
+        Command::Init(args) => run_init(args, config, stdout, stderr),
+        Command::Baseline(args) => run_baseline(args, config, stdout, stderr),
+        Command::Tighten(args) => run_tighten(args, config, stdout, stderr),
+        Command::Relax(args) => run_relax(args, config, stdout, stderr),
+        Command::Explain(args) => run_explain(args, config, stdout, stderr),
+        Command::Config(args) => match &args.command {
+            cli::ConfigCommand::Validate(args) => run_validate(args, config, stdout, stderr),
+            cli::ConfigCommand::Schema => run_schema(stdout),
+        },
     }
 }
 
@@ -193,6 +223,31 @@ mod tests {
         assert_eq!(details, Some((true, Vec::new())));
     }
 
+    #[test]
+    fn normalize_args_skips_global_flag_values() {
+        let args = vec!["loq", "--config", "ci.toml", "check", "-"]
+            .into_iter()
+            .map(OsString::from)
+            .collect::<Vec<_>>();
+        let normalized = normalize_args(args);
+        let cli = Cli::parse_from(normalized);
+
+        assert_eq!(cli.config, Some(PathBuf::from("ci.toml")));
+        let details = check_command_details(cli);
+        assert_eq!(details, Some((true, Vec::new())));
+    }
+
+    #[test]
+    fn normalize_args_keeps_dash_config_value() {
+        let args = vec!["loq", "check", "--config", "-"]
+            .into_iter()
+            .map(OsString::from)
+            .collect::<Vec<_>>();
+        let cli = Cli::parse_from(normalize_args(args));
+
+        assert_eq!(cli.config, Some(PathBuf::from("-")));
+    }
+
     #[test]
     fn check_command_details_returns_none_for_non_check_commands() {
         let cli = Cli::parse_from(["loq", "init"]);
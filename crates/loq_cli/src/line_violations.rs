@@ -5,19 +5,22 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 use loq_core::config::{compile_config, LoqConfig};
-use loq_core::{FileOutcome, Limit, Metric, OutcomeKind};
+use loq_core::{ConfigLayer, FileOutcome, Limit, Metric, OutcomeKind};
 use loq_fs::{CheckConfig, CheckOptions};
 
-use crate::exact_limits::is_exact_path;
+use crate::exact_limits::exact_rule_path;
 
 /// Collects line-metric violations keyed by match key.
 pub(crate) fn line_violations(outcomes: &[FileOutcome]) -> HashMap<String, usize> {
     outcomes
         .iter()
         .filter_map(|outcome| match &outcome.kind {
-            OutcomeKind::Violation { actual, limit, .. } if limit.metric == Metric::Lines => {
-                Some((outcome.match_key.clone(), *actual))
-            }
+            OutcomeKind::Violation { measurements, .. } => measurements
+                .iter()
+                .find(|measurement| {
+                    measurement.limit.metric == Metric::Lines && measurement.is_over()
+                })
+                .map(|measurement| (outcome.match_key.clone(), measurement.actual)),
             _ => None,
         })
         .collect()
@@ -35,17 +38,23 @@ pub(crate) fn scan_line_violations(
         .canonicalize()
         .context("failed to resolve config root")?;
     let mut config = config;
-    if !matches!(config.default_limit, Some(limit) if limit.metric == Metric::Tokens) {
+    if !matches!(config.default_limit, Some(limit) if limit.metric != Metric::Lines) {
         config.default_limit = Some(Limit::lines(threshold));
     }
+    let syntax = config.pattern_syntax;
     config.rules.retain(|rule| {
-        rule.limit.metric == Metric::Tokens || rule.paths.iter().any(|path| !is_exact_path(path))
+        !matches!(rule.limits.as_slice(), [limit] if limit.metric == Metric::Lines)
+            || rule
+                .paths
+                .iter()
+                .any(|path| exact_rule_path(path, syntax).is_none())
     });
     let compiled = compile_config(root.clone(), config, Some(config_path))?;
     let options = CheckOptions {
-        config: CheckConfig::Compiled(compiled),
+        config: CheckConfig::Compiled(Box::new(compiled)),
         cwd: root,
         use_cache: false,
+        overrides: ConfigLayer::default(),
     };
     let output = loq_fs::run_check(vec![scan_path.to_path_buf()], options)?;
     Ok(line_violations(&output.outcomes))
@@ -80,7 +89,13 @@ mod tests {
         let config = LoqConfig {
             rules: vec![Rule {
                 paths: vec!["[".into()],
-                limit: Limit::lines(1),
+                limits: vec![Limit::lines(1)],
+                severity: loq_core::Severity::Error,
+                fix_guidance: None,
+                warn_at: None,
+                exclude: vec![],
+                token_estimate: None,
+                follow_imports: false,
             }],
             ..LoqConfig::default()
         };
@@ -104,7 +119,13 @@ mod tests {
         let config = LoqConfig {
             rules: vec![Rule {
                 paths: vec!["*.md".into()],
-                limit: Limit::tokens(4),
+                limits: vec![Limit::tokens(4)],
+                severity: loq_core::Severity::Error,
+                fix_guidance: None,
+                warn_at: None,
+                exclude: vec![],
+                token_estimate: None,
+                follow_imports: false,
             }],
             ..LoqConfig::default()
         };
@@ -0,0 +1,398 @@
+//! Text and JSON output for `loq explain`.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use loq_core::decide::Decision;
+use loq_core::{Limit, MatchBy, Measurement, OutcomeKind, Severity, TokenEstimate};
+use loq_fs::explain::{Exclusion, Explanation};
+use loq_fs::PathIdentity;
+use serde::Serialize;
+use termcolor::{Color, WriteColor};
+
+use super::{dimmed, fg, format_number, formatted_measurement, limit_setting, write_path};
+
+/// Writes a decision trace, one labelled step per line.
+pub fn write_explanation<W: WriteColor>(
+    writer: &mut W,
+    explanation: &Explanation,
+    cwd: &Path,
+) -> io::Result<()> {
+    let config = explanation.config_path.as_deref().map_or_else(
+        || "built-in defaults".to_string(),
+        |path| relative(path, cwd),
+    );
+    write_label(writer, "config")?;
+    writeln!(writer, "{config}")?;
+    write_label(writer, "path")?;
+    write_path(writer, &explanation.identity.display)?;
+    writeln!(writer)?;
+    write_label(writer, "match key")?;
+    writeln!(writer, "{}", explanation.identity.match_key)?;
+
+    write_label(writer, "excluded")?;
+    match &explanation.exclusion {
+        None => writeln!(writer, "no")?,
+        Some(exclusion) => {
+            writer.set_color(&fg(Color::Yellow))?;
+            write!(writer, "yes")?;
+            writer.reset()?;
+            writeln!(writer, ", {}", describe_exclusion(exclusion, cwd))?;
+        }
+    }
+
+    write_label(writer, "rules")?;
+    if explanation.matched_rules.is_empty() {
+        writeln!(writer, "none matched")?;
+    }
+    let winner = explanation.matched_rules.len().saturating_sub(1);
+    for (position, rule) in explanation.matched_rules.iter().enumerate() {
+        if position > 0 {
+            write_label(writer, "")?;
+        }
+        write!(
+            writer,
+            "rules[{}]  {}  (match: {})",
+            rule.index,
+            limit_settings(&rule.limits),
+            rule.pattern
+        )?;
+        if rule.severity == Severity::Warning {
+            write!(writer, "  warning")?;
+        }
+        if position == winner {
+            writer.set_color(&fg(Color::Green))?;
+            write!(writer, "  ← wins (last match)")?;
+            writer.reset()?;
+        }
+        writeln!(writer)?;
+    }
+
+    write_label(writer, "limit")?;
+    match &explanation.decision {
+        Decision::Check { limits, matched_by } => {
+            let source = match matched_by {
+                MatchBy::Rule { pattern } => format!("match: {pattern}"),
+                MatchBy::Default => "default".to_string(),
+            };
+            writeln!(writer, "{} ({source})", limit_settings(limits))?;
+        }
+        Decision::SkipNoLimit => writeln!(writer, "none (no matching rule and no default)")?,
+    }
+
+    write_label(writer, "result")?;
+    match &explanation.outcome {
+        None => writeln!(writer, "not checked (excluded)"),
+        Some(outcome) => write_result(writer, outcome),
+    }
+}
+
+fn limit_settings(limits: &[Limit]) -> String {
+    limits
+        .iter()
+        .map(|limit| limit_setting(*limit))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn write_label<W: WriteColor>(writer: &mut W, label: &str) -> io::Result<()> {
+    writer.set_color(&dimmed())?;
+    write!(writer, "{label:<11}")?;
+    writer.reset()
+}
+
+fn write_result<W: WriteColor>(writer: &mut W, outcome: &OutcomeKind) -> io::Result<()> {
+    let (measurements, severity) = match outcome {
+        OutcomeKind::Violation {
+            measurements,
+            severity,
+            ..
+        } => (measurements, Some(*severity)),
+        OutcomeKind::Pass { measurements, .. } => (measurements, None),
+        OutcomeKind::NoLimit => return writeln!(writer, "not checked (no limit)"),
+        OutcomeKind::Missing => return writeln!(writer, "file not found"),
+        OutcomeKind::Binary => return writeln!(writer, "binary file skipped"),
+        OutcomeKind::Unreadable { error } => return writeln!(writer, "unreadable: {error}"),
+    };
+    for (position, measurement) in measurements.iter().enumerate() {
+        if position > 0 {
+            write_label(writer, "")?;
+        }
+        write_measurement(writer, measurement, severity)?;
+    }
+    Ok(())
+}
+
+/// Writes one measurement; `severity` is set when the file is over a limit.
+fn write_measurement<W: WriteColor>(
+    writer: &mut W,
+    measurement: &Measurement,
+    severity: Option<Severity>,
+) -> io::Result<()> {
+    let Measurement { limit, actual, .. } = *measurement;
+    let (symbol, color, note) = if measurement.is_over() {
+        let over = actual.saturating_sub(limit.max);
+        let note = format!("{} over", formatted_measurement(over, limit));
+        match severity {
+            Some(Severity::Warning) => ("⚠", Color::Yellow, note + ", warning"),
+            _ => ("✖", Color::Red, note),
+        }
+    } else {
+        let left = limit.max.saturating_sub(actual);
+        let note = format!("{} left", formatted_measurement(left, limit));
+        if measurement.near_limit {
+            ("●", Color::Cyan, note + ", near limit")
+        } else {
+            ("✔", Color::Green, note)
+        }
+    };
+    writer.set_color(&fg(color))?;
+    write!(writer, "{symbol} {}", formatted_measurement(actual, limit))?;
+    writer.reset()?;
+    writeln!(
+        writer,
+        " / {} {} ({note})",
+        format_number(limit.max),
+        limit.metric.label()
+    )
+}
+
+fn describe_exclusion(exclusion: &Exclusion, cwd: &Path) -> String {
+    match exclusion {
+        Exclusion::Hardcoded { name } => format!("'{name}' is always skipped"),
+        Exclusion::Pattern {
+            pattern,
+            config_path,
+        } => {
+            let source = config_path.as_deref().map_or_else(
+                || "built-in defaults".to_string(),
+                |path| relative(path, cwd),
+            );
+            format!("exclude pattern '{pattern}' in {source}")
+        }
+        Exclusion::NotIncluded {
+            patterns,
+            config_path,
+        } => {
+            let source = config_path.as_deref().map_or_else(
+                || "built-in defaults".to_string(),
+                |path| relative(path, cwd),
+            );
+            let patterns = patterns
+                .iter()
+                .map(|pattern| format!("'{pattern}'"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("matches no include pattern ({patterns}) in {source}")
+        }
+        Exclusion::Gitignore(found) => {
+            let source = found
+                .source
+                .as_deref()
+                .map_or_else(|| ".gitignore".to_string(), |path| relative(path, cwd));
+            format!(
+                "gitignore pattern '{}' in {source} (directory walks only; naming the file still checks it)",
+                found.pattern
+            )
+        }
+    }
+}
+
+fn relative(path: &Path, cwd: &Path) -> String {
+    PathIdentity::new(path, cwd, cwd).display
+}
+
+#[derive(Debug, Serialize)]
+struct JsonExplanation {
+    version: &'static str,
+    path: String,
+    match_key: String,
+    config: Option<String>,
+    excluded: Option<JsonExclusion>,
+    matched_rules: Vec<JsonRuleMatch>,
+    limits: Vec<JsonLimit>,
+    rule: Option<String>,
+    result: Option<JsonResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonExclusion {
+    reason: &'static str,
+    pattern: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRuleMatch {
+    index: usize,
+    pattern: String,
+    limits: Vec<JsonLimit>,
+    severity: &'static str,
+    winner: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonLimit {
+    metric: &'static str,
+    max: usize,
+    approximate: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimate: Option<TokenEstimate>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonResult {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    measurements: Vec<JsonMeasurement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonMeasurement {
+    metric: &'static str,
+    actual: usize,
+    max: usize,
+    status: &'static str,
+}
+
+/// Writes a decision trace as JSON.
+pub fn write_explanation_json<W: Write>(
+    writer: &mut W,
+    explanation: &Explanation,
+    cwd: &Path,
+) -> io::Result<()> {
+    let winner = explanation.matched_rules.len().saturating_sub(1);
+    let output = JsonExplanation {
+        version: env!("CARGO_PKG_VERSION"),
+        path: explanation.identity.display.clone(),
+        match_key: explanation.identity.match_key.clone(),
+        config: explanation
+            .config_path
+            .as_deref()
+            .map(|path| relative(path, cwd)),
+        excluded: explanation
+            .exclusion
+            .as_ref()
+            .map(|exclusion| json_exclusion(exclusion, cwd)),
+        matched_rules: explanation
+            .matched_rules
+            .iter()
+            .enumerate()
+            .map(|(position, rule)| JsonRuleMatch {
+                index: rule.index,
+                pattern: rule.pattern.clone(),
+                limits: rule.limits.iter().copied().map(json_limit).collect(),
+                severity: rule.severity.as_str(),
+                winner: position == winner,
+            })
+            .collect(),
+        limits: match &explanation.decision {
+            Decision::Check { limits, .. } => limits.iter().copied().map(json_limit).collect(),
+            Decision::SkipNoLimit => Vec::new(),
+        },
+        rule: match &explanation.decision {
+            Decision::Check { matched_by, .. } => Some(match matched_by {
+                MatchBy::Rule { pattern } => pattern.clone(),
+                MatchBy::Default => "default".to_string(),
+            }),
+            Decision::SkipNoLimit => None,
+        },
+        result: explanation.outcome.as_ref().map(json_result),
+    };
+    serde_json::to_writer_pretty(&mut *writer, &output)?;
+    writeln!(writer)
+}
+
+fn json_exclusion(exclusion: &Exclusion, cwd: &Path) -> JsonExclusion {
+    match exclusion {
+        Exclusion::Hardcoded { name } => JsonExclusion {
+            reason: "hardcoded",
+            pattern: (*name).to_string(),
+            source: None,
+        },
+        Exclusion::Pattern {
+            pattern,
+            config_path,
+        } => JsonExclusion {
+            reason: "exclude",
+            pattern: pattern.clone(),
+            source: config_path.as_deref().map(|path| relative(path, cwd)),
+        },
+        Exclusion::NotIncluded {
+            patterns,
+            config_path,
+        } => JsonExclusion {
+            reason: "include",
+            pattern: patterns.join(", "),
+            source: config_path.as_deref().map(|path| relative(path, cwd)),
+        },
+        Exclusion::Gitignore(found) => JsonExclusion {
+            reason: "gitignore",
+            pattern: found.pattern.clone(),
+            source: found.source.as_deref().map(|path| relative(path, cwd)),
+        },
+    }
+}
+
+const fn json_limit(limit: Limit) -> JsonLimit {
+    JsonLimit {
+        metric: limit.metric.as_str(),
+        max: limit.max,
+        approximate: limit.is_approximate(),
+        estimate: limit.token_estimate(),
+    }
+}
+
+fn json_result(outcome: &OutcomeKind) -> JsonResult {
+    let (status, measurements, detail) = match outcome {
+        OutcomeKind::Violation {
+            measurements,
+            severity,
+            ..
+        } => {
+            let status = match severity {
+                Severity::Error => "violation",
+                Severity::Warning => "warning",
+            };
+            (status, json_measurements(measurements, status), None)
+        }
+        OutcomeKind::Pass { measurements, .. } => {
+            let near = measurements
+                .iter()
+                .any(|measurement| measurement.near_limit);
+            let status = if near { "near_limit" } else { "pass" };
+            (status, json_measurements(measurements, status), None)
+        }
+        OutcomeKind::NoLimit => ("no_limit", Vec::new(), None),
+        OutcomeKind::Missing => ("missing", Vec::new(), None),
+        OutcomeKind::Binary => ("binary", Vec::new(), None),
+        OutcomeKind::Unreadable { error } => ("unreadable", Vec::new(), Some(error.clone())),
+    };
+    JsonResult {
+        status,
+        measurements,
+        detail,
+    }
+}
+
+/// Gives each measurement its own status; `over` is the status of an exceeded one.
+fn json_measurements(measurements: &[Measurement], over: &'static str) -> Vec<JsonMeasurement> {
+    measurements
+        .iter()
+        .map(|measurement| JsonMeasurement {
+            metric: measurement.limit.metric.as_str(),
+            actual: measurement.actual,
+            max: measurement.limit.max,
+            status: if measurement.is_over() {
+                over
+            } else if measurement.near_limit {
+                "near_limit"
+            } else {
+                "pass"
+            },
+        })
+        .collect()
+}
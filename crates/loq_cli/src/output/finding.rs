@@ -0,0 +1,384 @@
+//! Text output for check findings.
+
+use std::fmt::Write as _;
+use std::io;
+
+use loq_core::limit::format_bytes;
+use loq_core::report::{Finding, FindingKind, SkipReason};
+use loq_core::{
+    BundleMember, DirectoryMetric, Import, Limit, LongLines, MatchBy, Measurement, Metric,
+};
+use termcolor::{Color, WriteColor};
+
+use super::{
+    bold, dimmed, fg, format_number, formatted_measurement, limit_setting, plural, write_path,
+};
+
+pub fn write_finding<W: WriteColor>(
+    writer: &mut W,
+    finding: &Finding,
+    verbose: bool,
+) -> io::Result<()> {
+    let (symbol, color) = match &finding.kind {
+        FindingKind::Violation { .. }
+        | FindingKind::DirectoryViolation { .. }
+        | FindingKind::BundleViolation { .. } => ("✖", Color::Red),
+        FindingKind::NearLimit { .. } => ("●", Color::Cyan),
+        FindingKind::Warning { .. } | FindingKind::SkipWarning { .. } => ("⚠", Color::Yellow),
+    };
+    let write_symbol = |writer: &mut W| -> io::Result<()> {
+        writer.set_color(&fg(color))?;
+        write!(writer, "{symbol} ")?;
+        writer.reset()
+    };
+
+    // A file gets a line for each budget it exceeds or is near.
+    match &finding.kind {
+        FindingKind::Violation {
+            measurements,
+            matched_by,
+        }
+        | FindingKind::Warning {
+            measurements,
+            matched_by,
+        } => {
+            for measurement in measurements.iter().filter(|m| m.is_over()) {
+                write_symbol(writer)?;
+                write_exceeded(
+                    writer,
+                    color,
+                    &finding.path,
+                    measurement,
+                    matched_by,
+                    verbose,
+                )?;
+            }
+        }
+        FindingKind::NearLimit {
+            measurements,
+            matched_by,
+        } => {
+            for measurement in measurements.iter().filter(|m| m.near_limit) {
+                write_symbol(writer)?;
+                write_near_limit(
+                    writer,
+                    color,
+                    &finding.path,
+                    measurement,
+                    matched_by,
+                    verbose,
+                )?;
+            }
+        }
+        FindingKind::DirectoryViolation {
+            metric,
+            actual,
+            max,
+            pattern,
+        } => {
+            write_symbol(writer)?;
+            write_directory_violation(writer, color, *metric, *actual, *max)?;
+            write_path(writer, &finding.path)?;
+            writeln!(writer, "/")?;
+
+            if verbose {
+                write_directory_rule_line(writer, *metric, *max, pattern)?;
+            }
+        }
+        FindingKind::BundleViolation {
+            limit,
+            actual,
+            members,
+        } => {
+            write_symbol(writer)?;
+            write_measurement(writer, color, *actual, *limit, " > ")?;
+            write_bundle(writer, &finding.path, *limit, members)?;
+        }
+        FindingKind::SkipWarning { reason } => {
+            write_symbol(writer)?;
+            let msg = match reason {
+                SkipReason::Binary => "binary file skipped",
+                SkipReason::Unreadable(e) => {
+                    write_path(writer, &finding.path)?;
+                    return writeln!(writer, "  unreadable: {e}");
+                }
+                SkipReason::Missing => "file not found",
+            };
+            write_path(writer, &finding.path)?;
+            writeln!(writer, "  {msg}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a budget a file exceeds, after the finding's symbol.
+fn write_exceeded<W: WriteColor>(
+    writer: &mut W,
+    color: Color,
+    path: &str,
+    measurement: &Measurement,
+    matched_by: &MatchBy,
+    verbose: bool,
+) -> io::Result<()> {
+    let limit = measurement.limit;
+    write_measurement(writer, color, measurement.actual, limit, " > ")?;
+    write_path(writer, path)?;
+    write_long_lines(writer, &measurement.long_lines)?;
+    writeln!(writer)?;
+    write_import_tree(writer, limit, &measurement.imports)?;
+
+    if verbose {
+        write_rule_line(writer, limit, matched_by)?;
+    }
+    if let Some(guidance) = &measurement.fix_guidance {
+        write_finding_guidance(writer, guidance)?;
+    }
+    Ok(())
+}
+
+/// Writes a budget a passing file is close to, after the finding's symbol.
+fn write_near_limit<W: WriteColor>(
+    writer: &mut W,
+    color: Color,
+    path: &str,
+    measurement: &Measurement,
+    matched_by: &MatchBy,
+    verbose: bool,
+) -> io::Result<()> {
+    let limit = measurement.limit;
+    write_measurement(writer, color, measurement.actual, limit, " / ")?;
+    write_path(writer, path)?;
+    let headroom = measurement.headroom();
+    writer.set_color(&dimmed())?;
+    if limit.metric == Metric::Bytes {
+        write!(writer, "  {} left", formatted_measurement(headroom, limit))?;
+    } else {
+        write!(
+            writer,
+            "  {} {}{} left",
+            formatted_measurement(headroom, limit),
+            limit.unit(),
+            plural(headroom)
+        )?;
+    }
+    writer.reset()?;
+    writeln!(writer)?;
+
+    if verbose {
+        write_rule_line(writer, limit, matched_by)?;
+    }
+    Ok(())
+}
+
+/// Writes the `actual > limit ` columns that precede a finding's path.
+fn write_measurement<W: WriteColor>(
+    writer: &mut W,
+    color: Color,
+    actual: usize,
+    limit: Limit,
+    separator: &str,
+) -> io::Result<()> {
+    let (actual_str, limit_str) = match limit.metric {
+        // Sizes that round to the same unit would read "1 KiB > 1 KiB".
+        Metric::Bytes if format_bytes(actual) == format_bytes(limit.max) => (
+            format!("{} B", format_number(actual)),
+            format!("{} B", format_number(limit.max)),
+        ),
+        Metric::Bytes => (
+            formatted_measurement(actual, limit),
+            format_bytes(limit.max),
+        ),
+        _ => (
+            formatted_measurement(actual, limit),
+            format_number(limit.max),
+        ),
+    };
+    writer.set_color(&fg(color).set_bold(true).clone())?;
+    write!(writer, "{actual_str:>6}")?;
+    writer.reset()?;
+    if matches!(
+        limit.metric,
+        Metric::CodeLines | Metric::SourceLines | Metric::Tokens | Metric::LineLength
+    ) {
+        writer.set_color(&dimmed())?;
+        write!(writer, " {}", limit.metric.label())?;
+        writer.reset()?;
+    }
+    writer.set_color(&dimmed())?;
+    write!(writer, "{separator}")?;
+    writer.reset()?;
+    writer.set_color(&fg(Color::Green))?;
+    write!(writer, "{limit_str:<6}")?;
+    writer.reset()?;
+    write!(writer, " ")
+}
+
+/// Writes the `actual metric > max ` columns of a directory violation.
+fn write_directory_violation<W: WriteColor>(
+    writer: &mut W,
+    color: Color,
+    metric: DirectoryMetric,
+    actual: usize,
+    max: usize,
+) -> io::Result<()> {
+    writer.set_color(&fg(color).set_bold(true).clone())?;
+    write!(writer, "{:>6}", format_number(actual))?;
+    writer.reset()?;
+    writer.set_color(&dimmed())?;
+    write!(writer, " {} > ", metric.label())?;
+    writer.reset()?;
+    writer.set_color(&fg(Color::Green))?;
+    write!(writer, "{:<6}", format_number(max))?;
+    writer.reset()?;
+    write!(writer, " ")
+}
+
+/// Writes the verbose line naming the directory rule that was exceeded.
+fn write_directory_rule_line<W: WriteColor>(
+    writer: &mut W,
+    metric: DirectoryMetric,
+    max: usize,
+    pattern: &str,
+) -> io::Result<()> {
+    let key = match metric {
+        DirectoryMetric::Files => "max-files",
+        DirectoryMetric::TotalLines => "max-total-lines",
+    };
+    writer.set_color(&dimmed())?;
+    writeln!(
+        writer,
+        "                  └─ directory rule: {key}={max} (match: {pattern})"
+    )?;
+    writer.reset()
+}
+
+/// Writes the numbers of the first lines over a line length limit, e.g.
+/// `  lines 3, 7` or `  lines 1, 2, 3, 4, 5 and 9 more`.
+fn write_long_lines<W: WriteColor>(writer: &mut W, long_lines: &LongLines) -> io::Result<()> {
+    if long_lines.is_empty() {
+        return Ok(());
+    }
+    let mut numbers = long_lines
+        .first
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if long_lines.hidden() > 0 {
+        let _ = write!(numbers, " and {} more", long_lines.hidden());
+    }
+    writer.set_color(&dimmed())?;
+    write!(writer, "  line{} {numbers}", plural(long_lines.count))?;
+    writer.reset()
+}
+
+/// Writes a bundle's name, then each member beneath it with its share of
+/// the tokens.
+fn write_bundle<W: WriteColor>(
+    writer: &mut W,
+    name: &str,
+    limit: Limit,
+    members: &[BundleMember],
+) -> io::Result<()> {
+    writer.set_color(&bold())?;
+    write!(writer, "{name}")?;
+    writer.reset()?;
+    writeln!(writer)?;
+    for (index, member) in members.iter().enumerate() {
+        let branch = if index + 1 == members.len() {
+            "└─"
+        } else {
+            "├─"
+        };
+        writer.set_color(&dimmed())?;
+        write!(
+            writer,
+            "                  {branch} {:>6} ",
+            formatted_measurement(member.tokens, limit)
+        )?;
+        writer.reset()?;
+        write_path(writer, &member.path)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Writes a file's import tree with each file's own value, e.g.
+/// `   120 ├─ docs/style.md`.
+fn write_import_tree<W: WriteColor>(
+    writer: &mut W,
+    limit: Limit,
+    imports: &[Import],
+) -> io::Result<()> {
+    for (index, import) in imports.iter().enumerate() {
+        writer.set_color(&dimmed())?;
+        write!(
+            writer,
+            "                  {:>6} {}",
+            formatted_measurement(import.actual, limit),
+            tree_prefix(imports, index)
+        )?;
+        writer.reset()?;
+        write_path(writer, &import.path)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Returns the branches drawn before `imports[index]`, from the depths of the
+/// files listed after it.
+fn tree_prefix(imports: &[Import], index: usize) -> String {
+    let depth = imports[index].depth;
+    // Whether a later sibling at `level` follows before the branch ends.
+    let continues = |level: usize| {
+        imports[index + 1..]
+            .iter()
+            .find(|import| import.depth <= level)
+            .is_some_and(|import| import.depth == level)
+    };
+    let mut prefix = String::new();
+    for level in 1..depth {
+        prefix.push_str(if continues(level) { "│  " } else { "   " });
+    }
+    if depth > 0 {
+        prefix.push_str(if continues(depth) {
+            "├─ "
+        } else {
+            "└─ "
+        });
+    }
+    prefix
+}
+
+/// Writes the verbose line naming the limit and how it was matched.
+fn write_rule_line<W: WriteColor>(
+    writer: &mut W,
+    limit: Limit,
+    matched_by: &MatchBy,
+) -> io::Result<()> {
+    writer.set_color(&dimmed())?;
+    let rule_str = match matched_by {
+        MatchBy::Rule { pattern } => {
+            format!("{} (match: {pattern})", limit_setting(limit))
+        }
+        MatchBy::Default => format!("{} (default)", limit_setting(limit)),
+    };
+    writeln!(writer, "                  └─ rule: {rule_str}")?;
+    writer.reset()
+}
+
+/// Writes a finding's rendered guidance beneath it, indented under the path.
+fn write_finding_guidance<W: WriteColor>(writer: &mut W, guidance: &str) -> io::Result<()> {
+    for (index, line) in guidance.trim_end().lines().enumerate() {
+        if index == 0 {
+            writer.set_color(&dimmed())?;
+            write!(writer, "                  └─ ")?;
+            writer.reset()?;
+        } else {
+            write!(writer, "                     ")?;
+        }
+        writeln!(writer, "{line}")?;
+    }
+    Ok(())
+}
@@ -1,16 +1,22 @@
 //! JSON output format for check results.
 
 use std::io::{self, Write};
+use std::path::Path;
 
 use loq_core::report::{FindingKind, Report, SkipReason};
-use loq_core::{Limit, MatchBy, Metric};
+use loq_core::{BundleMember, Import, Measurement};
+use loq_core::{Diagnostic, DiagnosticKind, Limit, MatchBy, TokenEstimate};
 use loq_fs::walk::WalkError;
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 struct JsonOutput {
     version: &'static str,
-    violations: Vec<JsonViolation>,
+    violations: Vec<JsonFile>,
+    warnings: Vec<JsonFile>,
+    near_limit: Vec<JsonFile>,
+    directory_violations: Vec<JsonDirectoryViolation>,
+    bundle_violations: Vec<JsonBundleViolation>,
     skip_warnings: Vec<JsonSkipWarning>,
     walk_errors: Vec<String>,
     summary: JsonSummary,
@@ -18,20 +24,67 @@ struct JsonOutput {
     fix_guidance: Option<String>,
 }
 
+/// A file with its result for every budget it was measured against.
 #[derive(Debug, Serialize)]
-struct JsonViolation {
+struct JsonFile {
     path: String,
+    rule: String,
+    measurements: Vec<JsonMeasurement>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonMeasurement {
     metric: &'static str,
+    actual: usize,
+    max: usize,
+    passed: bool,
     approximate: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    lines: Option<usize>,
+    estimate: Option<TokenEstimate>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    max_lines: Option<usize>,
+    headroom: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    long_lines: Vec<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    tokens: Option<usize>,
+    long_line_count: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    imports: Vec<JsonImport>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    max_tokens: Option<usize>,
+    fix_guidance: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonDirectoryViolation {
+    path: String,
     rule: String,
+    metric: &'static str,
+    actual: usize,
+    max: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonBundleViolation {
+    name: String,
+    metric: &'static str,
+    actual: usize,
+    max: usize,
+    approximate: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimate: Option<TokenEstimate>,
+    members: Vec<JsonBundleMember>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonBundleMember {
+    path: String,
+    tokens: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonImport {
+    path: String,
+    depth: usize,
+    value: usize,
 }
 
 #[derive(Debug, Serialize)]
@@ -40,6 +93,8 @@ struct JsonSummary {
     skipped: usize,
     passed: usize,
     violations: usize,
+    warnings: usize,
+    near_limit: usize,
     walk_errors: usize,
 }
 
@@ -51,60 +106,95 @@ struct JsonSkipWarning {
     detail: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct JsonConfigErrors {
+    version: &'static str,
+    config_errors: Vec<JsonDiagnostic>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonDiagnostic {
+    path: String,
+    kind: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestion: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+}
+
 pub fn write_json<W: Write>(
     writer: &mut W,
     report: &Report,
     walk_errors: &[WalkError],
 ) -> io::Result<()> {
-    let summary = JsonSummary {
-        files_checked: report.summary.total,
-        skipped: report.summary.skipped,
-        passed: report.summary.passed,
-        violations: report.summary.errors,
-        walk_errors: walk_errors.len(),
-    };
+    let summary = json_summary(report, walk_errors.len());
     let mut violations = Vec::new();
+    let mut warnings = Vec::new();
+    let mut near_limit = Vec::new();
+    let mut directory_violations = Vec::new();
+    let mut bundle_violations = Vec::new();
     let mut skip_warnings = Vec::new();
 
     for finding in &report.findings {
         match &finding.kind {
             FindingKind::Violation {
+                measurements,
+                matched_by,
+            } => violations.push(json_file(&finding.path, measurements, matched_by)),
+            FindingKind::Warning {
+                measurements,
+                matched_by,
+            } => warnings.push(json_file(&finding.path, measurements, matched_by)),
+            FindingKind::NearLimit {
+                measurements,
+                matched_by,
+            } => near_limit.push(json_file(&finding.path, measurements, matched_by)),
+            FindingKind::DirectoryViolation {
+                metric,
+                actual,
+                max,
+                pattern,
+            } => directory_violations.push(JsonDirectoryViolation {
+                path: finding.path.clone(),
+                rule: pattern.clone(),
+                metric: metric.as_str(),
+                actual: *actual,
+                max: *max,
+            }),
+            FindingKind::BundleViolation {
                 limit,
                 actual,
-                matched_by,
-                ..
-            } => {
-                let rule = match matched_by {
-                    MatchBy::Rule { pattern } => pattern.clone(),
-                    MatchBy::Default => "default".to_string(),
-                };
-                violations.push(JsonViolation {
-                    path: finding.path.clone(),
-                    metric: limit.metric.as_str(),
-                    approximate: limit.is_approximate(),
-                    lines: metric_value(*actual, *limit, Metric::Lines),
-                    max_lines: metric_value(limit.max, *limit, Metric::Lines),
-                    tokens: metric_value(*actual, *limit, Metric::Tokens),
-                    max_tokens: metric_value(limit.max, *limit, Metric::Tokens),
-                    rule,
-                });
-            }
+                members,
+            } => bundle_violations.push(json_bundle_violation(
+                &finding.path,
+                *limit,
+                *actual,
+                members,
+            )),
             FindingKind::SkipWarning { reason } => {
-                let (reason, detail) = match reason {
-                    SkipReason::Missing => ("missing", None),
-                    SkipReason::Binary => ("binary", None),
-                    SkipReason::Unreadable(error) => ("unreadable", Some(error.clone())),
-                };
-                skip_warnings.push(JsonSkipWarning {
-                    path: finding.path.clone(),
-                    reason,
-                    detail,
-                });
+                skip_warnings.push(json_skip_warning(&finding.path, reason));
             }
         }
     }
 
     violations.sort_by(|a, b| a.path.cmp(&b.path));
+    warnings.sort_by(|a, b| a.path.cmp(&b.path));
+    near_limit.sort_by(|a, b| a.path.cmp(&b.path));
+    directory_violations.sort_by(|a, b| a.path.cmp(&b.path));
+    bundle_violations.sort_by(|a, b| a.name.cmp(&b.name));
     skip_warnings.sort_by(|a, b| a.path.cmp(&b.path));
 
     let mut walk_errors: Vec<String> = walk_errors
@@ -116,6 +206,10 @@ pub fn write_json<W: Write>(
     let output = JsonOutput {
         version: env!("CARGO_PKG_VERSION"),
         violations,
+        warnings,
+        near_limit,
+        directory_violations,
+        bundle_violations,
         skip_warnings,
         walk_errors,
         summary,
@@ -126,268 +220,130 @@ pub fn write_json<W: Write>(
     writeln!(writer)
 }
 
-fn metric_value(value: usize, limit: Limit, metric: Metric) -> Option<usize> {
-    (limit.metric == metric).then_some(value)
+/// Writes config validation problems for editors and other tools.
+pub fn write_config_errors_json<W: Write>(
+    writer: &mut W,
+    path: &Path,
+    diagnostics: &[Diagnostic],
+) -> io::Result<()> {
+    let config_errors = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let (key, suggestion, pattern) = match &diagnostic.kind {
+                DiagnosticKind::UnknownKey { key, suggestion } => {
+                    (Some(key.clone()), suggestion.clone(), None)
+                }
+                DiagnosticKind::InvalidGlob { pattern } => (None, None, Some(pattern.clone())),
+                DiagnosticKind::Syntax | DiagnosticKind::InvalidValue => (None, None, None),
+            };
+            JsonDiagnostic {
+                path: path.display().to_string(),
+                kind: diagnostic.kind.as_str(),
+                message: diagnostic.message.clone(),
+                help: diagnostic.help.clone(),
+                line: diagnostic.line_col.map(|(line, _)| line),
+                column: diagnostic.line_col.map(|(_, column)| column),
+                start: diagnostic.span.as_ref().map(|span| span.start),
+                end: diagnostic.span.as_ref().map(|span| span.end),
+                key,
+                suggestion,
+                pattern,
+            }
+        })
+        .collect();
+    let output = JsonConfigErrors {
+        version: env!("CARGO_PKG_VERSION"),
+        config_errors,
+    };
+    serde_json::to_writer_pretty(&mut *writer, &output)?;
+    writeln!(writer)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use loq_core::report::{build_report, FileOutcome, OutcomeKind};
-    use loq_fs::walk;
-
-    fn json_output_string(
-        outcomes: Vec<FileOutcome>,
-        walk_errors: Vec<walk::WalkError>,
-        fix_guidance: Option<String>,
-    ) -> String {
-        let report = build_report(&outcomes, fix_guidance);
-        let mut buf = Vec::new();
-        write_json(&mut buf, &report, &walk_errors).unwrap();
-        String::from_utf8(buf).unwrap()
-    }
-
-    #[test]
-    fn all_outcomes_counted() {
-        let outcomes = vec![
-            FileOutcome {
-                display_path: "a.rs".into(),
-                match_key: "a.rs".into(),
-                kind: OutcomeKind::NoLimit,
-            },
-            FileOutcome {
-                display_path: "b.rs".into(),
-                match_key: "b.rs".into(),
-                kind: OutcomeKind::Pass {
-                    limit: loq_core::Limit::lines(100),
-                    actual: 50,
-                    matched_by: MatchBy::Default,
-                },
-            },
-            FileOutcome {
-                display_path: "c.rs".into(),
-                match_key: "c.rs".into(),
-                kind: OutcomeKind::Violation {
-                    limit: loq_core::Limit::lines(100),
-                    actual: 150,
-                    matched_by: MatchBy::Default,
-                },
-            },
-        ];
-
-        let json = json_output_string(outcomes, vec![], None);
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["summary"]["files_checked"], 3);
-        assert_eq!(parsed["summary"]["skipped"], 1);
-        assert_eq!(parsed["summary"]["passed"], 1);
-        assert_eq!(parsed["summary"]["violations"], 1);
-    }
-
-    #[test]
-    fn missing_file_warning() {
-        let outcomes = vec![FileOutcome {
-            display_path: "missing.rs".into(),
-            match_key: "missing.rs".into(),
-            kind: OutcomeKind::Missing,
-        }];
-
-        let json = json_output_string(outcomes, vec![], None);
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["summary"]["skipped"], 1);
-        assert_eq!(parsed["skip_warnings"][0]["path"], "missing.rs");
-        assert_eq!(parsed["skip_warnings"][0]["reason"], "missing");
-        assert!(parsed["skip_warnings"][0]["detail"].is_null());
-    }
-
-    #[test]
-    fn unreadable_file_warning() {
-        let outcomes = vec![FileOutcome {
-            display_path: "locked.rs".into(),
-            match_key: "locked.rs".into(),
-            kind: OutcomeKind::Unreadable {
-                error: "permission denied".into(),
-            },
-        }];
-
-        let json = json_output_string(outcomes, vec![], None);
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["summary"]["skipped"], 1);
-        assert_eq!(parsed["skip_warnings"][0]["path"], "locked.rs");
-        assert_eq!(parsed["skip_warnings"][0]["reason"], "unreadable");
-        assert_eq!(parsed["skip_warnings"][0]["detail"], "permission denied");
-    }
-
-    #[test]
-    fn binary_file_warning() {
-        let outcomes = vec![FileOutcome {
-            display_path: "image.png".into(),
-            match_key: "image.png".into(),
-            kind: OutcomeKind::Binary,
-        }];
-
-        let json = json_output_string(outcomes, vec![], None);
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["summary"]["skipped"], 1);
-        assert_eq!(parsed["skip_warnings"][0]["path"], "image.png");
-        assert_eq!(parsed["skip_warnings"][0]["reason"], "binary");
-        assert!(parsed["skip_warnings"][0]["detail"].is_null());
-    }
-
-    #[test]
-    fn violation_with_rule_match() {
-        let outcomes = vec![FileOutcome {
-            display_path: "big.rs".into(),
-            match_key: "big.rs".into(),
-            kind: OutcomeKind::Violation {
-                limit: loq_core::Limit::lines(100),
-                actual: 200,
-                matched_by: MatchBy::Rule {
-                    pattern: "**/*.rs".into(),
-                },
-            },
-        }];
-
-        let json = json_output_string(outcomes, vec![], None);
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["violations"][0]["path"], "big.rs");
-        assert_eq!(parsed["violations"][0]["lines"], 200);
-        assert_eq!(parsed["violations"][0]["max_lines"], 100);
-        assert_eq!(parsed["violations"][0]["rule"], "**/*.rs");
-    }
-
-    #[test]
-    fn token_violation_uses_token_fields() {
-        let outcomes = vec![FileOutcome {
-            display_path: "prompt.md".into(),
-            match_key: "prompt.md".into(),
-            kind: OutcomeKind::Violation {
-                limit: loq_core::Limit::tokens(4),
-                actual: 5,
-                matched_by: MatchBy::Rule {
-                    pattern: "prompts/**/*.md".into(),
-                },
-            },
-        }];
-
-        let json = json_output_string(outcomes, vec![], None);
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["violations"][0]["metric"], "tokens");
-        assert_eq!(parsed["violations"][0]["approximate"], true);
-        assert_eq!(parsed["violations"][0]["tokens"], 5);
-        assert_eq!(parsed["violations"][0]["max_tokens"], 4);
-        assert!(parsed["violations"][0]["lines"].is_null());
-        assert!(parsed["violations"][0]["max_lines"].is_null());
+fn json_file(path: &str, measurements: &[Measurement], matched_by: &MatchBy) -> JsonFile {
+    let rule = match matched_by {
+        MatchBy::Rule { pattern } => pattern.clone(),
+        MatchBy::Default => "default".to_string(),
+    };
+    JsonFile {
+        path: path.to_string(),
+        rule,
+        measurements: measurements.iter().map(json_measurement).collect(),
     }
+}
 
-    #[test]
-    fn violation_with_default_match() {
-        let outcomes = vec![FileOutcome {
-            display_path: "big.rs".into(),
-            match_key: "big.rs".into(),
-            kind: OutcomeKind::Violation {
-                limit: loq_core::Limit::lines(100),
-                actual: 200,
-                matched_by: MatchBy::Default,
-            },
-        }];
-
-        let json = json_output_string(outcomes, vec![], None);
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["violations"][0]["rule"], "default");
+fn json_measurement(measurement: &Measurement) -> JsonMeasurement {
+    let limit = measurement.limit;
+    let long_lines = &measurement.long_lines;
+    JsonMeasurement {
+        metric: limit.metric.as_str(),
+        actual: measurement.actual,
+        max: limit.max,
+        passed: !measurement.is_over(),
+        approximate: limit.is_approximate(),
+        estimate: limit.token_estimate(),
+        headroom: measurement.near_limit.then(|| measurement.headroom()),
+        long_lines: long_lines.first.clone(),
+        long_line_count: (!long_lines.is_empty()).then_some(long_lines.count),
+        imports: measurement.imports.iter().map(json_import).collect(),
+        fix_guidance: measurement.fix_guidance.clone(),
     }
+}
 
-    #[test]
-    fn walk_errors_included() {
-        let json = json_output_string(
-            vec![],
-            vec![
-                walk::WalkError {
-                    message: "path/to/error1".into(),
-                },
-                walk::WalkError {
-                    message: "path/to/error2".into(),
-                },
-            ],
-            None,
-        );
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["summary"]["walk_errors"], 2);
-        let errors = parsed["walk_errors"].as_array().unwrap();
-        assert!(errors.contains(&serde_json::json!("path/to/error1")));
-        assert!(errors.contains(&serde_json::json!("path/to/error2")));
+fn json_bundle_violation(
+    name: &str,
+    limit: Limit,
+    actual: usize,
+    members: &[BundleMember],
+) -> JsonBundleViolation {
+    JsonBundleViolation {
+        name: name.to_string(),
+        metric: limit.metric.as_str(),
+        actual,
+        max: limit.max,
+        approximate: limit.is_approximate(),
+        estimate: limit.token_estimate(),
+        members: members
+            .iter()
+            .map(|member| JsonBundleMember {
+                path: member.path.clone(),
+                tokens: member.tokens,
+            })
+            .collect(),
     }
+}
 
-    #[test]
-    fn fix_guidance_included_with_violations() {
-        let outcomes = vec![FileOutcome {
-            display_path: "big.rs".into(),
-            match_key: "big.rs".into(),
-            kind: OutcomeKind::Violation {
-                limit: loq_core::Limit::lines(100),
-                actual: 200,
-                matched_by: MatchBy::Default,
-            },
-        }];
-
-        let json = json_output_string(outcomes, vec![], Some("Split large files.".into()));
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["fix_guidance"], "Split large files.");
+const fn json_summary(report: &Report, walk_errors: usize) -> JsonSummary {
+    JsonSummary {
+        files_checked: report.summary.total,
+        skipped: report.summary.skipped,
+        passed: report.summary.passed,
+        violations: report.summary.errors,
+        warnings: report.summary.warnings,
+        near_limit: report.summary.near_limit,
+        walk_errors,
     }
+}
 
-    #[test]
-    fn fix_guidance_excluded_without_violations() {
-        let outcomes = vec![FileOutcome {
-            display_path: "small.rs".into(),
-            match_key: "small.rs".into(),
-            kind: OutcomeKind::Pass {
-                limit: loq_core::Limit::lines(100),
-                actual: 50,
-                matched_by: MatchBy::Default,
-            },
-        }];
-
-        let json = json_output_string(outcomes, vec![], Some("Split large files.".into()));
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert!(parsed["fix_guidance"].is_null());
+fn json_import(import: &Import) -> JsonImport {
+    JsonImport {
+        path: import.path.clone(),
+        depth: import.depth,
+        value: import.actual,
     }
+}
 
-    #[test]
-    fn violations_sorted_by_path() {
-        let outcomes = vec![
-            FileOutcome {
-                display_path: "z.rs".into(),
-                match_key: "z.rs".into(),
-                kind: OutcomeKind::Violation {
-                    limit: loq_core::Limit::lines(100),
-                    actual: 200,
-                    matched_by: MatchBy::Default,
-                },
-            },
-            FileOutcome {
-                display_path: "a.rs".into(),
-                match_key: "a.rs".into(),
-                kind: OutcomeKind::Violation {
-                    limit: loq_core::Limit::lines(100),
-                    actual: 200,
-                    matched_by: MatchBy::Default,
-                },
-            },
-        ];
-
-        let json = json_output_string(outcomes, vec![], None);
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(parsed["violations"][0]["path"], "a.rs");
-        assert_eq!(parsed["violations"][1]["path"], "z.rs");
+fn json_skip_warning(path: &str, reason: &SkipReason) -> JsonSkipWarning {
+    let (reason, detail) = match reason {
+        SkipReason::Missing => ("missing", None),
+        SkipReason::Binary => ("binary", None),
+        SkipReason::Unreadable(error) => ("unreadable", Some(error.clone())),
+    };
+    JsonSkipWarning {
+        path: path.to_string(),
+        reason,
+        detail,
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,419 @@
+use super::*;
+use loq_core::report::{build_report, FileOutcome, Measurement, OutcomeKind};
+use loq_fs::walk;
+
+fn json_output_string(
+    outcomes: Vec<FileOutcome>,
+    walk_errors: Vec<walk::WalkError>,
+    fix_guidance: Option<String>,
+) -> String {
+    let report = build_report(&outcomes, &[], &[], fix_guidance);
+    let mut buf = Vec::new();
+    write_json(&mut buf, &report, &walk_errors).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn all_outcomes_counted() {
+    let outcomes = vec![
+        FileOutcome {
+            display_path: "a.rs".into(),
+            match_key: "a.rs".into(),
+            config_path: None,
+            kind: OutcomeKind::NoLimit,
+            lines: None,
+            bundle_tokens: None,
+        },
+        FileOutcome {
+            display_path: "b.rs".into(),
+            match_key: "b.rs".into(),
+            config_path: None,
+            kind: OutcomeKind::Pass {
+                measurements: vec![Measurement::new(loq_core::Limit::lines(100), 50)],
+                matched_by: MatchBy::Default,
+            },
+            lines: None,
+            bundle_tokens: None,
+        },
+        FileOutcome {
+            display_path: "c.rs".into(),
+            match_key: "c.rs".into(),
+            config_path: None,
+            kind: OutcomeKind::Violation {
+                measurements: vec![Measurement::new(loq_core::Limit::lines(100), 150)],
+                matched_by: MatchBy::Default,
+                severity: loq_core::Severity::Error,
+            },
+            lines: None,
+            bundle_tokens: None,
+        },
+    ];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["summary"]["files_checked"], 3);
+    assert_eq!(parsed["summary"]["skipped"], 1);
+    assert_eq!(parsed["summary"]["passed"], 1);
+    assert_eq!(parsed["summary"]["violations"], 1);
+}
+
+#[test]
+fn missing_file_warning() {
+    let outcomes = vec![FileOutcome {
+        display_path: "missing.rs".into(),
+        match_key: "missing.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Missing,
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["summary"]["skipped"], 1);
+    assert_eq!(parsed["skip_warnings"][0]["path"], "missing.rs");
+    assert_eq!(parsed["skip_warnings"][0]["reason"], "missing");
+    assert!(parsed["skip_warnings"][0]["detail"].is_null());
+}
+
+#[test]
+fn unreadable_file_warning() {
+    let outcomes = vec![FileOutcome {
+        display_path: "locked.rs".into(),
+        match_key: "locked.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Unreadable {
+            error: "permission denied".into(),
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["summary"]["skipped"], 1);
+    assert_eq!(parsed["skip_warnings"][0]["path"], "locked.rs");
+    assert_eq!(parsed["skip_warnings"][0]["reason"], "unreadable");
+    assert_eq!(parsed["skip_warnings"][0]["detail"], "permission denied");
+}
+
+#[test]
+fn binary_file_warning() {
+    let outcomes = vec![FileOutcome {
+        display_path: "image.png".into(),
+        match_key: "image.png".into(),
+        config_path: None,
+        kind: OutcomeKind::Binary,
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["summary"]["skipped"], 1);
+    assert_eq!(parsed["skip_warnings"][0]["path"], "image.png");
+    assert_eq!(parsed["skip_warnings"][0]["reason"], "binary");
+    assert!(parsed["skip_warnings"][0]["detail"].is_null());
+}
+
+#[test]
+fn violation_with_rule_match() {
+    let outcomes = vec![FileOutcome {
+        display_path: "big.rs".into(),
+        match_key: "big.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![Measurement::new(loq_core::Limit::lines(100), 200)],
+            matched_by: MatchBy::Rule {
+                pattern: "**/*.rs".into(),
+            },
+            severity: loq_core::Severity::Error,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let violation = &parsed["violations"][0];
+    assert_eq!(violation["path"], "big.rs");
+    assert_eq!(violation["rule"], "**/*.rs");
+    let measurement = &violation["measurements"][0];
+    assert_eq!(measurement["metric"], "lines");
+    assert_eq!(measurement["actual"], 200);
+    assert_eq!(measurement["max"], 100);
+    assert_eq!(measurement["passed"], false);
+}
+
+#[test]
+fn token_violation_is_marked_approximate() {
+    let outcomes = vec![FileOutcome {
+        display_path: "prompt.md".into(),
+        match_key: "prompt.md".into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![Measurement::new(loq_core::Limit::tokens(4), 5)],
+            matched_by: MatchBy::Rule {
+                pattern: "prompts/**/*.md".into(),
+            },
+            severity: loq_core::Severity::Error,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let measurement = &parsed["violations"][0]["measurements"][0];
+    assert_eq!(measurement["metric"], "tokens");
+    assert_eq!(measurement["approximate"], true);
+    assert_eq!(measurement["actual"], 5);
+    assert_eq!(measurement["max"], 4);
+}
+
+#[test]
+fn every_measured_limit_is_listed_with_its_result() {
+    let outcomes = vec![FileOutcome {
+        display_path: "AGENTS.md".into(),
+        match_key: "AGENTS.md".into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![
+                Measurement::new(loq_core::Limit::lines(300), 320),
+                Measurement::new(loq_core::Limit::tokens(6000), 5000),
+            ],
+            matched_by: MatchBy::Default,
+            severity: loq_core::Severity::Error,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["violations"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        parsed["violations"][0]["measurements"],
+        serde_json::json!([
+            {"metric": "lines", "actual": 320, "max": 300, "passed": false, "approximate": false},
+            {
+                "metric": "tokens",
+                "actual": 5000,
+                "max": 6000,
+                "passed": true,
+                "approximate": true,
+                "estimate": {"bytes_per_token": 4.0},
+            },
+        ])
+    );
+    assert_eq!(parsed["summary"]["violations"], 1);
+}
+
+#[test]
+fn violation_with_default_match() {
+    let outcomes = vec![FileOutcome {
+        display_path: "big.rs".into(),
+        match_key: "big.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![Measurement::new(loq_core::Limit::lines(100), 200)],
+            matched_by: MatchBy::Default,
+            severity: loq_core::Severity::Error,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["violations"][0]["rule"], "default");
+}
+
+#[test]
+fn walk_errors_included() {
+    let json = json_output_string(
+        vec![],
+        vec![
+            walk::WalkError {
+                message: "path/to/error1".into(),
+            },
+            walk::WalkError {
+                message: "path/to/error2".into(),
+            },
+        ],
+        None,
+    );
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["summary"]["walk_errors"], 2);
+    let errors = parsed["walk_errors"].as_array().unwrap();
+    assert!(errors.contains(&serde_json::json!("path/to/error1")));
+    assert!(errors.contains(&serde_json::json!("path/to/error2")));
+}
+
+#[test]
+fn fix_guidance_included_with_violations() {
+    let outcomes = vec![FileOutcome {
+        display_path: "big.rs".into(),
+        match_key: "big.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![Measurement::new(loq_core::Limit::lines(100), 200)],
+            matched_by: MatchBy::Default,
+            severity: loq_core::Severity::Error,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], Some("Split large files.".into()));
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["fix_guidance"], "Split large files.");
+}
+
+#[test]
+fn fix_guidance_excluded_without_violations() {
+    let outcomes = vec![FileOutcome {
+        display_path: "small.rs".into(),
+        match_key: "small.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Pass {
+            measurements: vec![Measurement::new(loq_core::Limit::lines(100), 50)],
+            matched_by: MatchBy::Default,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], Some("Split large files.".into()));
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert!(parsed["fix_guidance"].is_null());
+}
+
+#[test]
+fn rule_fix_guidance_included_per_violation() {
+    let outcomes = vec![FileOutcome {
+        display_path: "src/App.tsx".into(),
+        match_key: "src/App.tsx".into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![Measurement {
+                fix_guidance: Some("Move hooks out of src/App.tsx.".into()),
+                ..Measurement::new(loq_core::Limit::lines(300), 412)
+            }],
+            matched_by: MatchBy::Rule {
+                pattern: "**/*.tsx".into(),
+            },
+            severity: loq_core::Severity::Error,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let measurement = &parsed["violations"][0]["measurements"][0];
+    assert_eq!(
+        measurement["fix_guidance"],
+        "Move hooks out of src/App.tsx."
+    );
+    assert!(parsed["fix_guidance"].is_null());
+}
+
+#[test]
+fn violations_sorted_by_path() {
+    let outcomes = vec![
+        FileOutcome {
+            display_path: "z.rs".into(),
+            match_key: "z.rs".into(),
+            config_path: None,
+            kind: OutcomeKind::Violation {
+                measurements: vec![Measurement::new(loq_core::Limit::lines(100), 200)],
+                matched_by: MatchBy::Default,
+                severity: loq_core::Severity::Error,
+            },
+            lines: None,
+            bundle_tokens: None,
+        },
+        FileOutcome {
+            display_path: "a.rs".into(),
+            match_key: "a.rs".into(),
+            config_path: None,
+            kind: OutcomeKind::Violation {
+                measurements: vec![Measurement::new(loq_core::Limit::lines(100), 200)],
+                matched_by: MatchBy::Default,
+                severity: loq_core::Severity::Error,
+            },
+            lines: None,
+            bundle_tokens: None,
+        },
+    ];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["violations"][0]["path"], "a.rs");
+    assert_eq!(parsed["violations"][1]["path"], "z.rs");
+}
+
+#[test]
+fn warnings_listed_separately_from_violations() {
+    let outcomes = vec![FileOutcome {
+        display_path: "legacy.rs".into(),
+        match_key: "legacy.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![Measurement::new(loq_core::Limit::lines(100), 120)],
+            matched_by: MatchBy::Default,
+            severity: loq_core::Severity::Warning,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["violations"].as_array().unwrap().len(), 0);
+    assert_eq!(parsed["warnings"][0]["path"], "legacy.rs");
+    assert_eq!(parsed["warnings"][0]["measurements"][0]["actual"], 120);
+    assert_eq!(parsed["summary"]["warnings"], 1);
+    assert_eq!(parsed["summary"]["violations"], 0);
+}
+
+#[test]
+fn near_limit_files_include_headroom() {
+    let outcomes = vec![FileOutcome {
+        display_path: "close.rs".into(),
+        match_key: "close.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Pass {
+            measurements: vec![Measurement {
+                near_limit: true,
+                ..Measurement::new(loq_core::Limit::lines(100), 95)
+            }],
+            matched_by: MatchBy::Default,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+
+    let json = json_output_string(outcomes, vec![], None);
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed["near_limit"][0]["path"], "close.rs");
+    assert_eq!(parsed["near_limit"][0]["measurements"][0]["headroom"], 5);
+    assert_eq!(parsed["summary"]["near_limit"], 1);
+    assert_eq!(parsed["summary"]["passed"], 1);
+}
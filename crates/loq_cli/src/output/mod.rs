@@ -1,13 +1,18 @@
+mod explain;
+mod finding;
 mod json;
 
 use std::io;
 
-use loq_core::report::{Finding, FindingKind, SkipReason, Summary};
+use loq_core::limit::format_bytes;
+use loq_core::report::Summary;
 use loq_core::{Limit, Metric};
 use loq_fs::walk::WalkError;
 use termcolor::{Color, ColorSpec, WriteColor};
 
-pub use json::write_json;
+pub use explain::{write_explanation, write_explanation_json};
+pub use finding::write_finding;
+pub use json::{write_config_errors_json, write_json};
 
 fn fg(color: Color) -> ColorSpec {
     let mut spec = ColorSpec::new();
@@ -217,79 +222,6 @@ pub fn write_line<W: WriteColor>(
     Ok(())
 }
 
-pub fn write_finding<W: WriteColor>(
-    writer: &mut W,
-    finding: &Finding,
-    verbose: bool,
-) -> io::Result<()> {
-    let (symbol, color) = match &finding.kind {
-        FindingKind::Violation { .. } => ("✖", Color::Red),
-        FindingKind::SkipWarning { .. } => ("⚠", Color::Yellow),
-    };
-
-    writer.set_color(&fg(color))?;
-    write!(writer, "{symbol} ")?;
-    writer.reset()?;
-
-    match &finding.kind {
-        FindingKind::Violation {
-            actual,
-            limit,
-            matched_by,
-            ..
-        } => {
-            let actual_str = formatted_measurement(*actual, *limit);
-            let limit_str = format_number(limit.max);
-            writer.set_color(&fg(color).set_bold(true).clone())?;
-            write!(writer, "{actual_str:>6}")?;
-            writer.reset()?;
-            if limit.metric == Metric::Tokens {
-                writer.set_color(&dimmed())?;
-                write!(writer, " tokens")?;
-                writer.reset()?;
-            }
-            writer.set_color(&dimmed())?;
-            write!(writer, " > ")?;
-            writer.reset()?;
-            writer.set_color(&fg(Color::Green))?;
-            write!(writer, "{limit_str:<6}")?;
-            writer.reset()?;
-
-            write!(writer, " ")?;
-            write_path(writer, &finding.path)?;
-            writeln!(writer)?;
-
-            if verbose {
-                writer.set_color(&dimmed())?;
-                let rule_str = match matched_by {
-                    loq_core::MatchBy::Rule { pattern } => {
-                        format!("{}={} (match: {pattern})", limit_key(*limit), limit.max)
-                    }
-                    loq_core::MatchBy::Default => {
-                        format!("{}={} (default)", limit_key(*limit), limit.max)
-                    }
-                };
-                writeln!(writer, "                  └─ rule: {rule_str}")?;
-                writer.reset()?;
-            }
-        }
-        FindingKind::SkipWarning { reason } => {
-            let msg = match reason {
-                SkipReason::Binary => "binary file skipped",
-                SkipReason::Unreadable(e) => {
-                    write_path(writer, &finding.path)?;
-                    return writeln!(writer, "  unreadable: {e}");
-                }
-                SkipReason::Missing => "file not found",
-            };
-            write_path(writer, &finding.path)?;
-            writeln!(writer, "  {msg}")?;
-        }
-    }
-
-    Ok(())
-}
-
 pub(crate) fn write_path<W: WriteColor>(writer: &mut W, path: &str) -> io::Result<()> {
     if let Some(pos) = path.rfind('/') {
         let (dir, file) = path.split_at(pos + 1);
@@ -318,6 +250,9 @@ pub fn format_number(n: usize) -> String {
 }
 
 fn formatted_measurement(actual: usize, limit: Limit) -> String {
+    if limit.metric == Metric::Bytes {
+        return format_bytes(actual);
+    }
     let value = format_number(actual);
     if limit.is_approximate() {
         format!("~{value}")
@@ -329,7 +264,25 @@ fn formatted_measurement(actual: usize, limit: Limit) -> String {
 const fn limit_key(limit: Limit) -> &'static str {
     match limit.metric {
         Metric::Lines => "max-lines",
+        Metric::CodeLines => "max-code-lines",
+        Metric::SourceLines => "max-source-lines",
         Metric::Tokens => "max-tokens",
+        Metric::Bytes => "max-bytes",
+        Metric::LineLength => "max-line-length",
+    }
+}
+
+/// Formats a limit as its setting, e.g. `max-tokens=8000 bytes-per-token=3.2`
+/// for an estimated token limit.
+fn limit_setting(limit: Limit) -> String {
+    let setting = format!("{}={}", limit_key(limit), limit.max);
+    match limit.token_estimate() {
+        Some(estimate) => format!(
+            "{setting} {}={}",
+            estimate.key().replace('_', "-"),
+            estimate.factor()
+        ),
+        None => setting,
     }
 }
 
@@ -349,22 +302,38 @@ pub fn write_block<W: WriteColor>(
 }
 
 pub fn write_summary<W: WriteColor>(writer: &mut W, summary: &Summary) -> io::Result<()> {
-    if summary.errors > 0 {
-        let word = if summary.errors == 1 {
-            "violation"
-        } else {
-            "violations"
-        };
-        writer.set_color(&fg(Color::Red))?;
-        writeln!(writer, "{} {word}", summary.errors)?;
-    } else {
+    if summary.errors == 0 && summary.warnings == 0 {
         writer.set_color(&fg(Color::Green))?;
         write!(writer, "✔")?;
         writer.reset()?;
         let word = if summary.passed == 1 { "file" } else { "files" };
-        writeln!(writer, " {} {word} ok", format_number(summary.passed))?;
+        write!(writer, " {} {word} ok", format_number(summary.passed))?;
+    } else {
+        let mut parts = Vec::new();
+        if summary.errors > 0 {
+            let text = format!("{} violation{}", summary.errors, plural(summary.errors));
+            parts.push((Color::Red, text));
+        }
+        if summary.warnings > 0 {
+            let text = format!("{} warning{}", summary.warnings, plural(summary.warnings));
+            parts.push((Color::Yellow, text));
+        }
+        for (index, (color, text)) in parts.into_iter().enumerate() {
+            if index > 0 {
+                write!(writer, ", ")?;
+            }
+            writer.set_color(&fg(color))?;
+            write!(writer, "{text}")?;
+            writer.reset()?;
+        }
     }
-    writer.reset()
+    if summary.near_limit > 0 {
+        write!(writer, ", ")?;
+        writer.set_color(&fg(Color::Cyan))?;
+        write!(writer, "{} near limit", summary.near_limit)?;
+        writer.reset()?;
+    }
+    writeln!(writer)
 }
 
 pub fn print_error<W: WriteColor>(stderr: &mut W, message: &str) -> crate::ExitStatus {
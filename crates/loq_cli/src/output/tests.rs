@@ -1,6 +1,6 @@
 use super::*;
 use loq_core::report::{Finding, FindingKind, SkipReason, Summary};
-use loq_core::MatchBy;
+use loq_core::{MatchBy, Measurement};
 use termcolor::NoColor;
 
 fn output_string<F>(f: F) -> String
@@ -77,8 +77,7 @@ fn write_finding_violation() {
     let finding = Finding {
         path: "src/main.rs".into(),
         kind: FindingKind::Violation {
-            limit: loq_core::Limit::lines(100),
-            actual: 150,
+            measurements: vec![Measurement::new(loq_core::Limit::lines(100), 150)],
             matched_by: MatchBy::Default,
         },
     };
@@ -90,13 +89,109 @@ fn write_finding_violation() {
     assert!(out.contains("> 100"));
 }
 
+#[test]
+fn write_finding_violation_lists_each_exceeded_budget() {
+    let finding = Finding {
+        path: "docs/guide.md".into(),
+        kind: FindingKind::Violation {
+            measurements: vec![
+                Measurement::new(loq_core::Limit::lines(100), 150),
+                Measurement::new(loq_core::Limit::bytes(10_000), 5_000),
+                Measurement::new(loq_core::Limit::tokens(1_000), 1_200),
+            ],
+            matched_by: MatchBy::Default,
+        },
+    };
+    let out = output_string(|w| write_finding(w, &finding, false));
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!(lines.len(), 2, "{out}");
+    assert!(lines[0].contains("150 > 100"));
+    assert!(lines[1].contains("> 1_000"));
+}
+
+#[test]
+fn write_finding_warning() {
+    let finding = Finding {
+        path: "src/legacy.rs".into(),
+        kind: FindingKind::Warning {
+            measurements: vec![Measurement::new(loq_core::Limit::lines(100), 150)],
+            matched_by: MatchBy::Default,
+        },
+    };
+    let out = output_string(|w| write_finding(w, &finding, false));
+    assert!(out.starts_with("⚠"));
+    assert!(out.contains("150 > 100"));
+    assert!(out.contains("legacy.rs"));
+}
+
+#[test]
+fn write_finding_near_limit_shows_headroom() {
+    let finding = Finding {
+        path: "src/big.rs".into(),
+        kind: FindingKind::NearLimit {
+            measurements: vec![Measurement {
+                near_limit: true,
+                ..Measurement::new(loq_core::Limit::lines(100), 95)
+            }],
+            matched_by: MatchBy::Default,
+        },
+    };
+    let out = output_string(|w| write_finding(w, &finding, false));
+    assert_eq!(out, "●     95 / 100    src/big.rs  5 lines left\n");
+}
+
+#[test]
+fn write_finding_near_limit_names_line_length_units() {
+    let near = |limit: loq_core::Limit| Finding {
+        path: "src/wide.rs".into(),
+        kind: FindingKind::NearLimit {
+            measurements: vec![Measurement {
+                near_limit: true,
+                ..Measurement::new(limit, 90)
+            }],
+            matched_by: MatchBy::Default,
+        },
+    };
+    let columns = loq_core::Limit::line_length(120);
+    let bytes = columns.measured_in(loq_core::LineLengthUnit::Bytes);
+
+    let out = output_string(|w| write_finding(w, &near(columns), false));
+    assert!(out.ends_with("src/wide.rs  30 columns left\n"), "{out}");
+    let out = output_string(|w| write_finding(w, &near(bytes), false));
+    assert!(out.ends_with("src/wide.rs  30 bytes left\n"), "{out}");
+}
+
+#[test]
+fn write_finding_violation_shows_rule_guidance() {
+    let finding = Finding {
+        path: "src/App.tsx".into(),
+        kind: FindingKind::Violation {
+            measurements: vec![Measurement {
+                fix_guidance: Some("Split src/App.tsx.\nMove hooks to hooks/.".into()),
+                ..Measurement::new(loq_core::Limit::lines(300), 412)
+            }],
+            matched_by: MatchBy::Default,
+        },
+    };
+    let out = output_string(|w| write_finding(w, &finding, false));
+    let mut lines = out.lines().skip(1);
+    assert_eq!(
+        lines.next(),
+        Some("                  └─ Split src/App.tsx.")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("                     Move hooks to hooks/.")
+    );
+    assert!(lines.next().is_none());
+}
+
 #[test]
 fn write_finding_token_violation_marks_approximate_unit() {
     let finding = Finding {
         path: "prompts/build.md".into(),
         kind: FindingKind::Violation {
-            limit: loq_core::Limit::tokens(4),
-            actual: 5,
+            measurements: vec![Measurement::new(loq_core::Limit::tokens(4), 5)],
             matched_by: MatchBy::Rule {
                 pattern: "prompts/**/*.md".into(),
             },
@@ -115,8 +210,7 @@ fn write_finding_token_violation_verbose_shows_token_rule() {
     let finding = Finding {
         path: "prompts/build.md".into(),
         kind: FindingKind::Violation {
-            limit: loq_core::Limit::tokens(4),
-            actual: 5,
+            measurements: vec![Measurement::new(loq_core::Limit::tokens(4), 5)],
             matched_by: MatchBy::Rule {
                 pattern: "prompts/**/*.md".into(),
             },
@@ -134,8 +228,7 @@ fn write_finding_violation_verbose_default_match() {
     let finding = Finding {
         path: "src/lib.rs".into(),
         kind: FindingKind::Violation {
-            limit: loq_core::Limit::lines(100),
-            actual: 200,
+            measurements: vec![Measurement::new(loq_core::Limit::lines(100), 200)],
             matched_by: MatchBy::Default,
         },
     };
@@ -149,8 +242,7 @@ fn write_finding_violation_verbose_rule_match() {
     let finding = Finding {
         path: "src/lib.rs".into(),
         kind: FindingKind::Violation {
-            limit: loq_core::Limit::lines(50),
-            actual: 75,
+            measurements: vec![Measurement::new(loq_core::Limit::lines(50), 75)],
             matched_by: MatchBy::Rule {
                 pattern: "**/*.rs".into(),
             },
@@ -204,8 +296,7 @@ fn write_finding_path_without_directory() {
     let finding = Finding {
         path: "file.txt".into(),
         kind: FindingKind::Violation {
-            limit: loq_core::Limit::lines(10),
-            actual: 20,
+            measurements: vec![Measurement::new(loq_core::Limit::lines(10), 20)],
             matched_by: MatchBy::Default,
         },
     };
@@ -220,11 +311,55 @@ fn write_summary_with_violations() {
         skipped: 2,
         passed: 5,
         errors: 3,
+        warnings: 0,
+        near_limit: 0,
     };
     let out = output_string(|w| write_summary(w, &summary));
     assert!(out.contains("3 violations"));
 }
 
+#[test]
+fn write_summary_with_violations_and_warnings() {
+    let summary = Summary {
+        total: 10,
+        skipped: 0,
+        passed: 7,
+        errors: 2,
+        warnings: 1,
+        near_limit: 0,
+    };
+    let out = output_string(|w| write_summary(w, &summary));
+    assert_eq!(out, "2 violations, 1 warning\n");
+}
+
+#[test]
+fn write_summary_only_warnings() {
+    let summary = Summary {
+        total: 10,
+        skipped: 0,
+        passed: 8,
+        errors: 0,
+        warnings: 2,
+        near_limit: 0,
+    };
+    let out = output_string(|w| write_summary(w, &summary));
+    assert_eq!(out, "2 warnings\n");
+}
+
+#[test]
+fn write_summary_appends_near_limit_count() {
+    let summary = Summary {
+        total: 4,
+        skipped: 0,
+        passed: 4,
+        errors: 0,
+        warnings: 0,
+        near_limit: 2,
+    };
+    let out = output_string(|w| write_summary(w, &summary));
+    assert_eq!(out, "✔ 4 files ok, 2 near limit\n");
+}
+
 #[test]
 fn write_summary_all_passed() {
     let summary = Summary {
@@ -232,6 +367,8 @@ fn write_summary_all_passed() {
         skipped: 0,
         passed: 5,
         errors: 0,
+        warnings: 0,
+        near_limit: 0,
     };
     let out = output_string(|w| write_summary(w, &summary));
     assert!(out.contains("✔"));
@@ -245,6 +382,8 @@ fn write_summary_one_file_passed() {
         skipped: 0,
         passed: 1,
         errors: 0,
+        warnings: 0,
+        near_limit: 0,
     };
     let out = output_string(|w| write_summary(w, &summary));
     assert!(out.contains("1 file ok"));
@@ -258,6 +397,8 @@ fn write_summary_single_violation() {
         skipped: 0,
         passed: 0,
         errors: 1,
+        warnings: 0,
+        near_limit: 0,
     };
     let out = output_string(|w| write_summary(w, &summary));
     assert!(out.contains("1 violation"));
@@ -1,14 +1,16 @@
 //! Relax command implementation.
 
 use std::collections::HashMap;
+use std::path::Path;
 
 use anyhow::{Context, Result};
+use loq_core::config::{compile_config, ConfigLayer, LoqConfig};
 use loq_fs::{CheckConfig, CheckOptions};
 use termcolor::WriteColor;
-use toml_edit::DocumentMut;
+use toml_edit::Table;
 
 use crate::cli::RelaxArgs;
-use crate::config_edit::{config_path_and_root, load_doc_or_default, persist_doc};
+use crate::config_edit::{config_path_and_root, load_doc_or_default, loq_table_mut, persist_doc};
 use crate::exact_limits::{self, ExactLimits};
 use crate::line_violations::line_violations;
 use crate::output::{
@@ -22,10 +24,11 @@ struct RelaxReport {
 
 pub fn run_relax<W1: WriteColor, W2: WriteColor>(
     args: &RelaxArgs,
+    config: Option<&Path>,
     stdout: &mut W1,
     stderr: &mut W2,
 ) -> ExitStatus {
-    match run_relax_inner(args) {
+    match run_relax_inner(args, config) {
         Ok(report) if report.changes.is_empty() => {
             let _ = writeln!(stdout, "✔ No changes needed");
             ExitStatus::Success
@@ -38,9 +41,9 @@ pub fn run_relax<W1: WriteColor, W2: WriteColor>(
     }
 }
 
-fn run_relax_inner(args: &RelaxArgs) -> Result<RelaxReport> {
+fn run_relax_inner(args: &RelaxArgs, config: Option<&Path>) -> Result<RelaxReport> {
     let cwd = std::env::current_dir().context("failed to get current directory")?;
-    let (config_path, root) = config_path_and_root(&cwd)?;
+    let (config_path, root) = config_path_and_root(&cwd, config)?;
     let config_exists = config_path.exists();
 
     let paths = if args.files.is_empty() {
@@ -49,15 +52,21 @@ fn run_relax_inner(args: &RelaxArgs) -> Result<RelaxReport> {
         args.files.clone()
     };
 
-    let config = if config_exists {
-        CheckConfig::File(config_path.clone())
-    } else {
-        CheckConfig::Discover
+    let check_config = match (config, config_exists) {
+        (Some(_), true) => CheckConfig::Explicit(config_path.clone()),
+        (None, true) => CheckConfig::File(config_path.clone()),
+        (Some(_), false) => CheckConfig::Compiled(Box::new(compile_config(
+            root.clone(),
+            LoqConfig::default(),
+            None,
+        )?)),
+        (None, false) => CheckConfig::Discover,
     };
     let options = CheckOptions {
-        config,
+        config: check_config,
         cwd,
         use_cache: false,
+        overrides: ConfigLayer::default(),
     };
 
     let output = loq_fs::run_check(paths, options).context("relax check failed")?;
@@ -71,8 +80,9 @@ fn run_relax_inner(args: &RelaxArgs) -> Result<RelaxReport> {
 
     let (mut doc, config_exists_for_write) = load_doc_or_default(&config_path)?;
 
-    let existing_rules = ExactLimits::collect(&doc);
-    let changes = apply_relax_changes(&mut doc, &violations, &existing_rules, args.extra);
+    let table = loq_table_mut(&mut doc, &config_path)?;
+    let existing_rules = ExactLimits::collect(table);
+    let changes = apply_relax_changes(table, &violations, &existing_rules, args.extra);
 
     persist_doc(&root, &config_path, &doc, config_exists_for_write)?;
 
@@ -80,7 +90,7 @@ fn run_relax_inner(args: &RelaxArgs) -> Result<RelaxReport> {
 }
 
 fn apply_relax_changes(
-    doc: &mut DocumentMut,
+    doc: &mut Table,
     violations: &HashMap<String, usize>,
     existing_rules: &ExactLimits,
     buffer: usize,
@@ -124,7 +134,7 @@ fn write_report<W: WriteColor>(writer: &mut W, report: &RelaxReport) -> std::io:
 mod tests {
     use super::*;
     use termcolor::NoColor;
-    use toml_edit::Item;
+    use toml_edit::{DocumentMut, Item};
 
     #[test]
     fn apply_relax_changes_updates_and_adds_rules() {
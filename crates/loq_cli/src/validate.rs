@@ -0,0 +1,198 @@
+//! Config validate and schema command implementations.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use loq_core::config::{compile_config, CompiledConfig, ConfigError};
+use loq_core::PatternList;
+use loq_fs::discover::ConfigLocator;
+use loq_fs::walk::{expand_paths, WalkOptions};
+use loq_fs::PathIdentity;
+use serde::Serialize;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+use crate::cli::{OutputFormat, ValidateArgs};
+use crate::config_lint::{lint_config, Lint};
+use crate::output::{plural, print_error, write_config_errors_json};
+use crate::ExitStatus;
+
+/// A config loaded for linting.
+struct Target {
+    /// Config file path as given or discovered.
+    path: PathBuf,
+    compiled: CompiledConfig,
+    /// Whether the config was discovered, so nested configs govern their own files.
+    discovered: bool,
+    cwd: PathBuf,
+}
+
+pub fn run_validate<W1: WriteColor + Write, W2: WriteColor>(
+    args: &ValidateArgs,
+    config: Option<&Path>,
+    stdout: &mut W1,
+    stderr: &mut W2,
+) -> ExitStatus {
+    let target = match load_target(config) {
+        Ok(target) => target,
+        Err(err) => {
+            if args.output_format == OutputFormat::Json {
+                if let Some(ConfigError::Diagnostics {
+                    path, diagnostics, ..
+                }) = err.downcast_ref::<ConfigError>()
+                {
+                    let _ = write_config_errors_json(stdout, path, diagnostics);
+                    return ExitStatus::Error;
+                }
+            }
+            return print_error(stderr, &format!("{err:#}"));
+        }
+    };
+
+    let (walked, governs) = files_under_root(&target);
+    let lints = lint_config(&target.compiled, &walked, &governs);
+    let display = PathIdentity::new(&target.path, &target.cwd, &target.cwd).display;
+    let _ = match args.output_format {
+        OutputFormat::Json => write_json(stdout, &display, &lints),
+        OutputFormat::Text => write_text(stdout, &display, &lints),
+    };
+
+    if lints.is_empty() {
+        ExitStatus::Success
+    } else {
+        ExitStatus::Failure
+    }
+}
+
+pub fn run_schema<W: Write>(stdout: &mut W) -> ExitStatus {
+    let _ = stdout.write_all(loq_core::CONFIG_SCHEMA.as_bytes());
+    ExitStatus::Success
+}
+
+fn load_target(explicit: Option<&Path>) -> Result<Target> {
+    let cwd = std::env::current_dir()
+        .and_then(dunce::canonicalize)
+        .context("failed to get current directory")?;
+    let (path, root, discovered) = if let Some(path) = explicit {
+        (cwd.join(path), cwd.clone(), false)
+    } else {
+        let path = loq_fs::discover::find_config(&cwd)
+            .ok_or_else(|| anyhow!("no loq config found in or above the current directory"))?;
+        let root = path
+            .parent()
+            .context("config path has no parent")?
+            .to_path_buf();
+        (path, root, true)
+    };
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let config = loq_core::parse_config(&path, &text)?;
+    let compiled = compile_config(root, config, Some(&path))?;
+    Ok(Target {
+        path,
+        compiled,
+        discovered,
+        cwd,
+    })
+}
+
+/// Returns the match keys of every file under the config root, ignoring its
+/// includes and excludes, and of the subset the config governs.
+fn files_under_root(target: &Target) -> (Vec<String>, Vec<String>) {
+    let root = &target.compiled.root_dir;
+    let no_patterns = PatternList::default();
+    let options = WalkOptions {
+        respect_gitignore: target.compiled.respect_gitignore,
+        include: &no_patterns,
+        exclude: &no_patterns,
+        cwd: root,
+        root_dir: root,
+    };
+    let mut paths = expand_paths(std::slice::from_ref(root), &options).paths;
+    paths.sort();
+
+    let mut locator = ConfigLocator::new();
+    let mut walked = Vec::with_capacity(paths.len());
+    let mut governs = Vec::with_capacity(paths.len());
+    for path in paths {
+        let key = PathIdentity::new(&path, root, root).match_key;
+        let nearest = path.parent().and_then(|dir| locator.find(dir));
+        if !target.discovered || nearest.as_ref() == Some(&target.path) {
+            governs.push(key.clone());
+        }
+        walked.push(key);
+    }
+    (walked, governs)
+}
+
+fn subject(lint: &Lint) -> String {
+    match (lint.rule_index(), lint) {
+        (Some(index), _) => format!("rules[{index}] '{}'", lint.pattern()),
+        (None, Lint::UnmatchedInclude { .. }) => format!("include '{}'", lint.pattern()),
+        (None, _) => format!("exclude '{}'", lint.pattern()),
+    }
+}
+
+fn write_text<W: WriteColor>(writer: &mut W, config: &str, lints: &[Lint]) -> io::Result<()> {
+    let mut yellow = ColorSpec::new();
+    yellow.set_fg(Some(Color::Yellow));
+    let mut green = ColorSpec::new();
+    green.set_fg(Some(Color::Green));
+
+    if lints.is_empty() {
+        writer.set_color(&green)?;
+        write!(writer, "✔")?;
+        writer.reset()?;
+        return writeln!(writer, " {config}: no problems found");
+    }
+    for lint in lints {
+        writer.set_color(&yellow)?;
+        write!(writer, "⚠ ")?;
+        writer.reset()?;
+        writeln!(writer, "{}: {}", subject(lint), lint.message())?;
+    }
+    writer.set_color(&yellow)?;
+    write!(writer, "{} problem{}", lints.len(), plural(lints.len()))?;
+    writer.reset()?;
+    writeln!(writer, " in {config}")
+}
+
+#[derive(Debug, Serialize)]
+struct JsonValidation {
+    version: &'static str,
+    config: String,
+    lints: Vec<JsonLint>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonLint {
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule: Option<usize>,
+    pattern: String,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shadowed_by: Option<usize>,
+}
+
+fn write_json<W: Write>(writer: &mut W, config: &str, lints: &[Lint]) -> io::Result<()> {
+    let output = JsonValidation {
+        version: env!("CARGO_PKG_VERSION"),
+        config: config.to_string(),
+        lints: lints
+            .iter()
+            .map(|lint| JsonLint {
+                kind: lint.kind(),
+                rule: lint.rule_index(),
+                pattern: lint.pattern().to_string(),
+                message: lint.message(),
+                shadowed_by: match lint {
+                    Lint::ShadowedRule { by, .. } => by.as_ref().map(|(index, _)| *index),
+                    _ => None,
+                },
+            })
+            .collect(),
+    };
+    serde_json::to_writer_pretty(&mut *writer, &output)?;
+    writeln!(writer)
+}
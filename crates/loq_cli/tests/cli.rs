@@ -369,7 +369,7 @@ max_lines = 1
 }
 
 #[test]
-fn old_v1_cache_is_migrated_to_v2() {
+fn old_v1_cache_is_migrated_to_v8() {
     let temp = TempDir::new().unwrap();
     write_file(&temp, "loq.toml", "default_max_lines = 500\n");
     write_file(&temp, "a.txt", "hello\n");
@@ -385,21 +385,21 @@ fn old_v1_cache_is_migrated_to_v2() {
         .assert()
         .success();
 
-    // Verify cache was rewritten as v2
+    // Verify cache was rewritten as v8
     let cache_contents = std::fs::read_to_string(temp.path().join(".loq_cache")).unwrap();
     let cache: serde_json::Value = serde_json::from_str(&cache_contents).unwrap();
-    assert_eq!(cache["version"], 2, "cache should be upgraded to v2");
-    // v2 format uses `result` field with enum, not `lines`
+    assert_eq!(cache["version"], 8, "cache should be upgraded to v8");
+    // Entries now use a `result` enum rather than v1's `lines`
     let entries = cache["entries"].as_object().unwrap();
     assert!(!entries.is_empty(), "cache should have entries");
     for (_key, entry) in entries {
         assert!(
             entry.get("result").is_some(),
-            "v2 cache entries should have 'result' field"
+            "migrated cache entries should have 'result' field"
         );
         assert!(
             entry.get("lines").is_none(),
-            "v2 cache entries should not have 'lines' field"
+            "migrated cache entries should not have 'lines' field"
         );
     }
 }
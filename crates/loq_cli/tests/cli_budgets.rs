@@ -0,0 +1,361 @@
+//! Integration tests for limits that total several files: bundles,
+//! directory rules and followed imports.
+
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+fn write_file(dir: &Path, path: &str, contents: &str) {
+    let full = dir.join(path);
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(full, contents).unwrap();
+}
+
+/// Token counts use the default estimate of four bytes per token.
+fn bundle_project(config: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    write_file(temp.path(), "loq.toml", config);
+    write_file(temp.path(), "AGENTS.md", &"x".repeat(400));
+    write_file(temp.path(), "docs/architecture/a.md", &"x".repeat(800));
+    write_file(temp.path(), "docs/architecture/b.md", &"x".repeat(40));
+    write_file(temp.path(), "src/main.rs", &"x".repeat(4000));
+    temp
+}
+
+const BUNDLE: &str = r#"
+[[bundles]]
+name = "agent context"
+path = ["AGENTS.md", "docs/architecture/*.md"]
+max_tokens = 250
+"#;
+
+fn components_project(rule: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let config = format!("[[directory_rules]]\npath = \"components/*\"\n{rule}");
+    write_file(temp.path(), "loq.toml", &config);
+    write_file(temp.path(), "components/button/index.ts", "a\nb\nc\n");
+    write_file(temp.path(), "components/button/style.css", "a\nb\n");
+    write_file(temp.path(), "components/button/icons/close.svg", "a\n");
+    write_file(temp.path(), "components/card/index.ts", "a\n");
+    temp
+}
+
+fn imports_project(follow_imports: bool) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let config = format!(
+        "[[rules]]\npath = \"CLAUDE.md\"\nmax_lines = 10\nfollow_imports = {follow_imports}\n"
+    );
+    write_file(temp.path(), "loq.toml", &config);
+    write_file(
+        temp.path(),
+        "CLAUDE.md",
+        "intro\n@docs/style.md\nSee [testing](docs/testing.md).\n",
+    );
+    write_file(
+        temp.path(),
+        "docs/style.md",
+        "a\n@nested/naming.md\n@../CLAUDE.md\n",
+    );
+    write_file(temp.path(), "docs/nested/naming.md", "1\n2\n3\n4\n5\n");
+    write_file(temp.path(), "docs/testing.md", "1\n2\n3\n4\n");
+    temp
+}
+
+#[test]
+fn bundle_over_budget_lists_members_by_size() {
+    let temp = bundle_project(BUNDLE);
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("~310"))
+        .stdout(predicate::str::contains("agent context"))
+        .stdout(predicate::str::contains("main.rs").not())
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let a = stdout.find("~200").unwrap();
+    let agents = stdout.find("~100").unwrap();
+    let b = stdout.find("~10 ").unwrap();
+    assert!(a < agents && agents < b, "{stdout}");
+}
+
+#[test]
+fn bundle_violation_is_reported_in_json() {
+    let temp = bundle_project(BUNDLE);
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let violations = json["bundle_violations"].as_array().unwrap();
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert_eq!(violations[0]["name"], "agent context");
+    assert_eq!(violations[0]["metric"], "tokens");
+    assert_eq!(violations[0]["actual"], 310);
+    assert_eq!(violations[0]["max"], 250);
+    let members = violations[0]["members"].as_array().unwrap();
+    let members = members
+        .iter()
+        .map(|member| {
+            (
+                member["path"].as_str().unwrap(),
+                member["tokens"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        members,
+        [
+            ("docs/architecture/a.md", 200),
+            ("AGENTS.md", 100),
+            ("docs/architecture/b.md", 10),
+        ]
+    );
+    assert_eq!(json["summary"]["violations"], 1);
+}
+
+#[test]
+fn bundle_within_budget_passes() {
+    let temp = bundle_project(&BUNDLE.replace("250", "310"));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn members_keep_their_own_limits() {
+    let config = format!("default_max_lines = 1\n{BUNDLE}");
+    let temp = bundle_project(&config);
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let violations = json["bundle_violations"].as_array().unwrap();
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert_eq!(violations[0]["name"], "agent context");
+    assert!(json["violations"].as_array().unwrap().is_empty());
+    assert_eq!(json["summary"]["passed"], 4);
+}
+
+#[test]
+fn members_are_counted_with_the_bundle_estimate() {
+    let config = format!(
+        "{}\n[[rules]]\npath = \"AGENTS.md\"\nmax_tokens = 1000\ntokens_per_word = 1.0\n",
+        BUNDLE.replace("250", "215")
+    );
+    let temp = bundle_project(&config);
+    write_file(temp.path(), "AGENTS.md", &"x".repeat(40));
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let violations = json["bundle_violations"].as_array().unwrap();
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert_eq!(violations[0]["actual"], 220);
+    let agents = violations[0]["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|member| member["path"] == "AGENTS.md")
+        .unwrap();
+    assert_eq!(agents["tokens"], 10);
+}
+
+#[test]
+fn checking_one_member_totals_the_whole_bundle() {
+    let temp = bundle_project(BUNDLE);
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "AGENTS.md"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("~310"))
+        .stdout(predicate::str::contains("~200"));
+
+    // Bundles the run does not touch are not reported.
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "src/main.rs"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn directory_over_max_files_fails() {
+    let temp = components_project("max_files = 2\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "-v"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("3"))
+        .stdout(predicate::str::contains("files > "))
+        .stdout(predicate::str::contains("button"))
+        .stdout(predicate::str::contains(
+            "max-files=2 (match: components/*)",
+        ))
+        .stdout(predicate::str::contains("card").not());
+}
+
+#[test]
+fn directory_total_lines_are_reported_in_json() {
+    let temp = components_project("max_total_lines = 5\n");
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let violations = json["directory_violations"].as_array().unwrap();
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert_eq!(violations[0]["path"], "components/button");
+    assert_eq!(violations[0]["metric"], "total_lines");
+    assert_eq!(violations[0]["actual"], 6);
+    assert_eq!(violations[0]["max"], 5);
+    assert_eq!(violations[0]["rule"], "components/*");
+    assert_eq!(json["summary"]["violations"], 1);
+}
+
+#[test]
+fn directories_within_budget_pass() {
+    let temp = components_project("max_files = 3\nmax_total_lines = 6\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn checked_files_count_like_unlimited_ones() {
+    // The config above sets no default limit, so those files are unlimited.
+    let temp = components_project("max_total_lines = 5\n");
+    let config = std::fs::read_to_string(temp.path().join("loq.toml")).unwrap();
+    write_file(
+        temp.path(),
+        "loq.toml",
+        &format!("default_max_lines = 100\n{config}"),
+    );
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(json["directory_violations"][0]["actual"], 6);
+    assert_eq!(json["summary"]["passed"], 4);
+}
+
+#[test]
+fn checking_one_file_totals_its_whole_directory() {
+    let temp = components_project("max_files = 2\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "components/button/index.ts"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("button"));
+
+    // Directories the run does not touch are not reported.
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "components/card/index.ts"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn imports_count_toward_the_entry_file() {
+    let temp = imports_project(true);
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("15"))
+        .stdout(predicate::str::contains("├─ "))
+        .stdout(predicate::str::contains("│  └─ "))
+        .stdout(predicate::str::contains("naming.md"))
+        .stdout(predicate::str::contains("└─ "));
+}
+
+#[test]
+fn import_tree_is_reported_in_json() {
+    let temp = imports_project(true);
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let violations = json["violations"].as_array().unwrap();
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert_eq!(violations[0]["path"], "CLAUDE.md");
+    let measurement = &violations[0]["measurements"][0];
+    assert_eq!(measurement["actual"], 15);
+    let imports = measurement["imports"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|import| {
+            (
+                import["path"].as_str().unwrap(),
+                import["depth"].as_u64().unwrap(),
+                import["value"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        imports,
+        [
+            ("CLAUDE.md", 0, 3),
+            ("docs/style.md", 1, 3),
+            ("docs/nested/naming.md", 2, 5),
+            ("docs/testing.md", 1, 4),
+        ]
+    );
+}
+
+#[test]
+fn imports_are_not_followed_by_default() {
+    let temp = imports_project(false);
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success();
+}
@@ -0,0 +1,299 @@
+//! Integration tests for warning-severity limits, near-limit (`warn_at`)
+//! findings and `loq explain`.
+
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+fn write_file(dir: &Path, path: &str, contents: &str) {
+    let full = dir.join(path);
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(full, contents).unwrap();
+}
+
+fn repeat_lines(count: usize) -> String {
+    "line\n".repeat(count)
+}
+
+fn severity_project(config: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "loq.toml", config);
+    write_file(root, "src/legacy.txt", &repeat_lines(20));
+    temp
+}
+
+const WARNING_RULE: &str = "default_max_lines = 100\n\n[[rules]]\npath = \"src/**\"\nmax_lines = 10\nseverity = \"warning\"\n";
+
+fn warn_at_project(config: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "loq.toml", config);
+    write_file(root, "src/close.txt", &repeat_lines(19));
+    write_file(root, "src/small.txt", &repeat_lines(5));
+    temp
+}
+
+const EXPLAIN_CONFIG: &str = r#"default_max_lines = 500
+exclude = ["gen/**"]
+
+[[rules]]
+path = "**/*.rs"
+max_lines = 800
+
+[[rules]]
+path = "src/app.rs"
+max_lines = 2
+"#;
+
+fn explain_project() -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "loq.toml", EXPLAIN_CONFIG);
+    write_file(root, "src/app.rs", "a\nb\nc\n");
+    write_file(root, "src/lib.rs", "a\n");
+    write_file(root, "gen/out.rs", "a\n");
+    write_file(root, "notes.txt", "a\n");
+    temp
+}
+
+#[test]
+fn warnings_do_not_fail_check() {
+    let temp = severity_project(WARNING_RULE);
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("legacy.txt"))
+        .stdout(predicate::str::contains("1 warning"))
+        .stdout(predicate::str::contains("violation").not());
+}
+
+#[test]
+fn warnings_as_errors_fails_check() {
+    let temp = severity_project(WARNING_RULE);
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--warnings-as-errors"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation"))
+        .stdout(predicate::str::contains("warning").not());
+}
+
+#[test]
+fn default_severity_applies_to_default_limit() {
+    let temp = severity_project("default_max_lines = 10\ndefault_severity = \"warning\"\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 warning"));
+}
+
+#[test]
+fn error_rule_overrides_warning_default() {
+    let temp = severity_project(
+        "default_max_lines = 10\ndefault_severity = \"warning\"\n\n[[rules]]\npath = \"src/**\"\nmax_lines = 10\n",
+    );
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation"));
+}
+
+#[test]
+fn json_lists_warnings() {
+    let temp = severity_project(WARNING_RULE);
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["warnings"][0]["path"], "src/legacy.txt");
+    assert_eq!(json["summary"]["warnings"], 1);
+    assert_eq!(json["summary"]["violations"], 0);
+}
+
+#[test]
+fn near_limit_files_are_reported_without_failing() {
+    let temp = warn_at_project("default_max_lines = 20\nwarn_at = 0.9\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("close.txt"))
+        .stdout(predicate::str::contains("1 line left"))
+        .stdout(predicate::str::contains("small.txt").not())
+        .stdout(predicate::str::contains("1 near limit"));
+}
+
+#[test]
+fn rule_warn_at_overrides_global() {
+    let temp = warn_at_project(
+        "default_max_lines = 20\nwarn_at = 0.9\n\n[[rules]]\npath = \"src/close.txt\"\nmax_lines = 20\nwarn_at = 1\n",
+    );
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("near limit").not());
+}
+
+#[test]
+fn no_near_limit_findings_without_warn_at() {
+    let temp = warn_at_project("default_max_lines = 20\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("close.txt").not());
+}
+
+#[test]
+fn json_lists_near_limit_files() {
+    let temp = warn_at_project("default_max_lines = 20\nwarn_at = 0.5\n");
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["near_limit"][0]["path"], "src/close.txt");
+    assert_eq!(json["near_limit"][0]["measurements"][0]["headroom"], 1);
+    assert_eq!(json["summary"]["near_limit"], 1);
+}
+
+#[test]
+fn shows_every_matching_rule_and_the_winner() {
+    let temp = explain_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["explain", "src/app.rs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("loq.toml"))
+        .stdout(predicate::str::contains(
+            "rules[0]  max-lines=800  (match: **/*.rs)",
+        ))
+        .stdout(predicate::str::contains(
+            "rules[1]  max-lines=2  (match: src/app.rs)",
+        ))
+        .stdout(predicate::str::contains("← wins (last match)"))
+        .stdout(predicate::str::contains("max-lines=2 (match: src/app.rs)"))
+        .stdout(predicate::str::contains("(1 over)"));
+}
+
+#[test]
+fn falls_back_to_the_default_limit() {
+    let temp = explain_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["explain", "notes.txt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("none matched"))
+        .stdout(predicate::str::contains("max-lines=500 (default)"))
+        .stdout(predicate::str::contains("(499 left)"));
+}
+
+#[test]
+fn names_the_exclude_pattern() {
+    let temp = explain_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["explain", "gen/out.rs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "exclude pattern 'gen/**' in loq.toml",
+        ))
+        .stdout(predicate::str::contains("not checked (excluded)"));
+}
+
+#[test]
+fn json_output_has_the_full_trace() {
+    let temp = explain_project();
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["explain", "src/app.rs", "--output-format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["path"], "src/app.rs");
+    assert_eq!(parsed["match_key"], "src/app.rs");
+    assert_eq!(parsed["config"], "loq.toml");
+    assert!(parsed["excluded"].is_null());
+    assert_eq!(parsed["matched_rules"][0]["winner"], false);
+    assert_eq!(parsed["matched_rules"][1]["index"], 1);
+    assert_eq!(parsed["matched_rules"][1]["winner"], true);
+    assert_eq!(parsed["limits"][0]["max"], 2);
+    assert_eq!(parsed["rule"], "src/app.rs");
+    assert_eq!(parsed["result"]["status"], "violation");
+    assert_eq!(parsed["result"]["measurements"][0]["actual"], 3);
+    assert_eq!(parsed["result"]["measurements"][0]["status"], "violation");
+}
+
+#[test]
+fn json_output_names_gitignore_pattern() {
+    let temp = explain_project();
+    write_file(temp.path(), ".gitignore", "build/\n");
+    write_file(temp.path(), "build/out.txt", "a\n");
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["explain", "build/out.txt", "--output-format", "json"])
+        .output()
+        .unwrap();
+
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["excluded"]["reason"], "gitignore");
+    assert_eq!(parsed["excluded"]["pattern"], "build/");
+    assert_eq!(parsed["excluded"]["source"], ".gitignore");
+    assert!(parsed["result"].is_null());
+}
+
+#[test]
+fn explicit_config_is_used() {
+    let temp = explain_project();
+    write_file(temp.path(), "ci/strict.toml", "default_max_lines = 1\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["--config", "ci/strict.toml", "explain", "notes.txt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ci/strict.toml"))
+        .stdout(predicate::str::contains("max-lines=1 (default)"));
+}
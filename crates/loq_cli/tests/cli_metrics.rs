@@ -0,0 +1,362 @@
+//! Integration tests for the size metrics a rule can limit.
+
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+fn write_file(dir: &Path, path: &str, contents: &str) {
+    let full = dir.join(path);
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(full, contents).unwrap();
+}
+
+/// Creates a project with `config` as its `loq.toml` and `notes.txt`.
+fn notes_project(config: &str, contents: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    write_file(temp.path(), "loq.toml", config);
+    write_file(temp.path(), "notes.txt", contents);
+    temp
+}
+
+fn limits_project(rule: &str) -> TempDir {
+    let config = format!("[[rules]]\npath = \"notes.txt\"\n{rule}");
+    // 4 lines and 8 bytes.
+    notes_project(&config, "a\nb\nc\nd\n")
+}
+
+#[test]
+fn byte_limits_are_shown_in_human_units() {
+    let temp = TempDir::new().unwrap();
+    write_file(
+        temp.path(),
+        "loq.toml",
+        "[[rules]]\npath = \"*.svg\"\nmax_bytes = \"1 KiB\"\n",
+    );
+    std::fs::write(temp.path().join("logo.svg"), "x".repeat(1536)).unwrap();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1.5 KiB"))
+        .stdout(predicate::str::contains("1 KiB"))
+        .stdout(predicate::str::contains("logo.svg"));
+}
+
+#[test]
+fn byte_sizes_that_round_alike_are_shown_exactly() {
+    let temp = TempDir::new().unwrap();
+    write_file(temp.path(), "loq.toml", "default_max_bytes = 1024\n");
+    std::fs::write(temp.path().join("a.txt"), "x".repeat(1025)).unwrap();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1_025 B"))
+        .stdout(predicate::str::contains("1_024 B"));
+}
+
+#[test]
+fn json_reports_bytes_fields() {
+    let temp = TempDir::new().unwrap();
+    write_file(temp.path(), "loq.toml", "default_max_bytes = 10\n");
+    std::fs::write(temp.path().join("a.txt"), "hello world\n").unwrap();
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let measurement = &json["violations"][0]["measurements"][0];
+
+    assert_eq!(measurement["metric"], "bytes");
+    assert_eq!(measurement["actual"], 12);
+    assert_eq!(measurement["max"], 10);
+    assert_eq!(measurement["approximate"], false);
+    assert_eq!(
+        json["violations"][0]["measurements"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn near_limit_headroom_is_shown_in_human_units() {
+    let temp = TempDir::new().unwrap();
+    write_file(
+        temp.path(),
+        "loq.toml",
+        "default_max_bytes = \"4 KiB\"\nwarn_at = 0.5\n",
+    );
+    std::fs::write(temp.path().join("a.txt"), "x".repeat(3072)).unwrap();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 KiB left"));
+}
+
+#[test]
+fn blank_lines_do_not_count_against_code_line_limits() {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(temp.path().join("loq.toml"), "default_max_code_lines = 3\n").unwrap();
+    std::fs::write(temp.path().join("spaced.rs"), "a\n\n\nb\n\n\nc\n").unwrap();
+    std::fs::write(temp.path().join("dense.rs"), "a\nb\nc\nd\n").unwrap();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("code lines"))
+        .stdout(predicate::str::contains("dense.rs"))
+        .stdout(predicate::str::contains("spaced.rs").not());
+}
+
+#[test]
+fn json_reports_code_line_fields() {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(
+        temp.path().join("loq.toml"),
+        "[[rules]]\npath = \"*.py\"\nmax_code_lines = 1\n",
+    )
+    .unwrap();
+    std::fs::write(temp.path().join("a.py"), "x = 1\n\ny = 2\n").unwrap();
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let measurements = json["violations"][0]["measurements"].as_array().unwrap();
+
+    assert_eq!(measurements.len(), 1, "{measurements:?}");
+    assert_eq!(measurements[0]["metric"], "code_lines");
+    assert_eq!(measurements[0]["actual"], 2);
+    assert_eq!(measurements[0]["max"], 1);
+}
+
+#[test]
+fn doc_comments_do_not_count_against_source_line_limits() {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(
+        temp.path().join("loq.toml"),
+        "[[rules]]\npath = \"*.rs\"\nmax_source_lines = 3\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp.path().join("documented.rs"),
+        "//! Module docs.\n\n/// Adds one.\n/// More docs.\nfn add(x: u8) -> u8 {\n    x + 1\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp.path().join("dense.rs"),
+        "fn a() {}\nfn b() {}\nfn c() {}\nlet s = \"// not a comment\";\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("source lines"))
+        .stdout(predicate::str::contains("dense.rs"))
+        .stdout(predicate::str::contains("documented.rs").not());
+}
+
+#[test]
+fn json_reports_source_line_fields() {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(
+        temp.path().join("loq.toml"),
+        "default_max_source_lines = 1\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp.path().join("a.py"),
+        "\"\"\"Docs.\"\"\"\n# comment\nx = 1\ny = 2\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let measurement = &json["violations"][0]["measurements"][0];
+
+    assert_eq!(measurement["metric"], "source_lines");
+    assert_eq!(measurement["actual"], 2);
+    assert_eq!(measurement["max"], 1);
+}
+
+#[test]
+fn long_lines_are_listed_in_text_output() {
+    let temp = notes_project(
+        "default_max_line_length = 5\n",
+        "short\nmuch too long\nok\nalso too long",
+    );
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("line length"))
+        .stdout(predicate::str::contains("lines 2, 4"));
+}
+
+#[test]
+fn long_lines_are_listed_in_json_output() {
+    let temp = notes_project(
+        "[[rules]]\npath = \"notes.txt\"\nmax_line_length = 5\n",
+        "much too long\nok\n",
+    );
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let measurement = &json["violations"][0]["measurements"][0];
+    assert_eq!(measurement["metric"], "line_length");
+    assert_eq!(measurement["actual"], 13);
+    assert_eq!(measurement["max"], 5);
+    assert_eq!(measurement["long_lines"], serde_json::json!([1]));
+    assert_eq!(measurement["long_line_count"], 1);
+}
+
+#[test]
+fn only_the_first_long_lines_are_listed() {
+    let temp = notes_project("default_max_line_length = 1\n", &"xx\n".repeat(8));
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let measurement = &json["violations"][0]["measurements"][0];
+    assert_eq!(
+        measurement["long_lines"],
+        serde_json::json!([1, 2, 3, 4, 5])
+    );
+    assert_eq!(measurement["long_line_count"], 8);
+}
+
+#[test]
+fn long_lines_not_listed_are_counted_in_text_output() {
+    let temp = notes_project("default_max_line_length = 1\n", &"xx\n".repeat(8));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("lines 1, 2, 3, 4, 5 and 3 more"));
+}
+
+#[test]
+fn width_counts_wide_characters_as_two_columns() {
+    // Four CJK characters: 8 columns wide, 12 bytes long.
+    let width = notes_project("default_max_line_length = 10\n", "你好世界\n");
+    cargo_bin_cmd!("loq")
+        .current_dir(width.path())
+        .args(["check"])
+        .assert()
+        .success();
+
+    let bytes = notes_project(
+        "default_max_line_length = 10\nline_length_unit = \"bytes\"\n",
+        "你好世界\n",
+    );
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(bytes.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["violations"][0]["measurements"][0]["actual"], 12);
+}
+
+#[test]
+fn each_limit_is_reported_with_its_result() {
+    let temp = limits_project("max_lines = 3\nmax_bytes = 6\n");
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let violations = json["violations"].as_array().unwrap();
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    let measurements = violations[0]["measurements"].as_array().unwrap();
+    assert_eq!(measurements[0]["metric"], "lines");
+    assert_eq!(measurements[0]["actual"], 4);
+    assert_eq!(measurements[1]["metric"], "bytes");
+    assert_eq!(measurements[1]["actual"], 8);
+    assert_eq!(json["summary"]["violations"], 1);
+}
+
+#[test]
+fn limits_within_budget_are_reported_as_passed() {
+    let temp = limits_project("max_lines = 10\nmax_bytes = 6\n");
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let measurements = json["violations"][0]["measurements"].as_array().unwrap();
+    assert_eq!(measurements[0]["metric"], "lines");
+    assert_eq!(measurements[0]["passed"], true);
+    assert_eq!(measurements[1]["metric"], "bytes");
+    assert_eq!(measurements[1]["passed"], false);
+}
+
+#[test]
+fn file_within_every_limit_passes() {
+    let temp = limits_project("max_lines = 10\nmax_bytes = 100\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn explain_lists_every_measurement() {
+    let temp = limits_project("max_lines = 10\nmax_bytes = 6\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["explain", "notes.txt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("max-lines=10, max-bytes=6"))
+        .stdout(predicate::str::contains("/ 10 lines"))
+        .stdout(predicate::str::contains("/ 6 bytes"));
+}
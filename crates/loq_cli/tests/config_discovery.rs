@@ -1,6 +1,10 @@
+//! Integration tests for finding the config: nested configs, `--config`,
+//! `LOQ_CONFIG` and loq tables in `pyproject.toml` and `Cargo.toml`.
+
 use std::path::Path;
 
 use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
 use tempfile::TempDir;
 
 fn write_file(dir: &Path, path: &str, contents: &str) {
@@ -15,6 +19,32 @@ fn repeat_lines(count: usize) -> String {
     "line\n".repeat(count)
 }
 
+/// A repo with a lenient `loq.toml` and a strict CI config outside it.
+fn explicit_project() -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "repo/loq.toml", "default_max_lines = 100\n");
+    write_file(
+        root,
+        "ci/strict.toml",
+        "default_max_lines = 10\n\n[[rules]]\npath = \"src/allowed.txt\"\nmax_lines = 50\n",
+    );
+    write_file(root, "repo/src/big.txt", &repeat_lines(20));
+    write_file(root, "repo/src/allowed.txt", &repeat_lines(20));
+    temp
+}
+
+const PYPROJECT: &str = r#"[project]
+name = "demo"
+version = "0.1.0"
+
+[tool.ruff]
+line-length = 88
+
+[tool.loq]
+default_max_lines = 20
+"#;
+
 #[test]
 fn baseline_from_subdir_edits_root_config() {
     let temp = TempDir::new().unwrap();
@@ -126,3 +156,311 @@ fn tighten_from_subdir_preserves_sibling_rule() {
         "tighten changed a sibling rule: {config}"
     );
 }
+
+#[test]
+fn check_from_root_applies_nested_package_configs() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    std::fs::write(root.join("loq.toml"), "default_max_lines = 500\n").unwrap();
+    write_file(root, "packages/web/loq.toml", "default_max_lines = 5\n");
+    write_file(root, "packages/web/app.txt", &repeat_lines(6));
+    write_file(root, "packages/api/handler.txt", &repeat_lines(6));
+
+    let assert = cargo_bin_cmd!("loq")
+        .current_dir(root)
+        .args(["check", "--output-format", "json"])
+        .assert()
+        .failure();
+
+    let output: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(output["summary"]["violations"], 1);
+    assert_eq!(output["violations"][0]["path"], "packages/web/app.txt");
+    assert_eq!(output["violations"][0]["measurements"][0]["max"], 5);
+}
+
+#[test]
+fn check_uses_explicit_config_with_cwd_relative_paths() {
+    let temp = explicit_project();
+    let repo = temp.path().join("repo");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(&repo)
+        .args(["check"])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(&repo)
+        .args(["--config", "../ci/strict.toml", "check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("big.txt"))
+        .stdout(predicate::str::contains("allowed.txt").not())
+        .stdout(predicate::str::contains("1 violation"));
+}
+
+#[test]
+fn config_flag_is_accepted_after_subcommand() {
+    let temp = explicit_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path().join("repo"))
+        .args(["check", "--config", "../ci/strict.toml", "src"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation"));
+}
+
+#[test]
+fn env_var_selects_config() {
+    let temp = explicit_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path().join("repo"))
+        .env("LOQ_CONFIG", "../ci/strict.toml")
+        .args(["check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation"));
+}
+
+#[test]
+fn flag_overrides_env_var() {
+    let temp = explicit_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path().join("repo"))
+        .env("LOQ_CONFIG", "../ci/strict.toml")
+        .args(["--config", "loq.toml", "check"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn missing_explicit_config_is_an_error() {
+    let temp = explicit_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path().join("repo"))
+        .args(["--config", "../ci/missing.toml", "check"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("missing.toml"));
+}
+
+#[test]
+fn baseline_edits_explicit_config() {
+    let temp = explicit_project();
+    let root = temp.path();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root.join("repo"))
+        .args(["--config", "../ci/strict.toml", "baseline"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added 1 file"));
+
+    let strict = std::fs::read_to_string(root.join("ci/strict.toml")).unwrap();
+    assert!(strict.contains("\"src/big.txt\""), "config was: {strict}");
+    let repo_config = std::fs::read_to_string(root.join("repo/loq.toml")).unwrap();
+    assert_eq!(repo_config, "default_max_lines = 100\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root.join("repo"))
+        .args(["--config", "../ci/strict.toml", "check"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn relax_and_tighten_edit_explicit_config() {
+    let temp = explicit_project();
+    let root = temp.path();
+    let repo = root.join("repo");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(&repo)
+        .env("LOQ_CONFIG", "../ci/strict.toml")
+        .args(["relax", "src/big.txt"])
+        .assert()
+        .success();
+    let strict = std::fs::read_to_string(root.join("ci/strict.toml")).unwrap();
+    assert!(strict.contains("\"src/big.txt\""), "config was: {strict}");
+
+    write_file(root, "repo/src/big.txt", &repeat_lines(5));
+    cargo_bin_cmd!("loq")
+        .current_dir(&repo)
+        .env("LOQ_CONFIG", "../ci/strict.toml")
+        .args(["tighten"])
+        .assert()
+        .success();
+    let strict = std::fs::read_to_string(root.join("ci/strict.toml")).unwrap();
+    assert!(!strict.contains("\"src/big.txt\""), "config was: {strict}");
+}
+
+#[test]
+fn init_writes_explicit_config_path() {
+    let temp = TempDir::new().unwrap();
+    std::fs::create_dir(temp.path().join("ci")).unwrap();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["--config", "ci/loq.toml", "init"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created ci/loq.toml"));
+
+    assert!(temp.path().join("ci/loq.toml").is_file());
+    assert!(!temp.path().join("loq.toml").exists());
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["--config", "ci/loq.toml", "init"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("ci/loq.toml already exists"));
+}
+
+#[test]
+fn check_uses_pyproject_tool_loq() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "pyproject.toml", PYPROJECT);
+    write_file(root, "big.py", &repeat_lines(21));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root)
+        .args(["check", "big.py"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation"));
+}
+
+#[test]
+fn pyproject_without_tool_loq_is_not_a_config() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "loq.toml", "default_max_lines = 10\n");
+    write_file(root, "pkg/pyproject.toml", "[project]\nname = \"pkg\"\n");
+    write_file(root, "pkg/big.py", &repeat_lines(11));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root)
+        .args(["check", "pkg/big.py"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation"));
+}
+
+#[test]
+fn loq_toml_wins_over_host_manifests() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "loq.toml", "default_max_lines = 30\n");
+    write_file(root, "pyproject.toml", PYPROJECT);
+    write_file(root, "big.py", &repeat_lines(21));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root)
+        .args(["check", "big.py"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn check_uses_cargo_workspace_metadata() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        root,
+        "Cargo.toml",
+        "[workspace]\nmembers = []\n\n[workspace.metadata.loq]\ndefault_max_lines = 10\n",
+    );
+    write_file(root, "src/lib.rs", &repeat_lines(11));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root)
+        .args(["check", "src/lib.rs"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation"));
+}
+
+#[test]
+fn pyproject_unknown_key_reports_file_location() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        root,
+        "pyproject.toml",
+        "[project]\nname = \"demo\"\n\n[tool.loq]\ndefault_max_line = 10\n",
+    );
+    write_file(root, "a.py", "a\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root)
+        .args(["check", "a.py"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "pyproject.toml:5:1 - unknown key 'default_max_line'",
+        ));
+}
+
+#[test]
+fn baseline_edits_pyproject_in_place() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "pyproject.toml", PYPROJECT);
+    write_file(root, "legacy.py", &repeat_lines(22));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root)
+        .args(["baseline"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added 1 file"));
+
+    assert!(!root.join("loq.toml").exists());
+    let content = std::fs::read_to_string(root.join("pyproject.toml")).unwrap();
+    assert!(content.starts_with(PYPROJECT), "pyproject was: {content}");
+    assert!(
+        content.contains("[[tool.loq.rules]]\npath = \"legacy.py\"\nmax_lines = 22\n"),
+        "pyproject was: {content}"
+    );
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root)
+        .args(["check"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn relax_edits_cargo_package_metadata() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    let manifest = "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[package.metadata.loq]\ndefault_max_lines = 10\n\n[dependencies]\n";
+    write_file(root, "Cargo.toml", manifest);
+    write_file(root, "src/lib.rs", &repeat_lines(11));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(root)
+        .args(["relax", "src/lib.rs"])
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    assert!(
+        content.contains("[dependencies]"),
+        "manifest was: {content}"
+    );
+    assert!(
+        content.contains("[[package.metadata.loq.rules]]"),
+        "manifest was: {content}"
+    );
+    assert!(
+        content.contains("\"src/lib.rs\""),
+        "manifest was: {content}"
+    );
+}
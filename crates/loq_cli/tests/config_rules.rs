@@ -0,0 +1,311 @@
+//! Integration tests for how a config picks files and limits: command-line
+//! overrides, the `include` allow-list, `pattern_syntax` and rule excludes.
+
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+fn write_file(dir: &Path, path: &str, contents: &str) {
+    let full = dir.join(path);
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(full, contents).unwrap();
+}
+
+fn repeat_lines(count: usize) -> String {
+    "line\n".repeat(count)
+}
+
+fn overrides_project() -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "loq.toml", "default_max_lines = 100\n");
+    write_file(root, "src/a.txt", &repeat_lines(20));
+    write_file(root, "gen/b.txt", &repeat_lines(20));
+    temp
+}
+
+fn include_project(config: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "loq.toml", config);
+    write_file(root, "src/big.txt", &repeat_lines(20));
+    write_file(root, "prompts/small.md", &repeat_lines(5));
+    write_file(root, "docs/big.txt", &repeat_lines(20));
+    temp
+}
+
+fn pattern_project(config: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(root, "loq.toml", config);
+    for path in [
+        "build/out.txt",
+        "pkg/build/out.txt",
+        "dist/app.txt",
+        "web/dist/app.txt",
+        "src/main.txt",
+    ] {
+        write_file(root, path, &repeat_lines(20));
+    }
+    temp
+}
+
+fn rule_exclude_project(rule: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        root,
+        "loq.toml",
+        &format!("default_max_lines = 100\n\n[[rules]]\n{rule}\nmax_lines = 10\n"),
+    );
+    write_file(root, "app/models.py", &repeat_lines(20));
+    write_file(root, "app/migrations/0001_initial.py", &repeat_lines(50));
+    temp
+}
+
+#[test]
+fn default_max_lines_overrides_config() {
+    let temp = overrides_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success();
+
+    // The cached run above must not hide violations under the stricter limit.
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--default-max-lines", "10"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violations"));
+}
+
+#[test]
+fn extend_exclude_adds_to_config_excludes() {
+    let temp = overrides_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args([
+            "check",
+            "--default-max-lines",
+            "10",
+            "--extend-exclude",
+            "gen/**",
+        ])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("a.txt"))
+        .stdout(predicate::str::contains("b.txt").not())
+        .stdout(predicate::str::contains("1 violation"));
+}
+
+#[test]
+fn set_adds_rules_and_scalars() {
+    let temp = overrides_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args([
+            "check",
+            "--set",
+            "default_max_lines=10",
+            "--set",
+            "rules=[{ path = \"gen/**\", max_lines = 50 }]",
+        ])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation"))
+        .stdout(predicate::str::contains("b.txt").not());
+}
+
+#[test]
+fn overrides_apply_to_nested_configs() {
+    let temp = overrides_project();
+    write_file(temp.path(), "pkg/loq.toml", "default_max_lines = 100\n");
+    write_file(temp.path(), "pkg/c.txt", &repeat_lines(20));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "pkg", "--default-max-lines", "10"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation"));
+}
+
+#[test]
+fn invalid_set_is_reported() {
+    let temp = overrides_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--set", "default_max_line=10"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid override 'default_max_line=10': unknown key 'default_max_line'",
+        ));
+}
+
+#[test]
+fn files_outside_include_are_not_checked() {
+    let temp = include_project("default_max_lines = 10\ninclude = [\"src/**\", \"prompts/**\"]\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("src/big.txt"))
+        .stdout(predicate::str::contains("docs/big.txt").not())
+        .stdout(predicate::str::contains("\"files_checked\": 2"));
+}
+
+#[test]
+fn explicit_paths_outside_include_are_skipped() {
+    let temp = include_project("default_max_lines = 10\ninclude = [\"src/**\"]\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "docs/big.txt"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn exclude_still_applies_within_include() {
+    let temp = include_project(
+        "default_max_lines = 10\ninclude = [\"src/**\"]\nexclude = [\"src/big.txt\"]\n",
+    );
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn nested_config_applies_its_own_include() {
+    let temp = include_project("default_max_lines = 100\n");
+    write_file(
+        temp.path(),
+        "pkg/loq.toml",
+        "default_max_lines = 10\ninclude = [\"lib/**\"]\n",
+    );
+    write_file(temp.path(), "pkg/lib/big.txt", &repeat_lines(20));
+    write_file(temp.path(), "pkg/scripts/big.txt", &repeat_lines(20));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("pkg/lib/big.txt"))
+        .stdout(predicate::str::contains("pkg/scripts/big.txt").not());
+}
+
+#[test]
+fn gitignore_excludes_match_directories_and_anchor() {
+    let temp = pattern_project(
+        "default_max_lines = 10\npattern_syntax = \"gitignore\"\nexclude = [\"build/\", \"/dist\"]\n",
+    );
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("build/out.txt").not())
+        .stdout(predicate::str::contains("\"dist/app.txt\"").not())
+        .stdout(predicate::str::contains("web/dist/app.txt"))
+        .stdout(predicate::str::contains("src/main.txt"));
+}
+
+#[test]
+fn glob_syntax_is_the_default() {
+    let temp = pattern_project("default_max_lines = 10\nexclude = [\"build/\", \"/dist\"]\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("\"build/out.txt\""))
+        .stdout(predicate::str::contains("\"dist/app.txt\""));
+}
+
+#[test]
+fn gitignore_rule_names_match_at_any_depth() {
+    let temp = pattern_project(
+        "default_max_lines = 10\npattern_syntax = \"gitignore\"\n\n[[rules]]\npath = \"build\"\nmax_lines = 50\n\n[[rules]]\npath = [\"dist/\", \"src\"]\nmax_lines = 50\n",
+    );
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn baseline_anchors_root_level_files() {
+    let temp = TempDir::new().unwrap();
+    write_file(
+        temp.path(),
+        "loq.toml",
+        "default_max_lines = 10\npattern_syntax = \"gitignore\"\n",
+    );
+    write_file(temp.path(), "big.txt", &repeat_lines(20));
+    write_file(temp.path(), "pkg/big.txt", &repeat_lines(5));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["baseline"])
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(temp.path().join("loq.toml")).unwrap();
+    assert!(content.contains("path = \"/big.txt\""), "{content}");
+
+    write_file(temp.path(), "pkg/big.txt", &repeat_lines(15));
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("big.txt"));
+}
+
+#[test]
+fn negated_path_falls_back_to_default() {
+    let temp = rule_exclude_project("path = [\"**/*.py\", \"!**/migrations/**\"]");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("app/models.py"))
+        .stdout(predicate::str::contains("0001_initial.py").not())
+        .stdout(predicate::str::contains("\"rule\": \"**/*.py\""));
+}
+
+#[test]
+fn rule_exclude_falls_back_to_default() {
+    let temp = rule_exclude_project("path = \"**/*.py\"\nexclude = \"**/migrations/**\"");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("app/models.py"))
+        .stdout(predicate::str::contains("0001_initial.py").not());
+}
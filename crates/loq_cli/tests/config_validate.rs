@@ -0,0 +1,245 @@
+//! Integration tests for config diagnostics, `loq config validate` and
+//! `loq config schema`.
+
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+fn write_file(dir: &Path, path: &str, contents: &str) {
+    let full = dir.join(path);
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(full, contents).unwrap();
+}
+
+const STALE_CONFIG: &str = r#"default_max_lines = 500
+exclude = ["vendor/**"]
+
+[[rules]]
+path = "docs/**"
+max_lines = 100
+
+[[rules]]
+path = "**/*.md"
+max_lines = 200
+
+[[rules]]
+path = "src/deleted.rs"
+max_lines = 900
+
+[[rules]]
+path = "src/small.rs"
+max_lines = 400
+"#;
+
+fn validate_project(config: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    write_file(temp.path(), "loq.toml", config);
+    write_file(temp.path(), "docs/guide.md", "a\n");
+    write_file(temp.path(), "src/small.rs", "a\n");
+    temp
+}
+
+const INVALID_CONFIG: &str =
+    "default_max_line = 500\nexclude = [\"bad/[**\"]\n\n[[rules]]\npath = \"src/**\"\n";
+
+fn invalid_project() -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    std::fs::write(root.join("loq.toml"), INVALID_CONFIG).unwrap();
+    std::fs::write(root.join("a.txt"), "a\n").unwrap();
+    temp
+}
+
+#[test]
+fn clean_config_passes() {
+    let temp = validate_project("default_max_lines = 500\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["config", "validate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("loq.toml: no problems found"));
+}
+
+#[test]
+fn stale_rules_and_excludes_are_reported() {
+    let temp = validate_project(STALE_CONFIG);
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["config", "validate"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "exclude 'vendor/**': matches no files",
+        ))
+        .stdout(predicate::str::contains(
+            "rules[0] 'docs/**': shadowed by rules[1] '**/*.md'",
+        ))
+        .stdout(predicate::str::contains(
+            "rules[2] 'src/deleted.rs': file no longer exists",
+        ))
+        .stdout(predicate::str::contains(
+            "rules[3] 'src/small.rs': allows 400 lines, no more than the default of 500",
+        ))
+        .stdout(predicate::str::contains("4 problems"));
+}
+
+#[test]
+fn json_output_lists_lints() {
+    let temp = validate_project(STALE_CONFIG);
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["config", "validate", "--output-format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["config"], "loq.toml");
+    let lints = parsed["lints"].as_array().unwrap();
+    let kinds = lints
+        .iter()
+        .map(|lint| lint["kind"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            "unmatched_exclude",
+            "shadowed_rule",
+            "missing_exact_path",
+            "redundant_exact_path"
+        ]
+    );
+    assert_eq!(lints[1]["rule"], 0);
+    assert_eq!(lints[1]["shadowed_by"], 1);
+}
+
+#[test]
+fn nested_configs_keep_their_files() {
+    let temp = validate_project(
+        "default_max_lines = 500\n\n[[rules]]\npath = \"pkg/**\"\nmax_lines = 100\n",
+    );
+    write_file(temp.path(), "pkg/loq.toml", "default_max_lines = 50\n");
+    write_file(temp.path(), "pkg/lib.rs", "a\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["config", "validate"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "rules[0] 'pkg/**': matches no files",
+        ));
+}
+
+#[test]
+fn config_errors_are_reported_before_linting() {
+    let temp = validate_project("default_max_line = 500\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["config", "validate"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unknown key 'default_max_line'"));
+}
+
+#[test]
+fn missing_config_is_an_error() {
+    let temp = TempDir::new().unwrap();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["config", "validate"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("no loq config found"));
+}
+
+#[test]
+fn schema_is_printed_as_json() {
+    let output = cargo_bin_cmd!("loq")
+        .args(["config", "schema"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(schema["type"], "object");
+    assert!(schema["properties"]["default_max_lines"].is_object());
+    assert!(schema["definitions"]["rule"]["properties"]["max_tokens"].is_object());
+}
+
+#[test]
+fn every_problem_is_shown_with_a_code_frame() {
+    let temp = invalid_project();
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "loq.toml:1:1 - unknown key 'default_max_line'",
+        ))
+        .stderr(predicate::str::contains("1 | default_max_line = 500"))
+        .stderr(predicate::str::contains(
+            "= help: did you mean 'default_max_lines'?",
+        ))
+        .stderr(predicate::str::contains(
+            "loq.toml:2:12 - invalid glob 'bad/[**'",
+        ))
+        .stderr(predicate::str::contains(
+            "loq.toml:5:1 - rule for 'src/**' must set at least one of max_lines, max_code_lines, max_source_lines, max_tokens, max_bytes or max_line_length",
+        ))
+        .stderr(predicate::str::contains("3 problems found in"));
+}
+
+#[test]
+fn json_output_lists_diagnostics_as_data() {
+    let temp = invalid_project();
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let errors = parsed["config_errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0]["kind"], "unknown_key");
+    assert_eq!(errors[0]["key"], "default_max_line");
+    assert_eq!(errors[0]["suggestion"], "default_max_lines");
+    assert_eq!(errors[0]["line"], 1);
+    assert_eq!(errors[0]["column"], 1);
+    assert_eq!(errors[0]["start"], 0);
+    assert_eq!(errors[0]["end"], 16);
+    assert_eq!(errors[1]["kind"], "invalid_glob");
+    assert_eq!(errors[1]["pattern"], "bad/[**");
+    assert_eq!(errors[2]["kind"], "invalid_value");
+    assert!(errors[0]["path"].as_str().unwrap().ends_with("loq.toml"));
+}
+
+#[test]
+fn syntax_errors_point_at_the_source() {
+    let temp = TempDir::new().unwrap();
+    write_file(temp.path(), "loq.toml", "default_max_lines =\n");
+    write_file(temp.path(), "a.txt", "a\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("loq.toml:1:"))
+        .stderr(predicate::str::contains("1 | default_max_lines ="));
+}
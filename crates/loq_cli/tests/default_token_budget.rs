@@ -1,7 +1,53 @@
+use std::fmt::Write;
+
 use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 use tempfile::TempDir;
 
+fn prompt_project(config: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(temp.path().join("loq.toml"), config).unwrap();
+    // 30 bytes and 10 characters.
+    std::fs::write(temp.path().join("prompt.md"), "你好世界你好世界你好").unwrap();
+    temp
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let mut bits = 0u32;
+        for (index, byte) in chunk.iter().enumerate() {
+            bits |= u32::from(*byte) << (16 - 8 * index);
+        }
+        for index in 0..4 {
+            if index <= chunk.len() {
+                out.push(ALPHABET[(bits >> (18 - 6 * index) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Sets up a project whose vocabulary has every single byte and no merges, so
+/// each byte is one token.
+fn tokenizer_project(config: &str) -> TempDir {
+    let mut vocabulary = String::new();
+    for byte in 0..=255u8 {
+        writeln!(vocabulary, "{} {byte}", encode_base64(&[byte])).unwrap();
+    }
+    let temp = TempDir::new().unwrap();
+    std::fs::write(temp.path().join("loq.toml"), config).unwrap();
+    std::fs::write(temp.path().join("vocab.tiktoken"), vocabulary).unwrap();
+    std::fs::write(temp.path().join("prompt.md"), "hello world\n").unwrap();
+    temp
+}
+
+const TOKENIZER_CONFIG: &str =
+    "tokenizer = \"vocab.tiktoken\"\nexclude = [\"vocab.tiktoken\"]\ndefault_max_tokens = 10\n";
+
 #[test]
 fn default_max_tokens_flags_unmatched_file() {
     let temp = TempDir::new().unwrap();
@@ -61,7 +107,132 @@ fn all_commands_reject_both_default_budgets() {
             .assert()
             .code(2)
             .stderr(predicate::str::contains(
-                "only one of default_max_lines or default_max_tokens",
+                "only one of default_max_lines, default_max_code_lines, default_max_source_lines, default_max_tokens, default_max_bytes or default_max_line_length",
             ));
     }
 }
+
+#[test]
+fn chars_per_token_counts_characters() {
+    let temp = prompt_project("default_max_tokens = 7\nchars_per_token = 1\n");
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let measurement = &json["violations"][0]["measurements"][0];
+
+    assert_eq!(measurement["actual"], 10);
+    assert_eq!(measurement["approximate"], true);
+    assert_eq!(
+        measurement["estimate"],
+        serde_json::json!({ "chars_per_token": 1.0 })
+    );
+}
+
+#[test]
+fn rule_estimate_overrides_config_estimate() {
+    let temp = prompt_project(
+        "default_max_tokens = 7\nchars_per_token = 1\n\n[[rules]]\npath = \"*.md\"\nmax_tokens = 7\ntokens_per_word = 2\n",
+    );
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let violations = json["violations"].as_array().unwrap();
+
+    assert!(violations.is_empty(), "{violations:?}");
+}
+
+#[test]
+fn verbose_output_shows_the_estimate() {
+    let temp = prompt_project("default_max_tokens = 7\nbytes_per_token = 3.2\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "-v"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "max-tokens=7 bytes-per-token=3.2 (default)",
+        ));
+}
+
+#[test]
+fn changing_the_estimate_invalidates_the_cache() {
+    let temp = prompt_project("default_max_tokens = 7\n");
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .failure();
+
+    std::fs::write(
+        temp.path().join("loq.toml"),
+        "default_max_tokens = 7\nbytes_per_token = 10\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .success();
+}
+
+#[test]
+fn tokenizer_counts_exact_tokens() {
+    let temp = tokenizer_project(TOKENIZER_CONFIG);
+
+    let output = cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check", "--output-format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let violation = &json["violations"][0];
+
+    assert_eq!(violation["path"], "prompt.md");
+    assert_eq!(violation["measurements"][0]["actual"], 12);
+    assert_eq!(violation["measurements"][0]["approximate"], false);
+}
+
+#[test]
+fn exact_token_counts_are_not_marked_approximate() {
+    let temp = tokenizer_project(TOKENIZER_CONFIG);
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("12"))
+        .stdout(predicate::str::contains("~").not());
+}
+
+#[test]
+fn without_a_tokenizer_tokens_are_estimated() {
+    let temp = tokenizer_project("exclude = [\"vocab.tiktoken\"]\ndefault_max_tokens = 10\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .success();
+}
+
+#[test]
+fn missing_tokenizer_is_an_error() {
+    let temp = tokenizer_project("tokenizer = \"missing.tiktoken\"\ndefault_max_tokens = 10\n");
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("failed to read tokenizer"));
+}
@@ -0,0 +1,7 @@
+line
+line
+line
+line
+line
+line
+line
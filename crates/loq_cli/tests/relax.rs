@@ -168,3 +168,29 @@ fn orders_paths_and_applies_buffer_for_multiple_files() {
     assert!(content.contains("path = \"src/b.rs\""));
     assert!(content.contains("max_lines = 15"));
 }
+
+#[test]
+fn raises_rules_with_other_keys_in_place() {
+    let temp = TempDir::new().unwrap();
+    let config = r#"default_max_lines = 500
+
+[[rules]]
+path = "src/legacy.rs"
+max_lines = 600
+max_line_length = 20
+"#;
+    write_file(&temp, "loq.toml", config);
+    write_file(&temp, "src/legacy.rs", &repeat_lines(650));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["relax", "--extra", "50"])
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(temp.path().join("loq.toml")).unwrap();
+    assert_eq!(
+        content,
+        config.replace("max_lines = 600", "max_lines = 700")
+    );
+}
@@ -167,6 +167,15 @@ fn fix_guidance_shown_on_violation() {
     insta::assert_snapshot!(stdout);
 }
 
+#[test]
+fn rule_fix_guidance_shown_per_violation() {
+    let (stdout, stderr, success) = run_loq("rule_fix_guidance");
+
+    assert!(!success, "should fail with violations");
+    assert!(stderr.is_empty(), "stderr should be empty");
+    insta::assert_snapshot!(stdout);
+}
+
 #[test]
 fn token_budget() {
     let (stdout, stderr, success) = run_loq("token_budget");
@@ -243,3 +252,12 @@ fn json_output_token_budget() {
         ".version" => "[version]",
     });
 }
+
+#[test]
+fn json_output_rule_fix_guidance() {
+    let (json, success) = run_loq_json("rule_fix_guidance");
+    assert!(!success, "should fail with violations");
+    insta::assert_json_snapshot!(json, {
+        ".version" => "[version]",
+    });
+}
@@ -140,3 +140,31 @@ fn fails_on_invalid_config() {
         .failure()
         .stderr(predicate::str::contains("failed to parse"));
 }
+
+#[test]
+fn keeps_rules_with_other_keys() {
+    let temp = TempDir::new().unwrap();
+    let config = r#"default_max_lines = 500
+
+[[rules]]
+path = "wide.txt"
+max_lines = 600
+max_line_length = 20
+"#;
+    write_file(&temp, "loq.toml", config);
+    write_file(&temp, "wide.txt", &format!("{}\n", "x".repeat(30)));
+
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["tighten"])
+        .assert()
+        .success();
+
+    let updated = std::fs::read_to_string(temp.path().join("loq.toml")).unwrap();
+    assert_eq!(updated, config);
+    cargo_bin_cmd!("loq")
+        .current_dir(temp.path())
+        .args(["check"])
+        .assert()
+        .failure();
+}
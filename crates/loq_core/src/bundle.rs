@@ -0,0 +1,230 @@
+//! Token budgets for bundles of files.
+//!
+//! A `[[bundles]]` entry names files that are loaded into context together,
+//! such as agent instructions and the docs they pull in, and caps their
+//! combined token count. Totals are summed over every file the config governs,
+//! not just those a run checks.
+
+use std::path::Path;
+
+use crate::config::{compile_glob, ConfigError};
+use crate::pattern::{Matcher, PatternSyntax};
+use crate::report::FileOutcome;
+use crate::{Limit, TokenCounter};
+
+/// A validated `[[bundles]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bundle {
+    /// Name shown in findings, if set.
+    pub name: Option<String>,
+    /// Glob patterns for the bundle's files.
+    pub paths: Vec<String>,
+    /// Budget for the members' combined tokens.
+    pub max_tokens: usize,
+}
+
+impl Bundle {
+    /// Compiles the bundle's patterns; its tokens are counted with `counter`.
+    pub(crate) fn compile(
+        self,
+        syntax: PatternSyntax,
+        counter: TokenCounter,
+        source_path: &Path,
+    ) -> Result<CompiledBundle, ConfigError> {
+        let matchers = self
+            .paths
+            .iter()
+            .map(|pattern| compile_glob(pattern, syntax, source_path))
+            .collect::<Result<_, _>>()?;
+        Ok(CompiledBundle {
+            name: self.name.unwrap_or_else(|| self.paths.join(", ")),
+            patterns: self.paths,
+            limit: Limit::tokens(self.max_tokens).counted_by(counter),
+            matchers,
+        })
+    }
+}
+
+/// A bundle with compiled glob matchers.
+#[derive(Debug, Clone)]
+pub struct CompiledBundle {
+    /// Name shown in findings: the configured name or the joined patterns.
+    pub name: String,
+    /// Original glob pattern strings.
+    pub patterns: Vec<String>,
+    /// The combined token budget and how its tokens are counted.
+    pub limit: Limit,
+    matchers: Vec<Matcher>,
+}
+
+impl CompiledBundle {
+    /// Tests if `path` is one of the bundle's files.
+    #[must_use]
+    pub fn matches(&self, path: &str) -> bool {
+        self.matchers.iter().any(|matcher| matcher.is_match(path))
+    }
+}
+
+/// One file's share of a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleMember {
+    /// Path relative to the working directory for display.
+    pub path: String,
+    /// The file's tokens.
+    pub tokens: usize,
+}
+
+/// A bundle over its token budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleViolation {
+    /// The bundle's name.
+    pub name: String,
+    /// The combined token budget.
+    pub limit: Limit,
+    /// Combined tokens of the members.
+    pub actual: usize,
+    /// Every member, largest first.
+    pub members: Vec<BundleMember>,
+}
+
+/// Checks `bundles` against the combined tokens of their members.
+///
+/// Members are the files in `checked` and the `rest` of the tree that a
+/// bundle's patterns match and whose tokens were measured, which leaves out
+/// binary, unreadable and missing files. A file counts toward every bundle it
+/// is in. Only bundles with a checked member are reported, so a partial run
+/// such as `--staged` sees whole bundles without failing on ones it did not
+/// touch.
+#[must_use]
+pub fn check_bundles(
+    bundles: &[CompiledBundle],
+    checked: &[FileOutcome],
+    rest: &[FileOutcome],
+) -> Vec<BundleViolation> {
+    let mut violations = Vec::new();
+    for bundle in bundles {
+        if !checked
+            .iter()
+            .any(|outcome| bundle.matches(&outcome.match_key))
+        {
+            continue;
+        }
+        let mut members = checked
+            .iter()
+            .chain(rest)
+            .filter(|outcome| bundle.matches(&outcome.match_key))
+            .filter_map(|outcome| {
+                Some(BundleMember {
+                    path: outcome.display_path.clone(),
+                    tokens: outcome.bundle_tokens?,
+                })
+            })
+            .collect::<Vec<_>>();
+        let actual = members.iter().map(|member| member.tokens).sum();
+        if actual > bundle.limit.max {
+            members.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+            violations.push(BundleViolation {
+                name: bundle.name.clone(),
+                limit: bundle.limit,
+                actual,
+                members,
+            });
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::OutcomeKind;
+
+    fn bundle(paths: &[&str], max_tokens: usize) -> CompiledBundle {
+        Bundle {
+            name: None,
+            paths: paths.iter().map(ToString::to_string).collect(),
+            max_tokens,
+        }
+        .compile(
+            PatternSyntax::Glob,
+            TokenCounter::default(),
+            Path::new("loq.toml"),
+        )
+        .unwrap()
+    }
+
+    fn outcome(path: &str, bundle_tokens: Option<usize>) -> FileOutcome {
+        FileOutcome {
+            display_path: path.to_string(),
+            match_key: path.to_string(),
+            config_path: None,
+            kind: OutcomeKind::NoLimit,
+            lines: None,
+            bundle_tokens,
+        }
+    }
+
+    #[test]
+    fn members_are_listed_largest_first() {
+        let outcomes = [
+            outcome("AGENTS.md", Some(300)),
+            outcome("docs/architecture/a.md", Some(500)),
+            outcome("docs/architecture/b.md", Some(300)),
+            outcome("src/main.rs", Some(9000)),
+        ];
+
+        let violations = check_bundles(
+            &[bundle(&["AGENTS.md", "docs/architecture/*.md"], 1000)],
+            &outcomes,
+            &[],
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].name, "AGENTS.md, docs/architecture/*.md");
+        assert_eq!(violations[0].actual, 1100);
+        let members = violations[0]
+            .members
+            .iter()
+            .map(|member| (member.path.as_str(), member.tokens))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            [
+                ("docs/architecture/a.md", 500),
+                ("AGENTS.md", 300),
+                ("docs/architecture/b.md", 300),
+            ]
+        );
+    }
+
+    #[test]
+    fn bundles_within_budget_pass() {
+        let outcomes = [outcome("AGENTS.md", Some(300)), outcome("CLAUDE.md", None)];
+
+        assert!(check_bundles(&[bundle(&["*.md"], 300)], &outcomes, &[]).is_empty());
+    }
+
+    #[test]
+    fn files_count_toward_every_bundle_they_are_in() {
+        let bundles = [bundle(&["*.md"], 100), bundle(&["AGENTS.md"], 100)];
+
+        let violations = check_bundles(&bundles, &[outcome("AGENTS.md", Some(150))], &[]);
+
+        assert_eq!(violations.len(), 2, "{violations:?}");
+    }
+
+    #[test]
+    fn bundles_total_unchecked_members() {
+        let bundles = [bundle(&["*.md"], 100), bundle(&["docs/*.md"], 10)];
+        let rest = [
+            outcome("CLAUDE.md", Some(80)),
+            outcome("docs/a.md", Some(20)),
+        ];
+
+        let violations = check_bundles(&bundles, &[outcome("AGENTS.md", Some(30))], &rest);
+
+        assert_eq!(violations.len(), 1, "{violations:?}");
+        assert_eq!(violations[0].actual, 110);
+        assert_eq!(violations[0].members.len(), 2);
+    }
+}
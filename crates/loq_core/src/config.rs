@@ -5,10 +5,18 @@
 
 use std::path::{Path, PathBuf};
 
-use globset::{GlobBuilder, GlobMatcher};
-use thiserror::Error;
+use crate::bundle::{Bundle, CompiledBundle};
+use crate::directory::{CompiledDirectoryRule, DirectoryRule};
+use crate::pattern::{Matcher, PatternSyntax};
+use crate::{Limit, LineLengthUnit, Severity, TokenCounter, TokenEstimate};
 
-use crate::Limit;
+mod error;
+mod patterns;
+
+pub use error::ConfigError;
+pub(crate) use patterns::compile_glob;
+use patterns::compile_patterns;
+pub use patterns::PatternList;
 
 /// Default line limit when no config is provided.
 pub const DEFAULT_MAX_LINES: usize = 500;
@@ -20,8 +28,21 @@ pub const DEFAULT_RESPECT_GITIGNORE: bool = true;
 pub struct Rule {
     /// Glob patterns matched by this rule.
     pub paths: Vec<String>,
-    /// Maximum allowed budget.
-    pub limit: Limit,
+    /// Glob patterns for paths this rule skips, from `!` entries in `path`
+    /// and the rule's `exclude` key.
+    pub exclude: Vec<String>,
+    /// Budgets a matching file must stay within, one per metric.
+    pub limits: Vec<Limit>,
+    /// Whether exceeding a limit fails the check.
+    pub severity: Severity,
+    /// Fraction of a limit above which passing files are flagged as near it.
+    pub warn_at: Option<f64>,
+    /// Guidance template shown with each violation of this rule.
+    pub fix_guidance: Option<String>,
+    /// How this rule's tokens are estimated, overriding the config's.
+    pub token_estimate: Option<TokenEstimate>,
+    /// Whether a matching file is measured together with the files it imports.
+    pub follow_imports: bool,
 }
 
 /// Validated `loq.toml` configuration before glob compilation.
@@ -29,24 +50,186 @@ pub struct Rule {
 pub struct LoqConfig {
     /// Default budget for files not matching any rule.
     pub default_limit: Option<Limit>,
+    /// Severity of the default budget.
+    pub default_severity: Severity,
     /// Whether to skip files matched by `.gitignore`.
     pub respect_gitignore: bool,
+    /// How include, exclude and rule patterns are interpreted.
+    pub pattern_syntax: PatternSyntax,
+    /// Glob patterns for the only files to check; empty checks every file.
+    pub include: Vec<String>,
     /// Glob patterns for files to skip.
     pub exclude: Vec<String>,
     /// Path-specific rules (last match wins).
     pub rules: Vec<Rule>,
+    /// Budgets on the files in matching directories.
+    pub directory_rules: Vec<DirectoryRule>,
+    /// Token budgets on groups of files loaded together.
+    pub bundles: Vec<Bundle>,
+    /// Fraction of a limit above which passing files are flagged as near it.
+    pub warn_at: Option<f64>,
     /// Guidance text shown when violations exist.
     pub fix_guidance: Option<String>,
+    /// Vocabulary file used to count tokens exactly. A relative path is
+    /// relative to the working directory, as config file paths are.
+    pub tokenizer: Option<PathBuf>,
+    /// How tokens are estimated when no tokenizer is set.
+    pub token_estimate: Option<TokenEstimate>,
+    /// How line length limits are measured.
+    pub line_length_unit: LineLengthUnit,
 }
 
 impl Default for LoqConfig {
     fn default() -> Self {
         Self {
             default_limit: Some(Limit::lines(DEFAULT_MAX_LINES)),
+            default_severity: Severity::Error,
             respect_gitignore: DEFAULT_RESPECT_GITIGNORE,
+            pattern_syntax: PatternSyntax::Glob,
+            include: Vec::new(),
             exclude: Vec::new(),
             rules: Vec::new(),
+            directory_rules: Vec::new(),
+            bundles: Vec::new(),
+            warn_at: None,
             fix_guidance: None,
+            tokenizer: None,
+            token_estimate: None,
+            line_length_unit: LineLengthUnit::Width,
+        }
+    }
+}
+
+impl LoqConfig {
+    /// Layers command-line overrides on top of this config.
+    ///
+    /// Follows [`ConfigLayer::inherit`]: set scalars win, excludes are unioned,
+    /// and override rules are appended so they win on a match.
+    #[must_use]
+    pub fn with_overrides(self, overrides: &ConfigLayer) -> Self {
+        let base = ConfigLayer {
+            default_limit: self.default_limit,
+            default_severity: Some(self.default_severity),
+            respect_gitignore: Some(self.respect_gitignore),
+            pattern_syntax: Some(self.pattern_syntax),
+            include: Some(self.include),
+            exclude: self.exclude,
+            rules: self.rules,
+            directory_rules: self.directory_rules,
+            bundles: self.bundles,
+            warn_at: self.warn_at,
+            fix_guidance: self.fix_guidance,
+            tokenizer: self.tokenizer,
+            token_estimate: self.token_estimate,
+            line_length_unit: Some(self.line_length_unit),
+        };
+        overrides.clone().inherit(base).into_config()
+    }
+}
+
+/// One config file's settings before inheritance is resolved.
+///
+/// Unset scalars are `None` so a child layer only overrides what it sets.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayer {
+    /// Default budget, if this layer sets one.
+    pub default_limit: Option<Limit>,
+    /// Severity of the default budget, if this layer sets it.
+    pub default_severity: Option<Severity>,
+    /// Whether to skip `.gitignore`d files, if this layer sets it.
+    pub respect_gitignore: Option<bool>,
+    /// Pattern syntax, if this layer sets it.
+    pub pattern_syntax: Option<PatternSyntax>,
+    /// Glob patterns for the only files to check, if this layer sets them.
+    pub include: Option<Vec<String>>,
+    /// Glob patterns for files to skip.
+    pub exclude: Vec<String>,
+    /// Path-specific rules (last match wins).
+    pub rules: Vec<Rule>,
+    /// Directory budgets.
+    pub directory_rules: Vec<DirectoryRule>,
+    /// Bundle token budgets.
+    pub bundles: Vec<Bundle>,
+    /// Near-limit fraction, if this layer sets it.
+    pub warn_at: Option<f64>,
+    /// Guidance text, if this layer sets it.
+    pub fix_guidance: Option<String>,
+    /// Tokenizer vocabulary path, if this layer sets it, resolved against
+    /// the config file that declares it.
+    pub tokenizer: Option<PathBuf>,
+    /// Token estimate, if this layer sets it.
+    pub token_estimate: Option<TokenEstimate>,
+    /// Line length unit, if this layer sets it.
+    pub line_length_unit: Option<LineLengthUnit>,
+}
+
+impl ConfigLayer {
+    /// Layers `self` on top of `parent`.
+    ///
+    /// Scalars and `include` set here override the parent, excludes are
+    /// unioned, and rules, directory rules and bundles are appended after
+    /// the parent's so this layer's matches win.
+    #[must_use]
+    pub fn inherit(self, parent: Self) -> Self {
+        let mut exclude = parent.exclude;
+        for pattern in self.exclude {
+            if !exclude.contains(&pattern) {
+                exclude.push(pattern);
+            }
+        }
+        let mut rules = parent.rules;
+        rules.extend(self.rules);
+        let mut directory_rules = parent.directory_rules;
+        directory_rules.extend(self.directory_rules);
+        let mut bundles = parent.bundles;
+        bundles.extend(self.bundles);
+        // A tokenizer and an estimate are alternatives, so setting either
+        // replaces both.
+        let (tokenizer, token_estimate) =
+            if self.tokenizer.is_some() || self.token_estimate.is_some() {
+                (self.tokenizer, self.token_estimate)
+            } else {
+                (parent.tokenizer, parent.token_estimate)
+            };
+
+        Self {
+            default_limit: self.default_limit.or(parent.default_limit),
+            default_severity: self.default_severity.or(parent.default_severity),
+            respect_gitignore: self.respect_gitignore.or(parent.respect_gitignore),
+            pattern_syntax: self.pattern_syntax.or(parent.pattern_syntax),
+            include: self.include.or(parent.include),
+            exclude,
+            rules,
+            directory_rules,
+            bundles,
+            warn_at: self.warn_at.or(parent.warn_at),
+            fix_guidance: self.fix_guidance.or(parent.fix_guidance),
+            tokenizer,
+            token_estimate,
+            line_length_unit: self.line_length_unit.or(parent.line_length_unit),
+        }
+    }
+
+    /// Resolves the layer into a config, filling unset values with defaults.
+    ///
+    /// An unset default budget stays unset: files without a matching rule are skipped.
+    #[must_use]
+    pub fn into_config(self) -> LoqConfig {
+        LoqConfig {
+            default_limit: self.default_limit,
+            default_severity: self.default_severity.unwrap_or_default(),
+            respect_gitignore: self.respect_gitignore.unwrap_or(DEFAULT_RESPECT_GITIGNORE),
+            pattern_syntax: self.pattern_syntax.unwrap_or_default(),
+            include: self.include.unwrap_or_default(),
+            exclude: self.exclude,
+            rules: self.rules,
+            directory_rules: self.directory_rules,
+            bundles: self.bundles,
+            warn_at: self.warn_at,
+            fix_guidance: self.fix_guidance,
+            tokenizer: self.tokenizer,
+            token_estimate: self.token_estimate,
+            line_length_unit: self.line_length_unit.unwrap_or_default(),
         }
     }
 }
@@ -56,28 +239,76 @@ impl Default for LoqConfig {
 pub struct CompiledConfig {
     /// Root directory for relative path matching.
     pub root_dir: PathBuf,
+    /// Config file this was loaded from, or `None` for built-in defaults.
+    pub source_path: Option<PathBuf>,
     /// Default budget for files not matching any rule.
     pub default_limit: Option<Limit>,
+    /// Severity of the default budget.
+    pub default_severity: Severity,
     /// Whether to respect `.gitignore` patterns.
     pub respect_gitignore: bool,
+    /// How include, exclude and rule patterns are interpreted.
+    pub pattern_syntax: PatternSyntax,
+    /// Fraction of a limit above which passing files are flagged as near it.
+    pub warn_at: Option<f64>,
     /// Guidance text shown when violations exist.
     pub fix_guidance: Option<String>,
+    /// Vocabulary file that token limits are counted with, if any.
+    pub tokenizer: Option<PathBuf>,
+    /// How line length limits are measured.
+    pub line_length_unit: LineLengthUnit,
+    include: PatternList,
     exclude: PatternList,
     rules: Vec<CompiledRule>,
+    directory_rules: Vec<CompiledDirectoryRule>,
+    bundles: Vec<CompiledBundle>,
 }
 
 impl CompiledConfig {
+    /// Returns the include pattern list, empty when every path is included.
+    #[must_use]
+    pub const fn include_patterns(&self) -> &PatternList {
+        &self.include
+    }
+
     /// Returns the exclude pattern list.
     #[must_use]
     pub const fn exclude_patterns(&self) -> &PatternList {
         &self.exclude
     }
 
+    /// Returns `true` if `path` matches an include pattern, or no includes are set.
+    #[must_use]
+    pub fn includes(&self, path: &str) -> bool {
+        self.include.is_empty() || self.include.matches(path).is_some()
+    }
+
     /// Returns the compiled rules.
     #[must_use]
     pub fn rules(&self) -> &[CompiledRule] {
         &self.rules
     }
+
+    /// Returns the compiled directory rules.
+    #[must_use]
+    pub fn directory_rules(&self) -> &[CompiledDirectoryRule] {
+        &self.directory_rules
+    }
+
+    /// Returns the compiled bundles.
+    #[must_use]
+    pub fn bundles(&self) -> &[CompiledBundle] {
+        &self.bundles
+    }
+
+    /// Returns the rule governing `path` with its matching pattern (last match wins).
+    #[must_use]
+    pub fn matching_rule(&self, path: &str) -> Option<(&CompiledRule, &str)> {
+        self.rules
+            .iter()
+            .rev()
+            .find_map(|rule| rule.matches(path).map(|pattern| (rule, pattern)))
+    }
 }
 
 /// A rule with compiled glob matchers.
@@ -85,16 +316,31 @@ impl CompiledConfig {
 pub struct CompiledRule {
     /// Original glob pattern strings.
     pub patterns: Vec<String>,
-    /// Maximum allowed budget.
-    pub limit: Limit,
-    matchers: Vec<GlobMatcher>,
+    /// Glob patterns for paths this rule skips.
+    pub exclude: Vec<String>,
+    /// Budgets a matching file must stay within, one per metric.
+    pub limits: Vec<Limit>,
+    /// Whether exceeding a limit fails the check.
+    pub severity: Severity,
+    /// Fraction of a limit above which passing files are flagged as near it.
+    pub warn_at: Option<f64>,
+    /// Guidance template shown with each violation of this rule.
+    pub fix_guidance: Option<String>,
+    /// Whether a matching file is measured together with the files it imports.
+    pub follow_imports: bool,
+    matchers: Vec<Matcher>,
+    exclude_matchers: Vec<Matcher>,
 }
 
 impl CompiledRule {
     /// Tests if the given path matches any of this rule's patterns.
-    /// Returns the first matching pattern, or `None` if no match.
+    /// Returns the first matching pattern, or `None` if no pattern matches
+    /// or one of the rule's exclude patterns does.
     #[must_use]
     pub fn matches(&self, path: &str) -> Option<&str> {
+        if self.exclude_matchers.iter().any(|m| m.is_match(path)) {
+            return None;
+        }
         for (matcher, pattern) in self.matchers.iter().zip(&self.patterns) {
             if matcher.is_match(path) {
                 return Some(pattern);
@@ -104,111 +350,6 @@ impl CompiledRule {
     }
 }
 
-/// A list of compiled glob patterns for matching paths.
-#[derive(Debug, Clone)]
-pub struct PatternList {
-    patterns: Vec<PatternMatcher>,
-}
-
-impl PatternList {
-    /// Creates a new pattern list from compiled matchers.
-    pub(crate) const fn new(patterns: Vec<PatternMatcher>) -> Self {
-        Self { patterns }
-    }
-
-    /// Returns the first matching pattern, or `None` if no match.
-    #[must_use]
-    pub fn matches(&self, path: &str) -> Option<&str> {
-        for pattern in &self.patterns {
-            if pattern.matcher.is_match(path) {
-                return Some(pattern.pattern.as_str());
-            }
-        }
-        None
-    }
-
-    /// Returns an iterator over the pattern strings.
-    pub fn patterns(&self) -> impl Iterator<Item = &str> {
-        self.patterns.iter().map(|p| p.pattern.as_str())
-    }
-}
-
-/// A single compiled glob pattern.
-#[derive(Debug, Clone)]
-pub(crate) struct PatternMatcher {
-    pattern: String,
-    matcher: GlobMatcher,
-}
-
-/// Errors that can occur when parsing or compiling configuration.
-#[derive(Debug, Error)]
-pub enum ConfigError {
-    /// TOML syntax error.
-    #[error("{}", format_toml_error(path, line_col, message))]
-    Toml {
-        /// Path to the config file.
-        path: PathBuf,
-        /// Error message from the TOML parser.
-        message: String,
-        /// Location in the file (line, column).
-        line_col: Option<(usize, usize)>,
-    },
-    /// Unknown key in the config file.
-    #[error("{}", format_unknown_key_error(path, key, line_col, suggestion))]
-    UnknownKey {
-        /// Path to the config file.
-        path: PathBuf,
-        /// The unrecognized key.
-        key: String,
-        /// Location in the file (line, column).
-        line_col: Option<(usize, usize)>,
-        /// Suggested correction if one is close enough.
-        suggestion: Option<String>,
-    },
-    /// Invalid glob pattern.
-    #[error("{} - invalid glob '{}': {}", path.display(), pattern, message)]
-    Glob {
-        /// Path to the config file.
-        path: PathBuf,
-        /// The invalid pattern.
-        pattern: String,
-        /// Error message from the glob parser.
-        message: String,
-    },
-    /// Invalid limit configuration.
-    #[error("{} - {}", path.display(), message)]
-    InvalidLimit {
-        /// Path to the config file.
-        path: PathBuf,
-        /// Validation message.
-        message: String,
-    },
-}
-
-#[allow(clippy::ref_option)]
-fn format_toml_error(path: &Path, line_col: &Option<(usize, usize)>, message: &str) -> String {
-    if let Some((line, col)) = line_col {
-        format!("{}:{}:{} - {}", path.display(), line, col, message)
-    } else {
-        format!("{} - {}", path.display(), message)
-    }
-}
-
-#[allow(clippy::ref_option)]
-fn format_unknown_key_error(
-    path: &Path,
-    key: &str,
-    line_col: &Option<(usize, usize)>,
-    suggestion: &Option<String>,
-) -> String {
-    let base = format_toml_error(path, line_col, &format!("unknown key '{key}'"));
-    if let Some(suggestion) = suggestion {
-        format!("{base}\n       did you mean '{suggestion}'?")
-    } else {
-        base
-    }
-}
-
 /// Compiles a parsed configuration into efficient matchers.
 ///
 /// Takes a `LoqConfig` and compiles all glob patterns into matchers.
@@ -221,56 +362,78 @@ pub fn compile_config(
     let path_for_errors =
         source_path.map_or_else(|| PathBuf::from("<built-in defaults>"), Path::to_path_buf);
 
-    let exclude = compile_patterns(&config.exclude, &path_for_errors)?;
+    let include = compile_patterns(&config.include, config.pattern_syntax, &path_for_errors)?;
+    let syntax = config.pattern_syntax;
+    let exclude = compile_patterns(&config.exclude, syntax, &path_for_errors)?;
+    let tokenizer = config.tokenizer;
+    let counter = match (&tokenizer, config.token_estimate) {
+        (Some(_), _) => TokenCounter::Tokenizer,
+        (None, estimate) => TokenCounter::Estimate(estimate.unwrap_or_default()),
+    };
     let mut rules = Vec::new();
     for rule in config.rules {
         let mut matchers = Vec::new();
         for pattern in &rule.paths {
-            matchers.push(compile_glob(pattern, &path_for_errors)?);
+            matchers.push(compile_glob(pattern, syntax, &path_for_errors)?);
+        }
+        let mut exclude_matchers = Vec::new();
+        for pattern in &rule.exclude {
+            exclude_matchers.push(compile_glob(pattern, syntax, &path_for_errors)?);
         }
         rules.push(CompiledRule {
             patterns: rule.paths,
-            limit: rule.limit,
+            exclude: rule.exclude,
+            limits: rule
+                .limits
+                .iter()
+                .map(|limit| {
+                    limit
+                        .counted_by(rule.token_estimate.map_or(counter, TokenCounter::Estimate))
+                        .measured_in(config.line_length_unit)
+                })
+                .collect(),
+            severity: rule.severity,
+            warn_at: rule.warn_at,
+            fix_guidance: rule.fix_guidance,
+            follow_imports: rule.follow_imports,
             matchers,
+            exclude_matchers,
         });
     }
 
+    let directory_rules = config
+        .directory_rules
+        .into_iter()
+        .map(|rule| rule.compile(&path_for_errors))
+        .collect::<Result<_, _>>()?;
+    let bundles = config
+        .bundles
+        .into_iter()
+        .map(|bundle| bundle.compile(syntax, counter, &path_for_errors))
+        .collect::<Result<_, _>>()?;
+
     Ok(CompiledConfig {
         root_dir,
-        default_limit: config.default_limit,
+        source_path: source_path.map(Path::to_path_buf),
+        default_limit: config.default_limit.map(|limit| {
+            limit
+                .counted_by(counter)
+                .measured_in(config.line_length_unit)
+        }),
+        default_severity: config.default_severity,
         respect_gitignore: config.respect_gitignore,
+        pattern_syntax: syntax,
+        warn_at: config.warn_at,
         fix_guidance: config.fix_guidance,
+        tokenizer,
+        line_length_unit: config.line_length_unit,
+        include,
         exclude,
         rules,
+        directory_rules,
+        bundles,
     })
 }
 
-fn compile_patterns(patterns: &[String], source_path: &Path) -> Result<PatternList, ConfigError> {
-    let mut compiled = Vec::new();
-    for pattern in patterns {
-        let matcher = compile_glob(pattern, source_path)?;
-        compiled.push(PatternMatcher {
-            pattern: pattern.clone(),
-            matcher,
-        });
-    }
-    Ok(PatternList::new(compiled))
-}
-
-fn compile_glob(pattern: &str, source_path: &Path) -> Result<GlobMatcher, ConfigError> {
-    let mut builder = GlobBuilder::new(pattern);
-    #[cfg(windows)]
-    {
-        builder.case_insensitive(true);
-    }
-    builder.literal_separator(true);
-    let glob = builder.build().map_err(|err| ConfigError::Glob {
-        path: source_path.to_path_buf(),
-        pattern: pattern.to_string(),
-        message: err.to_string(),
-    })?;
-    Ok(glob.compile_matcher())
-}
-
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,72 @@
+//! Errors from parsing and compiling configuration.
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::diagnostic::{render_diagnostics, Diagnostic};
+
+/// Errors that can occur when parsing or compiling configuration.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// One or more problems found while validating a config file.
+    #[error("{}", render_diagnostics(path, text, diagnostics))]
+    Diagnostics {
+        /// Path to the config file.
+        path: PathBuf,
+        /// Source text the spans point into.
+        text: String,
+        /// Every problem found, in file order.
+        diagnostics: Vec<Diagnostic>,
+    },
+    /// Invalid glob pattern.
+    #[error("{} - invalid glob '{}': {}", path.display(), pattern, message)]
+    Glob {
+        /// Path to the config file.
+        path: PathBuf,
+        /// The invalid pattern.
+        pattern: String,
+        /// Error message from the glob parser.
+        message: String,
+    },
+    /// A host manifest (`pyproject.toml`, `Cargo.toml`) has no loq table.
+    #[error("{} - no [{}] table found", path.display(), table)]
+    MissingTable {
+        /// Path to the config file.
+        path: PathBuf,
+        /// Dotted key path of the expected table.
+        table: String,
+    },
+    /// Invalid command-line override (`--set key=value`).
+    #[error("invalid override '{assignment}': {message}")]
+    Override {
+        /// The assignment as given on the command line.
+        assignment: String,
+        /// What was wrong with it.
+        message: String,
+    },
+    /// An `extends` chain refers back to a file already in the chain.
+    #[error("extends cycle: {}", format_chain(chain))]
+    ExtendsCycle {
+        /// Files in the chain, ending with the repeated file.
+        chain: Vec<PathBuf>,
+    },
+    /// An `extends` target does not exist or could not be read.
+    #[error("{} - extended config not found\n       extends chain: {}", missing_path(chain).display(), format_chain(chain))]
+    ExtendsMissing {
+        /// Files in the chain, ending with the missing file.
+        chain: Vec<PathBuf>,
+    },
+}
+
+fn format_chain(chain: &[PathBuf]) -> String {
+    chain
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+fn missing_path(chain: &[PathBuf]) -> &Path {
+    chain.last().map_or_else(|| Path::new(""), PathBuf::as_path)
+}
@@ -0,0 +1,93 @@
+//! Compiled glob pattern lists.
+
+use std::path::Path;
+
+use super::ConfigError;
+use crate::pattern::{Matcher, PatternSyntax};
+
+/// A list of compiled glob patterns for matching paths.
+#[derive(Debug, Clone, Default)]
+pub struct PatternList {
+    patterns: Vec<PatternMatcher>,
+}
+
+impl PatternList {
+    /// Creates a new pattern list from compiled matchers.
+    pub(crate) const fn new(patterns: Vec<PatternMatcher>) -> Self {
+        Self { patterns }
+    }
+
+    /// Returns the first matching pattern, or `None` if no match.
+    #[must_use]
+    pub fn matches(&self, path: &str) -> Option<&str> {
+        for pattern in &self.patterns {
+            if pattern.matcher.is_match(path) {
+                return Some(pattern.pattern.as_str());
+            }
+        }
+        None
+    }
+
+    /// Returns `true` if the list has no patterns.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns every pattern matching `path`, in list order.
+    pub fn matching<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a str> {
+        self.patterns
+            .iter()
+            .filter(move |pattern| pattern.matcher.is_match(path))
+            .map(|pattern| pattern.pattern.as_str())
+    }
+
+    /// Returns `true` if some pattern could match a path below the
+    /// directory `dir`. Directories for which this is `false` can be skipped.
+    #[must_use]
+    pub fn could_match_under(&self, dir: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matcher.could_match_under(dir))
+    }
+
+    /// Returns an iterator over the pattern strings.
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.patterns.iter().map(|p| p.pattern.as_str())
+    }
+}
+
+/// A single compiled glob pattern.
+#[derive(Debug, Clone)]
+pub(crate) struct PatternMatcher {
+    pub(super) pattern: String,
+    pub(super) matcher: Matcher,
+}
+
+pub(super) fn compile_patterns(
+    patterns: &[String],
+    syntax: PatternSyntax,
+    source_path: &Path,
+) -> Result<PatternList, ConfigError> {
+    let mut compiled = Vec::new();
+    for pattern in patterns {
+        let matcher = compile_glob(pattern, syntax, source_path)?;
+        compiled.push(PatternMatcher {
+            pattern: pattern.clone(),
+            matcher,
+        });
+    }
+    Ok(PatternList::new(compiled))
+}
+
+pub(crate) fn compile_glob(
+    pattern: &str,
+    syntax: PatternSyntax,
+    source_path: &Path,
+) -> Result<Matcher, ConfigError> {
+    Matcher::new(pattern, syntax).map_err(|err| ConfigError::Glob {
+        path: source_path.to_path_buf(),
+        pattern: pattern.to_string(),
+        message: err.to_string(),
+    })
+}
@@ -1,3 +1,4 @@
+use super::patterns::PatternMatcher;
 use super::*;
 use std::path::PathBuf;
 
@@ -18,9 +19,24 @@ fn invalid_glob_reports_error() {
         exclude: vec![],
         rules: vec![Rule {
             paths: vec!["[[".to_string()],
-            limit: Limit::lines(1),
+            limits: vec![Limit::lines(1)],
+            severity: Severity::Error,
+            fix_guidance: None,
+            warn_at: None,
+            exclude: vec![],
+            token_estimate: None,
+            follow_imports: false,
         }],
         fix_guidance: None,
+        default_severity: Severity::Error,
+        warn_at: None,
+        include: vec![],
+        pattern_syntax: PatternSyntax::Glob,
+        tokenizer: None,
+        token_estimate: None,
+        line_length_unit: LineLengthUnit::Width,
+        directory_rules: Vec::new(),
+        bundles: Vec::new(),
     };
     let err = compile_config(PathBuf::from("."), config, None).unwrap_err();
     assert!(matches!(err, ConfigError::Glob { .. }));
@@ -34,6 +50,15 @@ fn glob_error_display_is_stable() {
         exclude: vec!["[[".to_string()],
         rules: vec![],
         fix_guidance: None,
+        default_severity: Severity::Error,
+        warn_at: None,
+        include: vec![],
+        pattern_syntax: PatternSyntax::Glob,
+        tokenizer: None,
+        token_estimate: None,
+        line_length_unit: LineLengthUnit::Width,
+        directory_rules: Vec::new(),
+        bundles: Vec::new(),
     };
     let err = compile_config(PathBuf::from("."), config, None).unwrap_err();
     assert!(err.to_string().contains("invalid glob"));
@@ -47,9 +72,24 @@ fn glob_star_does_not_cross_directories() {
         exclude: vec![],
         rules: vec![Rule {
             paths: vec!["src/*.rs".to_string()],
-            limit: Limit::lines(1),
+            limits: vec![Limit::lines(1)],
+            severity: Severity::Error,
+            fix_guidance: None,
+            warn_at: None,
+            exclude: vec![],
+            token_estimate: None,
+            follow_imports: false,
         }],
         fix_guidance: None,
+        default_severity: Severity::Error,
+        warn_at: None,
+        include: vec![],
+        pattern_syntax: PatternSyntax::Glob,
+        tokenizer: None,
+        token_estimate: None,
+        line_length_unit: LineLengthUnit::Width,
+        directory_rules: Vec::new(),
+        bundles: Vec::new(),
     };
     let compiled = compile_config(PathBuf::from("."), config, None).unwrap();
     let rule = &compiled.rules()[0];
@@ -66,13 +106,28 @@ fn token_rule_compiles_to_token_limit() {
         exclude: vec![],
         rules: vec![Rule {
             paths: vec!["prompts/**/*.md".to_string()],
-            limit: Limit::tokens(8000),
+            limits: vec![Limit::tokens(8000)],
+            severity: Severity::Error,
+            fix_guidance: None,
+            warn_at: None,
+            exclude: vec![],
+            token_estimate: None,
+            follow_imports: false,
         }],
         fix_guidance: None,
+        default_severity: Severity::Error,
+        warn_at: None,
+        include: vec![],
+        pattern_syntax: PatternSyntax::Glob,
+        tokenizer: None,
+        token_estimate: None,
+        line_length_unit: LineLengthUnit::Width,
+        directory_rules: Vec::new(),
+        bundles: Vec::new(),
     };
     let compiled = compile_config(PathBuf::from("."), config, None).unwrap();
 
-    assert_eq!(compiled.rules()[0].limit, Limit::tokens(8000));
+    assert_eq!(compiled.rules()[0].limits, [Limit::tokens(8000)]);
 }
 
 #[test]
@@ -83,35 +138,174 @@ fn default_token_limit_compiles() {
         exclude: vec![],
         rules: vec![],
         fix_guidance: None,
+        default_severity: Severity::Error,
+        warn_at: None,
+        include: vec![],
+        pattern_syntax: PatternSyntax::Glob,
+        tokenizer: None,
+        token_estimate: None,
+        line_length_unit: LineLengthUnit::Width,
+        directory_rules: Vec::new(),
+        bundles: Vec::new(),
     };
     let compiled = compile_config(PathBuf::from("."), config, None).unwrap();
 
     assert_eq!(compiled.default_limit, Some(Limit::tokens(2000)));
 }
 
+#[test]
+fn tokenizer_counts_token_limits_exactly() {
+    let config = LoqConfig {
+        default_limit: Some(Limit::tokens(2000)),
+        respect_gitignore: true,
+        exclude: vec![],
+        rules: vec![Rule {
+            paths: vec!["*.rs".to_string()],
+            limits: vec![Limit::lines(300)],
+            severity: Severity::Error,
+            fix_guidance: None,
+            warn_at: None,
+            exclude: vec![],
+            token_estimate: None,
+            follow_imports: false,
+        }],
+        fix_guidance: None,
+        default_severity: Severity::Error,
+        warn_at: None,
+        include: vec![],
+        pattern_syntax: PatternSyntax::Glob,
+        tokenizer: Some(PathBuf::from("vocab.tiktoken")),
+        token_estimate: None,
+        line_length_unit: LineLengthUnit::Width,
+        directory_rules: Vec::new(),
+        bundles: Vec::new(),
+    };
+    let compiled = compile_config(PathBuf::from("root"), config, None).unwrap();
+
+    let default = compiled.default_limit.unwrap();
+    assert_eq!(default.counter, TokenCounter::Tokenizer);
+    assert!(!default.is_approximate());
+    assert_eq!(compiled.rules()[0].limits, [Limit::lines(300)]);
+    assert_eq!(compiled.tokenizer, Some(PathBuf::from("vocab.tiktoken")));
+}
+
+#[test]
+fn rule_token_estimate_overrides_config_counter() {
+    let rule = |estimate| Rule {
+        paths: vec!["docs/**".to_string()],
+        limits: vec![Limit::tokens(100)],
+        severity: Severity::Error,
+        fix_guidance: None,
+        warn_at: None,
+        exclude: vec![],
+        token_estimate: estimate,
+        follow_imports: false,
+    };
+    let config = LoqConfig {
+        default_limit: Some(Limit::tokens(2000)),
+        rules: vec![rule(None), rule(Some(TokenEstimate::CharsPerToken(1.5)))],
+        token_estimate: Some(TokenEstimate::BytesPerToken(3.0)),
+        ..LoqConfig::default()
+    };
+    let compiled = compile_config(PathBuf::from("."), config, None).unwrap();
+
+    let estimate = |limit: Limit| limit.token_estimate();
+    assert_eq!(
+        estimate(compiled.default_limit.unwrap()),
+        Some(TokenEstimate::BytesPerToken(3.0))
+    );
+    assert_eq!(
+        estimate(compiled.rules()[0].limits[0]),
+        Some(TokenEstimate::BytesPerToken(3.0))
+    );
+    assert_eq!(
+        estimate(compiled.rules()[1].limits[0]),
+        Some(TokenEstimate::CharsPerToken(1.5))
+    );
+}
+
 #[test]
 fn pattern_list_no_match_returns_none() {
     let patterns = vec![PatternMatcher {
         pattern: "*.rs".to_string(),
-        matcher: globset::GlobBuilder::new("*.rs")
-            .literal_separator(true)
-            .build()
-            .unwrap()
-            .compile_matcher(),
+        matcher: Matcher::new("*.rs", PatternSyntax::Glob).unwrap(),
     }];
     let list = PatternList::new(patterns);
     assert!(list.matches("foo.txt").is_none());
 }
 
 #[test]
-fn format_toml_error_without_location() {
-    let msg = format_toml_error(Path::new("test.toml"), &None, "parse error");
-    assert_eq!(msg, "test.toml - parse error");
+fn layer_inherit_unions_excludes_and_appends_rules() {
+    let parent = ConfigLayer {
+        default_limit: Some(Limit::lines(300)),
+        respect_gitignore: Some(false),
+        exclude: vec!["a/**".to_string(), "b/**".to_string()],
+        rules: vec![Rule {
+            paths: vec!["**/*.rs".to_string()],
+            limits: vec![Limit::lines(100)],
+            severity: Severity::Error,
+            fix_guidance: None,
+            warn_at: None,
+            exclude: vec![],
+            token_estimate: None,
+            follow_imports: false,
+        }],
+        fix_guidance: Some("parent".to_string()),
+        default_severity: None,
+        include: None,
+        pattern_syntax: None,
+        warn_at: None,
+        tokenizer: None,
+        token_estimate: None,
+        line_length_unit: None,
+        directory_rules: Vec::new(),
+        bundles: Vec::new(),
+    };
+    let child = ConfigLayer {
+        exclude: vec!["b/**".to_string(), "c/**".to_string()],
+        rules: vec![Rule {
+            paths: vec!["src/*.rs".to_string()],
+            limits: vec![Limit::lines(50)],
+            severity: Severity::Error,
+            fix_guidance: None,
+            warn_at: None,
+            exclude: vec![],
+            token_estimate: None,
+            follow_imports: false,
+        }],
+        ..ConfigLayer::default()
+    };
+
+    let config = child.inherit(parent).into_config();
+
+    assert_eq!(config.default_limit, Some(Limit::lines(300)));
+    assert!(!config.respect_gitignore);
+    assert_eq!(config.exclude, vec!["a/**", "b/**", "c/**"]);
+    assert_eq!(config.rules[1].paths, vec!["src/*.rs"]);
+    assert_eq!(config.fix_guidance.as_deref(), Some("parent"));
 }
 
 #[test]
-fn format_unknown_key_error_without_suggestion() {
-    let msg = format_unknown_key_error(Path::new("test.toml"), "xyz", &Some((1, 1)), &None);
-    assert!(msg.contains("unknown key 'xyz'"));
-    assert!(!msg.contains("did you mean"));
+fn layer_inherit_replaces_include() {
+    let parent = ConfigLayer {
+        include: Some(vec!["src/**".to_string()]),
+        ..ConfigLayer::default()
+    };
+    let child = ConfigLayer {
+        include: Some(vec!["prompts/**".to_string()]),
+        ..ConfigLayer::default()
+    };
+    let unset = ConfigLayer::default();
+
+    let config = child.inherit(parent.clone()).into_config();
+    assert_eq!(config.include, vec!["prompts/**"]);
+    let config = unset.inherit(parent).into_config();
+    assert_eq!(config.include, vec!["src/**"]);
+}
+
+#[test]
+fn empty_layer_resolves_to_no_default_limit() {
+    let config = ConfigLayer::default().into_config();
+    assert_eq!(config.default_limit, None);
+    assert!(config.respect_gitignore);
 }
@@ -24,36 +24,34 @@ pub enum MatchBy {
 /// The decision for how to handle a file.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Decision {
-    /// File should be checked against a limit.
+    /// File should be checked against one or more limits.
     Check {
-        /// Maximum allowed budget.
-        limit: Limit,
-        /// How the limit was determined.
+        /// Budgets the file must stay within, in config order.
+        limits: Vec<Limit>,
+        /// How the limits were determined.
         matched_by: MatchBy,
     },
     /// No default limit and no matching rule; skip.
     SkipNoLimit,
 }
 
-/// Decides what limit applies to a file path.
+/// Decides what limits apply to a file path.
 ///
 /// Checks rules (last match wins), then falls back to default.
 #[must_use]
 pub fn decide(config: &CompiledConfig, path: &str) -> Decision {
-    for rule in config.rules().iter().rev() {
-        if let Some(pattern) = rule.matches(path) {
-            return Decision::Check {
-                limit: rule.limit,
-                matched_by: MatchBy::Rule {
-                    pattern: pattern.to_string(),
-                },
-            };
-        }
+    if let Some((rule, pattern)) = config.matching_rule(path) {
+        return Decision::Check {
+            limits: rule.limits.clone(),
+            matched_by: MatchBy::Rule {
+                pattern: pattern.to_string(),
+            },
+        };
     }
 
     if let Some(default_limit) = config.default_limit {
         Decision::Check {
-            limit: default_limit,
+            limits: vec![default_limit],
             matched_by: MatchBy::Default,
         }
     } else {
@@ -65,6 +63,7 @@ pub fn decide(config: &CompiledConfig, path: &str) -> Decision {
 mod tests {
     use super::*;
     use crate::config::{compile_config, LoqConfig, Rule};
+    use crate::{LineLengthUnit, PatternSyntax, Severity};
     use std::path::PathBuf;
 
     fn compiled(config: LoqConfig) -> CompiledConfig {
@@ -80,19 +79,40 @@ mod tests {
             rules: vec![
                 Rule {
                     paths: vec!["**/*.rs".to_string()],
-                    limit: Limit::lines(100),
+                    limits: vec![Limit::lines(100)],
+                    severity: Severity::Error,
+                    fix_guidance: None,
+                    warn_at: None,
+                    exclude: vec![],
+                    token_estimate: None,
+                    follow_imports: false,
                 },
                 Rule {
                     paths: vec!["**/*.rs".to_string()],
-                    limit: Limit::lines(200),
+                    limits: vec![Limit::lines(200)],
+                    severity: Severity::Error,
+                    fix_guidance: None,
+                    warn_at: None,
+                    exclude: vec![],
+                    token_estimate: None,
+                    follow_imports: false,
                 },
             ],
             fix_guidance: None,
+            default_severity: Severity::Error,
+            warn_at: None,
+            include: vec![],
+            pattern_syntax: PatternSyntax::Glob,
+            tokenizer: None,
+            token_estimate: None,
+            line_length_unit: LineLengthUnit::Width,
+            directory_rules: Vec::new(),
+            bundles: Vec::new(),
         };
         let decision = decide(&compiled(config), "src/main.rs");
         match decision {
-            Decision::Check { limit, .. } => {
-                assert_eq!(limit, Limit::lines(200));
+            Decision::Check { limits, .. } => {
+                assert_eq!(limits, [Limit::lines(200)]);
             }
             Decision::SkipNoLimit => panic!("expected check"),
         }
@@ -106,11 +126,20 @@ mod tests {
             exclude: vec![],
             rules: vec![],
             fix_guidance: None,
+            default_severity: Severity::Error,
+            warn_at: None,
+            include: vec![],
+            pattern_syntax: PatternSyntax::Glob,
+            tokenizer: None,
+            token_estimate: None,
+            line_length_unit: LineLengthUnit::Width,
+            directory_rules: Vec::new(),
+            bundles: Vec::new(),
         };
         let decision = decide(&compiled(config), "src/file.txt");
         match decision {
-            Decision::Check { limit, matched_by } => {
-                assert_eq!(limit, Limit::lines(123));
+            Decision::Check { limits, matched_by } => {
+                assert_eq!(limits, [Limit::lines(123)]);
                 assert_eq!(matched_by, MatchBy::Default);
             }
             Decision::SkipNoLimit => panic!("expected default"),
@@ -125,6 +154,15 @@ mod tests {
             exclude: vec![],
             rules: vec![],
             fix_guidance: None,
+            default_severity: Severity::Error,
+            warn_at: None,
+            include: vec![],
+            pattern_syntax: PatternSyntax::Glob,
+            tokenizer: None,
+            token_estimate: None,
+            line_length_unit: LineLengthUnit::Width,
+            directory_rules: Vec::new(),
+            bundles: Vec::new(),
         };
         let decision = decide(&compiled(config), "src/file.txt");
         assert_eq!(decision, Decision::SkipNoLimit);
@@ -138,17 +176,32 @@ mod tests {
             exclude: vec![],
             rules: vec![Rule {
                 paths: vec!["src/a.rs".to_string(), "src/b.rs".to_string()],
-                limit: Limit::lines(100),
+                limits: vec![Limit::lines(100)],
+                severity: Severity::Error,
+                fix_guidance: None,
+                warn_at: None,
+                exclude: vec![],
+                token_estimate: None,
+                follow_imports: false,
             }],
             fix_guidance: None,
+            default_severity: Severity::Error,
+            warn_at: None,
+            include: vec![],
+            pattern_syntax: PatternSyntax::Glob,
+            tokenizer: None,
+            token_estimate: None,
+            line_length_unit: LineLengthUnit::Width,
+            directory_rules: Vec::new(),
+            bundles: Vec::new(),
         };
         let compiled = compiled(config);
 
         // First pattern matches
         let decision_a = decide(&compiled, "src/a.rs");
         match decision_a {
-            Decision::Check { limit, matched_by } => {
-                assert_eq!(limit, Limit::lines(100));
+            Decision::Check { limits, matched_by } => {
+                assert_eq!(limits, [Limit::lines(100)]);
                 assert_eq!(
                     matched_by,
                     MatchBy::Rule {
@@ -176,11 +229,67 @@ mod tests {
         // Neither pattern matches - falls back to default
         let decision_c = decide(&compiled, "src/c.rs");
         match decision_c {
-            Decision::Check { limit, matched_by } => {
-                assert_eq!(limit, Limit::lines(500));
+            Decision::Check { limits, matched_by } => {
+                assert_eq!(limits, [Limit::lines(500)]);
                 assert_eq!(matched_by, MatchBy::Default);
             }
             Decision::SkipNoLimit => panic!("expected default for c.rs"),
         }
     }
+
+    #[test]
+    fn rule_exclude_falls_through_to_earlier_rule_and_default() {
+        let config = LoqConfig {
+            rules: vec![
+                Rule {
+                    paths: vec!["**/*.py".to_string()],
+                    exclude: vec![],
+                    limits: vec![Limit::lines(800)],
+                    severity: Severity::Error,
+                    warn_at: None,
+                    fix_guidance: None,
+                    token_estimate: None,
+                    follow_imports: false,
+                },
+                Rule {
+                    paths: vec!["**/*.py".to_string(), "**/*.pyi".to_string()],
+                    exclude: vec!["**/migrations/**".to_string()],
+                    limits: vec![Limit::lines(300)],
+                    severity: Severity::Error,
+                    warn_at: None,
+                    fix_guidance: None,
+                    token_estimate: None,
+                    follow_imports: false,
+                },
+            ],
+            ..LoqConfig::default()
+        };
+        let compiled = compiled(config);
+
+        assert_eq!(
+            decide(&compiled, "app/models.py"),
+            Decision::Check {
+                limits: vec![Limit::lines(300)],
+                matched_by: MatchBy::Rule {
+                    pattern: "**/*.py".to_string()
+                },
+            }
+        );
+        assert_eq!(
+            decide(&compiled, "app/migrations/0001_initial.py"),
+            Decision::Check {
+                limits: vec![Limit::lines(800)],
+                matched_by: MatchBy::Rule {
+                    pattern: "**/*.py".to_string()
+                },
+            }
+        );
+        assert_eq!(
+            decide(&compiled, "app/migrations/stubs.pyi"),
+            Decision::Check {
+                limits: vec![Limit::lines(500)],
+                matched_by: MatchBy::Default,
+            }
+        );
+    }
 }
@@ -0,0 +1,252 @@
+//! Config diagnostics with source spans.
+//!
+//! Validation collects every problem in a config file as a [`Diagnostic`]
+//! pointing at the offending TOML, rendered as a code frame for humans and
+//! available as plain data for editors.
+
+use std::fmt::Write;
+use std::ops::Range;
+use std::path::Path;
+
+use toml_edit::{ImDocument, Item, Table, TableLike, Value};
+
+/// What a diagnostic is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// The file is not valid TOML or a value has the wrong type.
+    Syntax,
+    /// A key loq does not recognize.
+    UnknownKey {
+        /// The unrecognized key.
+        key: String,
+        /// A known key with a similar name.
+        suggestion: Option<String>,
+    },
+    /// A value with the right type but an invalid setting, e.g. conflicting limits.
+    InvalidValue,
+    /// A glob pattern that does not compile.
+    InvalidGlob {
+        /// The invalid pattern.
+        pattern: String,
+    },
+}
+
+impl DiagnosticKind {
+    /// Returns the kind as used in machine-readable output.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Syntax => "syntax",
+            Self::UnknownKey { .. } => "unknown_key",
+            Self::InvalidValue => "invalid_value",
+            Self::InvalidGlob { .. } => "invalid_glob",
+        }
+    }
+}
+
+/// One problem found in a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// What the problem is about.
+    pub kind: DiagnosticKind,
+    /// Human-readable description.
+    pub message: String,
+    /// Suggested fix, if any.
+    pub help: Option<String>,
+    /// Byte range of the offending TOML, if known.
+    pub span: Option<Range<usize>>,
+    /// One-based line and column of the span start, if known.
+    pub line_col: Option<(usize, usize)>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic at `span` within `text`.
+    #[must_use]
+    pub fn new(
+        kind: DiagnosticKind,
+        message: impl Into<String>,
+        span: Option<Range<usize>>,
+        text: &str,
+    ) -> Self {
+        let help = match &kind {
+            DiagnosticKind::UnknownKey {
+                suggestion: Some(suggestion),
+                ..
+            } => Some(format!("did you mean '{suggestion}'?")),
+            _ => None,
+        };
+        let line_col = span
+            .as_ref()
+            .and_then(|span| line_col_from_offset(text, span.start));
+        Self {
+            kind,
+            message: message.into(),
+            help,
+            span,
+            line_col,
+        }
+    }
+}
+
+/// Renders diagnostics as code frames, one after another.
+#[must_use]
+pub fn render_diagnostics(path: &Path, text: &str, diagnostics: &[Diagnostic]) -> String {
+    let mut out = String::new();
+    for (index, diagnostic) in diagnostics.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        render_one(&mut out, path, text, diagnostic);
+    }
+    if diagnostics.len() > 1 {
+        let _ = write!(
+            out,
+            "\n{} problems found in {}",
+            diagnostics.len(),
+            path.display()
+        );
+    }
+    out
+}
+
+fn render_one(out: &mut String, path: &Path, text: &str, diagnostic: &Diagnostic) {
+    let location = diagnostic
+        .line_col
+        .map_or_else(String::new, |(line, col)| format!(":{line}:{col}"));
+    let _ = write!(out, "{}{location} - {}", path.display(), diagnostic.message);
+
+    let frame =
+        diagnostic
+            .span
+            .as_ref()
+            .zip(diagnostic.line_col)
+            .and_then(|(span, (line, col))| {
+                let source_line = text.lines().nth(line - 1)?;
+                let start = col - 1;
+                let end = (start + span.len()).min(source_line.len()).max(start + 1);
+                Some((line, source_line, start, end))
+            });
+    let gutter = frame.map_or(1, |(line, ..)| line.to_string().len());
+    let pad = " ".repeat(gutter);
+
+    if let Some((line, source_line, start, end)) = frame {
+        let carets = "^".repeat(
+            source_line[start..]
+                .chars()
+                .take(end - start)
+                .count()
+                .max(1),
+        );
+        let indent = " ".repeat(source_line[..start].chars().count());
+        let _ = write!(
+            out,
+            "\n{pad} |\n{line} | {source_line}\n{pad} | {indent}{carets}"
+        );
+    }
+    if let Some(help) = &diagnostic.help {
+        let _ = write!(out, "\n{pad} = help: {help}");
+    }
+}
+
+/// Converts a byte offset into a one-based line and column.
+pub(crate) fn line_col_from_offset(text: &str, offset: usize) -> Option<(usize, usize)> {
+    if offset > text.len() {
+        return None;
+    }
+    let mut line = 1;
+    let mut col = 1;
+    for (idx, ch) in text.char_indices() {
+        if idx >= offset {
+            break;
+        }
+        if ch == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    Some((line, col))
+}
+
+/// Byte spans of keys and values in a config's source text.
+pub(crate) struct SourceSpans {
+    doc: Option<ImDocument<String>>,
+    prefix: Vec<String>,
+}
+
+impl SourceSpans {
+    /// Indexes `text`, resolving paths relative to the loq table at `table`.
+    pub(crate) fn new(text: &str, table: &[&str]) -> Self {
+        Self {
+            doc: ImDocument::parse(text.to_string()).ok(),
+            prefix: table.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// Returns the span of the key at `path`, e.g. `["rules", "0", "max_line"]`.
+    pub(crate) fn key(&self, path: &[&str]) -> Option<Range<usize>> {
+        let (last, parents) = path.split_last()?;
+        let table = self.node(parents)?.table_like()?;
+        table.get_key_value(last)?.0.span()
+    }
+
+    /// Returns the span of the value at `path`.
+    pub(crate) fn value(&self, path: &[&str]) -> Option<Range<usize>> {
+        self.node(path)?.span()
+    }
+
+    fn node(&self, path: &[&str]) -> Option<Node<'_>> {
+        let mut node = Node::Table(self.doc.as_ref()?.as_table());
+        let segments = self
+            .prefix
+            .iter()
+            .map(String::as_str)
+            .chain(path.iter().copied());
+        for segment in segments {
+            node = match segment.parse::<usize>() {
+                Ok(index) => node.index(index)?,
+                Err(_) => Node::Item(node.table_like()?.get(segment)?),
+            };
+        }
+        Some(node)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Item(&'a Item),
+    Table(&'a Table),
+    Value(&'a Value),
+}
+
+impl<'a> Node<'a> {
+    fn span(self) -> Option<Range<usize>> {
+        match self {
+            Self::Item(item) => item.span(),
+            Self::Table(table) => table.span(),
+            Self::Value(value) => value.span(),
+        }
+    }
+
+    fn table_like(self) -> Option<&'a dyn TableLike> {
+        match self {
+            Self::Item(item) => item.as_table_like(),
+            Self::Table(table) => Some(table),
+            Self::Value(value) => value.as_inline_table().map(|table| table as &dyn TableLike),
+        }
+    }
+
+    fn index(self, index: usize) -> Option<Self> {
+        match self {
+            Self::Item(Item::ArrayOfTables(tables)) => tables.get(index).map(Self::Table),
+            Self::Item(Item::Value(value)) | Self::Value(value) => {
+                value.as_array()?.get(index).map(Self::Value)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
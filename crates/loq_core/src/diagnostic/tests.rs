@@ -0,0 +1,64 @@
+use super::*;
+
+#[test]
+fn line_col_from_offset_handles_newlines() {
+    let text = "line1\nline2\nline3";
+    assert_eq!(line_col_from_offset(text, 6), Some((2, 1)));
+}
+
+#[test]
+fn line_col_from_offset_out_of_bounds() {
+    assert!(line_col_from_offset("short", 100).is_none());
+}
+
+#[test]
+fn render_without_span_has_no_frame() {
+    let diagnostic = Diagnostic::new(DiagnosticKind::Syntax, "parse error", None, "");
+    let rendered = render_diagnostics(Path::new("test.toml"), "", &[diagnostic]);
+    assert_eq!(rendered, "test.toml - parse error");
+}
+
+#[test]
+fn render_draws_carets_under_span() {
+    let text = "default_max_lines = 500\nmax_line = 10\n";
+    let diagnostic = Diagnostic::new(
+        DiagnosticKind::UnknownKey {
+            key: "max_line".to_string(),
+            suggestion: Some("max_lines".to_string()),
+        },
+        "unknown key 'max_line'",
+        Some(24..32),
+        text,
+    );
+    let rendered = render_diagnostics(Path::new("loq.toml"), text, &[diagnostic]);
+    assert_eq!(
+        rendered,
+        "loq.toml:2:1 - unknown key 'max_line'\n  |\n2 | max_line = 10\n  | ^^^^^^^^\n  = help: did you mean 'max_lines'?"
+    );
+}
+
+#[test]
+fn render_counts_multiple_problems() {
+    let text = "a = 1\nb = 2\n";
+    let diagnostics = [
+        Diagnostic::new(DiagnosticKind::InvalidValue, "bad a", Some(4..5), text),
+        Diagnostic::new(DiagnosticKind::InvalidValue, "bad b", Some(10..11), text),
+    ];
+    let rendered = render_diagnostics(Path::new("loq.toml"), text, &diagnostics);
+    assert!(rendered.contains("loq.toml:1:5 - bad a\n  |\n1 | a = 1\n  |     ^"));
+    assert!(rendered.contains("loq.toml:2:5 - bad b"));
+    assert!(rendered.ends_with("2 problems found in loq.toml"));
+}
+
+#[test]
+fn spans_resolve_inside_host_table() {
+    let text =
+        "[tool.loq]\nexclude = [\"a\", \"b\"]\n\n[[tool.loq.rules]]\npath = \"x\"\nmax_lines = 1\n";
+    let spans = SourceSpans::new(text, &["tool", "loq"]);
+    assert_eq!(&text[spans.value(&["exclude", "1"]).unwrap()], "\"b\"");
+    assert_eq!(
+        &text[spans.key(&["rules", "0", "max_lines"]).unwrap()],
+        "max_lines"
+    );
+    assert!(spans.key(&["rules", "1", "path"]).is_none());
+}
@@ -0,0 +1,308 @@
+//! Directory-level budgets.
+//!
+//! Aggregates the file outcomes of a check run per directory matched by a
+//! `[[directory_rules]]` entry and compares the totals with the rule's budgets.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::config::{compile_glob, ConfigError};
+use crate::pattern::{Matcher, PatternSyntax};
+use crate::report::{FileOutcome, OutcomeKind};
+
+/// A validated `[[directory_rules]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryRule {
+    /// Glob patterns for the directories this rule budgets.
+    pub paths: Vec<String>,
+    /// Most files a matching directory may hold, at any depth.
+    pub max_files: Option<usize>,
+    /// Most lines the files in a matching directory may add up to.
+    pub max_total_lines: Option<usize>,
+}
+
+impl DirectoryRule {
+    /// Compiles the rule's patterns. Directory paths are always plain globs:
+    /// gitignore syntax would also match every directory below a matching one.
+    pub(crate) fn compile(self, source_path: &Path) -> Result<CompiledDirectoryRule, ConfigError> {
+        let matchers = self
+            .paths
+            .iter()
+            .map(|pattern| compile_glob(pattern, PatternSyntax::Glob, source_path))
+            .collect::<Result<_, _>>()?;
+        Ok(CompiledDirectoryRule {
+            patterns: self.paths,
+            max_files: self.max_files,
+            max_total_lines: self.max_total_lines,
+            matchers,
+        })
+    }
+}
+
+/// A directory rule with compiled glob matchers.
+#[derive(Debug, Clone)]
+pub struct CompiledDirectoryRule {
+    /// Original glob pattern strings.
+    pub patterns: Vec<String>,
+    /// Most files a matching directory may hold, at any depth.
+    pub max_files: Option<usize>,
+    /// Most lines the files in a matching directory may add up to.
+    pub max_total_lines: Option<usize>,
+    pub(crate) matchers: Vec<Matcher>,
+}
+
+impl CompiledDirectoryRule {
+    /// Returns the first pattern matching the directory `path`, if any.
+    #[must_use]
+    pub fn matches(&self, path: &str) -> Option<&str> {
+        self.matchers
+            .iter()
+            .zip(&self.patterns)
+            .find_map(|(matcher, pattern)| matcher.is_match(path).then_some(pattern.as_str()))
+    }
+
+    /// Returns the budgets this rule sets.
+    fn budgets(&self) -> impl Iterator<Item = (DirectoryMetric, usize)> {
+        [
+            (DirectoryMetric::Files, self.max_files),
+            (DirectoryMetric::TotalLines, self.max_total_lines),
+        ]
+        .into_iter()
+        .filter_map(|(metric, max)| Some((metric, max?)))
+    }
+}
+
+/// What a directory budget counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectoryMetric {
+    /// Files in the directory, at any depth.
+    Files,
+    /// Lines across those files.
+    TotalLines,
+}
+
+impl DirectoryMetric {
+    /// Returns the name used in JSON output.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Files => "files",
+            Self::TotalLines => "total_lines",
+        }
+    }
+
+    /// Returns the plural unit shown in text output.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Files => "files",
+            Self::TotalLines => "total lines",
+        }
+    }
+}
+
+/// A directory over one of its budgets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryViolation {
+    /// Directory path relative to the working directory for display.
+    pub display_path: String,
+    /// The directory rule pattern that matched.
+    pub pattern: String,
+    /// What the budget counts.
+    pub metric: DirectoryMetric,
+    /// Actual total.
+    pub actual: usize,
+    /// The configured budget.
+    pub max: usize,
+}
+
+/// Running totals for one matched directory.
+struct Totals {
+    display_path: String,
+    files: usize,
+    lines: usize,
+    /// Whether one of the files was checked rather than only counted.
+    checked: bool,
+}
+
+/// Checks the directories matched by `rules` against their budgets.
+///
+/// A file counts toward every matched directory it sits in, at any depth.
+/// Totals add up the `checked` files and the `rest` of the tree, leaving out
+/// missing files, but only directories holding a checked file are reported.
+/// A partial run such as `--staged` thus sees whole directories without
+/// failing on ones it did not touch.
+#[must_use]
+pub fn check_directories(
+    rules: &[CompiledDirectoryRule],
+    checked: &[FileOutcome],
+    rest: &[FileOutcome],
+) -> Vec<DirectoryViolation> {
+    let mut totals: BTreeMap<(usize, &str), Totals> = BTreeMap::new();
+    let outcomes = checked
+        .iter()
+        .map(|outcome| (outcome, true))
+        .chain(rest.iter().map(|outcome| (outcome, false)));
+    for (outcome, is_checked) in outcomes {
+        if matches!(outcome.kind, OutcomeKind::Missing) {
+            continue;
+        }
+        for (end, _) in outcome.match_key.match_indices('/') {
+            let directory = &outcome.match_key[..end];
+            for (index, rule) in rules.iter().enumerate() {
+                if rule.matches(directory).is_none() {
+                    continue;
+                }
+                let entry = totals.entry((index, directory)).or_insert_with(|| Totals {
+                    display_path: directory_display(outcome, end),
+                    files: 0,
+                    lines: 0,
+                    checked: false,
+                });
+                entry.files += 1;
+                entry.lines += outcome.lines.unwrap_or(0);
+                entry.checked |= is_checked;
+            }
+        }
+    }
+
+    let mut violations = Vec::new();
+    for ((index, directory), totals) in totals.into_iter().filter(|(_, t)| t.checked) {
+        let rule = &rules[index];
+        let pattern = rule.matches(directory).unwrap_or_default();
+        for (metric, max) in rule.budgets() {
+            let actual = match metric {
+                DirectoryMetric::Files => totals.files,
+                DirectoryMetric::TotalLines => totals.lines,
+            };
+            if actual > max {
+                violations.push(DirectoryViolation {
+                    display_path: totals.display_path.clone(),
+                    pattern: pattern.to_string(),
+                    metric,
+                    actual,
+                    max,
+                });
+            }
+        }
+    }
+    violations
+}
+
+/// Returns the display path of the directory that `outcome`'s match key
+/// ends at `end`, falling back to the match key's directory.
+fn directory_display(outcome: &FileOutcome, end: usize) -> String {
+    let rest = &outcome.match_key[end..];
+    if let Some(directory) = outcome.display_path.strip_suffix(rest) {
+        return directory.to_string();
+    }
+    if outcome.display_path == rest[1..] {
+        return ".".to_string();
+    }
+    outcome.match_key[..end].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        pattern: &str,
+        max_files: Option<usize>,
+        max_total_lines: Option<usize>,
+    ) -> CompiledDirectoryRule {
+        CompiledDirectoryRule {
+            patterns: vec![pattern.to_string()],
+            max_files,
+            max_total_lines,
+            matchers: vec![Matcher::new(pattern, PatternSyntax::Glob).unwrap()],
+        }
+    }
+
+    fn outcome(match_key: &str, lines: usize) -> FileOutcome {
+        FileOutcome {
+            display_path: match_key.to_string(),
+            match_key: match_key.to_string(),
+            config_path: None,
+            kind: OutcomeKind::NoLimit,
+            lines: Some(lines),
+            bundle_tokens: None,
+        }
+    }
+
+    #[test]
+    fn each_matched_directory_is_counted_separately() {
+        let outcomes = [
+            outcome("src/components/button/index.ts", 10),
+            outcome("src/components/button/style.css", 10),
+            outcome("src/components/button/icons/x.svg", 10),
+            outcome("src/components/card/index.ts", 10),
+        ];
+
+        let violations =
+            check_directories(&[rule("src/components/*", Some(2), None)], &outcomes, &[]);
+
+        assert_eq!(
+            violations,
+            [DirectoryViolation {
+                display_path: "src/components/button".to_string(),
+                pattern: "src/components/*".to_string(),
+                metric: DirectoryMetric::Files,
+                actual: 3,
+                max: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn total_lines_add_up_across_files() {
+        let outcomes = [outcome("docs/a.md", 30), outcome("docs/b.md", 25)];
+
+        let violations = check_directories(&[rule("docs", Some(5), Some(50))], &outcomes, &[]);
+
+        assert_eq!(violations.len(), 1, "{violations:?}");
+        assert_eq!(violations[0].metric, DirectoryMetric::TotalLines);
+        assert_eq!(violations[0].actual, 55);
+    }
+
+    #[test]
+    fn missing_files_are_not_counted() {
+        let mut missing = outcome("docs/gone.md", 0);
+        missing.kind = OutcomeKind::Missing;
+
+        let violations = check_directories(
+            &[rule("docs", Some(1), None)],
+            &[outcome("docs/a.md", 1), missing],
+            &[],
+        );
+
+        assert!(violations.is_empty(), "{violations:?}");
+    }
+
+    #[test]
+    fn unchecked_files_count_toward_checked_directories() {
+        let rules = [rule("docs/*", Some(1), None)];
+        let rest = [
+            outcome("docs/a/x.md", 1),
+            outcome("docs/b/x.md", 1),
+            outcome("docs/b/y.md", 1),
+        ];
+
+        let violations = check_directories(&rules, &[outcome("docs/a/y.md", 1)], &rest);
+
+        assert_eq!(violations.len(), 1, "{violations:?}");
+        assert_eq!(violations[0].display_path, "docs/a");
+        assert_eq!(violations[0].actual, 2);
+    }
+
+    #[test]
+    fn display_path_follows_the_working_directory() {
+        let mut nested = outcome("src/a.rs", 1);
+        nested.display_path = "../src/a.rs".to_string();
+        assert_eq!(directory_display(&nested, 3), "../src");
+
+        let mut here = outcome("src/a.rs", 1);
+        here.display_path = "a.rs".to_string();
+        assert_eq!(directory_display(&here, 3), ".");
+    }
+}
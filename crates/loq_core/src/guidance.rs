@@ -0,0 +1,122 @@
+//! Per-rule fix guidance templates.
+//!
+//! A rule's `fix_guidance` is rendered for each violating file, substituting
+//! `{path}`, `{actual}`, `{limit}`, `{metric}`, `{over}` and `{rule}`.
+//! Unknown placeholders are left as written.
+
+use crate::decide::MatchBy;
+use crate::Limit;
+
+/// The finding a guidance template is rendered for.
+#[derive(Debug, Clone, Copy)]
+pub struct GuidanceContext<'a> {
+    /// Display path of the file.
+    pub path: &'a str,
+    /// Measured value.
+    pub actual: usize,
+    /// The limit that was exceeded.
+    pub limit: Limit,
+    /// How the limit was determined.
+    pub matched_by: &'a MatchBy,
+}
+
+impl GuidanceContext<'_> {
+    fn value(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "path" => self.path.to_string(),
+            "actual" => self.actual.to_string(),
+            "limit" => self.limit.max.to_string(),
+            "metric" => self.limit.metric.label().to_string(),
+            "over" => self.actual.saturating_sub(self.limit.max).to_string(),
+            "rule" => match self.matched_by {
+                MatchBy::Rule { pattern } => pattern.clone(),
+                MatchBy::Default => "default".to_string(),
+            },
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Renders a guidance template for one finding.
+///
+/// Trailing whitespace is dropped, so multi-line TOML strings render cleanly.
+#[must_use]
+pub fn render_guidance(template: &str, context: &GuidanceContext<'_>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let replacement = after
+            .find('}')
+            .and_then(|close| Some((close, context.value(&after[..close])?)));
+        if let Some((close, value)) = replacement {
+            out.push_str(&value);
+            rest = &after[close + 1..];
+        } else {
+            out.push('{');
+            rest = after;
+        }
+    }
+    out.push_str(rest);
+    out.truncate(out.trim_end().len());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(matched_by: &MatchBy) -> GuidanceContext<'_> {
+        GuidanceContext {
+            path: "src/App.tsx",
+            actual: 412,
+            limit: Limit::lines(300),
+            matched_by,
+        }
+    }
+
+    #[test]
+    fn renders_all_placeholders() {
+        let matched_by = MatchBy::Rule {
+            pattern: "**/*.tsx".to_string(),
+        };
+        let rendered = render_guidance(
+            "{path}: {actual} {metric} > {limit} ({over} over, rule {rule})",
+            &context(&matched_by),
+        );
+        assert_eq!(
+            rendered,
+            "src/App.tsx: 412 lines > 300 (112 over, rule **/*.tsx)"
+        );
+    }
+
+    #[test]
+    fn default_match_renders_as_default() {
+        let rendered = render_guidance("rule={rule}", &context(&MatchBy::Default));
+        assert_eq!(rendered, "rule=default");
+    }
+
+    #[test]
+    fn unknown_and_unclosed_placeholders_are_kept() {
+        let rendered = render_guidance(
+            "use {hooks} for {path} and {unclosed",
+            &context(&MatchBy::Default),
+        );
+        assert_eq!(rendered, "use {hooks} for src/App.tsx and {unclosed");
+    }
+
+    #[test]
+    fn nested_brace_before_placeholder() {
+        let rendered = render_guidance("{{path}}", &context(&MatchBy::Default));
+        assert_eq!(rendered, "{src/App.tsx}");
+    }
+
+    #[test]
+    fn trailing_whitespace_is_trimmed() {
+        let rendered = render_guidance("\nSplit {path}.\n", &context(&MatchBy::Default));
+        assert_eq!(rendered, "\nSplit src/App.tsx.");
+    }
+}
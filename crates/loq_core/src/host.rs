@@ -0,0 +1,165 @@
+//! Config tables embedded in other tools' manifests.
+//!
+//! Settings can live in `loq.toml`, under `[tool.loq]` in `pyproject.toml`, or
+//! under `[package.metadata.loq]` / `[workspace.metadata.loq]` in `Cargo.toml`.
+
+use std::path::Path;
+
+/// Config file names, in discovery precedence order within one directory.
+pub const CONFIG_FILE_NAMES: [&str; 3] = ["loq.toml", "pyproject.toml", "Cargo.toml"];
+
+pub(crate) const PYPROJECT_TABLE: &[&str] = &["tool", "loq"];
+pub(crate) const CARGO_PACKAGE_TABLE: &[&str] = &["package", "metadata", "loq"];
+pub(crate) const CARGO_WORKSPACE_TABLE: &[&str] = &["workspace", "metadata", "loq"];
+
+/// The kind of file holding loq settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// A dedicated loq config file; settings live at the top level.
+    Loq,
+    /// `pyproject.toml` with a `[tool.loq]` table.
+    Pyproject,
+    /// `Cargo.toml` with a `[package.metadata.loq]` or `[workspace.metadata.loq]` table.
+    Cargo,
+}
+
+impl ConfigFormat {
+    /// Picks the format from the file name; anything unrecognized is a loq config.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.file_name().and_then(|name| name.to_str()) {
+            Some("pyproject.toml") => Self::Pyproject,
+            Some("Cargo.toml") => Self::Cargo,
+            _ => Self::Loq,
+        }
+    }
+
+    /// Returns the key path of the loq table in `text`, or `None` if the host has none.
+    ///
+    /// In `Cargo.toml`, `[package.metadata.loq]` takes precedence over
+    /// `[workspace.metadata.loq]` when both exist.
+    #[must_use]
+    pub fn table_path(self, text: &str) -> Option<&'static [&'static str]> {
+        match self {
+            Self::Loq => Some(&[]),
+            Self::Pyproject => {
+                let doc = text.parse::<toml::Table>().ok()?;
+                has_table(&doc, PYPROJECT_TABLE).then_some(PYPROJECT_TABLE)
+            }
+            Self::Cargo => {
+                let doc = text.parse::<toml::Table>().ok()?;
+                [CARGO_PACKAGE_TABLE, CARGO_WORKSPACE_TABLE]
+                    .into_iter()
+                    .find(|path| has_table(&doc, path))
+            }
+        }
+    }
+
+    /// Returns the key path a new loq table is created at when the host has none.
+    #[must_use]
+    pub fn default_table_path(self, text: &str) -> &'static [&'static str] {
+        match self {
+            Self::Loq => &[],
+            Self::Pyproject => PYPROJECT_TABLE,
+            Self::Cargo => {
+                let is_virtual_workspace = text
+                    .parse::<toml::Table>()
+                    .is_ok_and(|doc| doc.contains_key("workspace") && !doc.contains_key("package"));
+                if is_virtual_workspace {
+                    CARGO_WORKSPACE_TABLE
+                } else {
+                    CARGO_PACKAGE_TABLE
+                }
+            }
+        }
+    }
+}
+
+/// Returns true when the file at `path` holds loq settings.
+///
+/// Always true for dedicated loq config files; host manifests must contain a loq table.
+#[must_use]
+pub fn contains_config(path: &Path, text: &str) -> bool {
+    ConfigFormat::from_path(path).table_path(text).is_some()
+}
+
+fn has_table(doc: &toml::Table, path: &[&str]) -> bool {
+    let mut current = doc;
+    for key in path {
+        match current.get(*key).and_then(toml::Value::as_table) {
+            Some(table) => current = table,
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_from_file_name() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("a/pyproject.toml")),
+            ConfigFormat::Pyproject
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("Cargo.toml")),
+            ConfigFormat::Cargo
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("loq.toml")),
+            ConfigFormat::Loq
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("ci/strict.toml")),
+            ConfigFormat::Loq
+        );
+    }
+
+    #[test]
+    fn pyproject_requires_tool_loq_table() {
+        let format = ConfigFormat::Pyproject;
+        assert_eq!(
+            format.table_path("[tool.loq]\ndefault_max_lines = 1\n"),
+            Some(PYPROJECT_TABLE)
+        );
+        assert_eq!(format.table_path("[tool.ruff]\nline-length = 88\n"), None);
+        assert_eq!(format.table_path("[tool]\nloq = 1\n"), None);
+        assert_eq!(format.table_path("not toml ["), None);
+    }
+
+    #[test]
+    fn cargo_prefers_package_metadata() {
+        let format = ConfigFormat::Cargo;
+        assert_eq!(
+            format.table_path("[workspace.metadata.loq]\n"),
+            Some(CARGO_WORKSPACE_TABLE)
+        );
+        assert_eq!(
+            format.table_path("[workspace.metadata.loq]\n[package.metadata.loq]\n"),
+            Some(CARGO_PACKAGE_TABLE)
+        );
+        assert_eq!(format.table_path("[package]\nname = \"x\"\n"), None);
+    }
+
+    #[test]
+    fn cargo_default_table_follows_manifest_kind() {
+        let format = ConfigFormat::Cargo;
+        assert_eq!(
+            format.default_table_path("[workspace]\nmembers = []\n"),
+            CARGO_WORKSPACE_TABLE
+        );
+        assert_eq!(
+            format.default_table_path("[package]\nname = \"x\"\n[workspace]\n"),
+            CARGO_PACKAGE_TABLE
+        );
+    }
+
+    #[test]
+    fn contains_config_always_true_for_loq_files() {
+        assert!(contains_config(Path::new("loq.toml"), ""));
+        assert!(!contains_config(Path::new("pyproject.toml"), ""));
+    }
+}
@@ -7,21 +7,48 @@
 //! # Architecture
 //!
 //! - [`config`]: Configuration types and compilation (glob patterns → matchers)
+//! - [`pattern`]: Glob and `.gitignore`-style pattern syntaxes
 //! - [`parse`]: TOML parsing with unknown key detection and suggestions
+//! - [`diagnostic`]: Config problems with source spans and code frames
+//! - [`schema`]: JSON Schema for config files
+//! - [`host`]: Config tables embedded in `pyproject.toml` and `Cargo.toml`
 //! - [`decide`]: Rule matching logic (exclude → rules → default)
+//! - [`guidance`]: Per-rule fix guidance templates
+//! - [`severity`]: Whether a limit fails the check or only warns
 //! - [`report`]: Outcome aggregation and finding generation
+//! - [`directory`]: Directory-level budgets over a run's outcomes
+//! - [`bundle`]: Combined token budgets for files loaded together
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod bundle;
 pub mod config;
 pub mod decide;
+pub mod diagnostic;
+pub mod directory;
+pub mod guidance;
+pub mod host;
 pub mod limit;
 pub mod parse;
+pub mod pattern;
 pub mod report;
+pub mod schema;
+pub mod severity;
 
-pub use config::{CompiledConfig, ConfigError, LoqConfig, PatternList, Rule};
+pub use bundle::{Bundle, BundleMember, BundleViolation};
+pub use config::{CompiledConfig, ConfigError, ConfigLayer, LoqConfig, PatternList, Rule};
 pub use decide::{Decision, MatchBy};
-pub use limit::{Limit, Metric};
-pub use parse::parse_config;
-pub use report::{FileOutcome, Finding, FindingKind, OutcomeKind, Report, SkipReason, Summary};
+pub use diagnostic::{render_diagnostics, Diagnostic, DiagnosticKind};
+pub use directory::{DirectoryMetric, DirectoryRule, DirectoryViolation};
+pub use guidance::{render_guidance, GuidanceContext};
+pub use host::{contains_config, ConfigFormat, CONFIG_FILE_NAMES};
+pub use limit::{Limit, LineLengthUnit, Metric, TokenCounter, TokenEstimate};
+pub use parse::{parse_config, parse_overrides};
+pub use pattern::PatternSyntax;
+pub use report::{
+    FileOutcome, Finding, FindingKind, Import, LongLines, Measurement, OutcomeKind, Report,
+    SkipReason, Summary,
+};
+pub use schema::CONFIG_SCHEMA;
+pub use severity::Severity;
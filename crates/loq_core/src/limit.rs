@@ -1,6 +1,6 @@
 //! File budget units and limits.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// The unit used to measure a file budget.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
@@ -8,8 +8,16 @@ use serde::Serialize;
 pub enum Metric {
     /// Physical line count.
     Lines,
+    /// Lines containing at least one non-whitespace character.
+    CodeLines,
+    /// Lines with code outside comments and doc blocks.
+    SourceLines,
     /// Approximate token count.
     Tokens,
+    /// File size in bytes.
+    Bytes,
+    /// Length of the longest line.
+    LineLength,
 }
 
 impl Metric {
@@ -18,17 +26,135 @@ impl Metric {
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::Lines => "lines",
+            Self::CodeLines => "code_lines",
+            Self::SourceLines => "source_lines",
             Self::Tokens => "tokens",
+            Self::Bytes => "bytes",
+            Self::LineLength => "line_length",
         }
     }
 
-    /// Returns true when measurements for this metric are approximate.
+    /// Returns the plural name used in human-readable output.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::CodeLines => "code lines",
+            Self::SourceLines => "source lines",
+            Self::LineLength => "line length",
+            _ => self.as_str(),
+        }
+    }
+
+    /// Returns true when measurements for this metric are estimated unless
+    /// a tokenizer counts them.
     #[must_use]
     pub const fn is_approximate(self) -> bool {
         matches!(self, Self::Tokens)
     }
 }
 
+/// How line length is measured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineLengthUnit {
+    /// Columns a terminal displays, counting wide CJK characters and emoji as
+    /// two and combining marks as none.
+    #[default]
+    Width,
+    /// Bytes, not counting the line ending.
+    Bytes,
+}
+
+impl LineLengthUnit {
+    /// Returns the name used in config files.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Width => "width",
+            Self::Bytes => "bytes",
+        }
+    }
+}
+
+/// How a token budget is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenCounter {
+    /// Estimated from the file's size or text.
+    Estimate(TokenEstimate),
+    /// Counted exactly with the config's tokenizer.
+    Tokenizer,
+}
+
+impl Default for TokenCounter {
+    fn default() -> Self {
+        DEFAULT_COUNTER
+    }
+}
+
+const DEFAULT_COUNTER: TokenCounter = TokenCounter::Estimate(TokenEstimate::DEFAULT);
+
+/// A heuristic that estimates tokens without a tokenizer.
+///
+/// Serializes as its config key and factor, e.g. `{"bytes_per_token": 3.2}`.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenEstimate {
+    /// Bytes divided by this many bytes per token, rounded up.
+    BytesPerToken(f64),
+    /// Characters divided by this many characters per token, rounded up.
+    CharsPerToken(f64),
+    /// Whitespace-separated words times this many tokens per word, rounded up.
+    TokensPerWord(f64),
+}
+
+impl TokenEstimate {
+    /// Four bytes per token, which suits English prose and code.
+    pub const DEFAULT: Self = Self::BytesPerToken(4.0);
+
+    /// Returns the config key that selects this estimate.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::BytesPerToken(_) => "bytes_per_token",
+            Self::CharsPerToken(_) => "chars_per_token",
+            Self::TokensPerWord(_) => "tokens_per_word",
+        }
+    }
+
+    /// Returns the estimate's factor.
+    #[must_use]
+    pub const fn factor(self) -> f64 {
+        match self {
+            Self::BytesPerToken(factor)
+            | Self::CharsPerToken(factor)
+            | Self::TokensPerWord(factor) => factor,
+        }
+    }
+}
+
+impl Default for TokenEstimate {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// Factors are validated to be finite and positive, so comparing their bits
+// is a total equality.
+impl PartialEq for TokenEstimate {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key() && self.factor().to_bits() == other.factor().to_bits()
+    }
+}
+
+impl Eq for TokenEstimate {}
+
+impl std::hash::Hash for TokenEstimate {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+        self.factor().to_bits().hash(state);
+    }
+}
+
 /// A maximum file budget in one metric.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Limit {
@@ -36,6 +162,10 @@ pub struct Limit {
     pub metric: Metric,
     /// Maximum allowed value.
     pub max: usize,
+    /// How tokens are counted; ignored by other metrics.
+    pub counter: TokenCounter,
+    /// How line length is measured; ignored by other metrics.
+    pub line_length_unit: LineLengthUnit,
 }
 
 impl Limit {
@@ -45,21 +175,222 @@ impl Limit {
         Self {
             metric: Metric::Lines,
             max,
+            counter: DEFAULT_COUNTER,
+            line_length_unit: LineLengthUnit::Width,
+        }
+    }
+
+    /// Creates a limit on lines that are not blank.
+    #[must_use]
+    pub const fn code_lines(max: usize) -> Self {
+        Self {
+            metric: Metric::CodeLines,
+            max,
+            counter: DEFAULT_COUNTER,
+            line_length_unit: LineLengthUnit::Width,
         }
     }
 
-    /// Creates an approximate token limit.
+    /// Creates a limit on lines with code outside comments.
+    #[must_use]
+    pub const fn source_lines(max: usize) -> Self {
+        Self {
+            metric: Metric::SourceLines,
+            max,
+            counter: DEFAULT_COUNTER,
+            line_length_unit: LineLengthUnit::Width,
+        }
+    }
+
+    /// Creates a token limit, estimated at four bytes per token.
     #[must_use]
     pub const fn tokens(max: usize) -> Self {
         Self {
             metric: Metric::Tokens,
             max,
+            counter: DEFAULT_COUNTER,
+            line_length_unit: LineLengthUnit::Width,
+        }
+    }
+
+    /// Creates a byte-size limit.
+    #[must_use]
+    pub const fn bytes(max: usize) -> Self {
+        Self {
+            metric: Metric::Bytes,
+            max,
+            counter: DEFAULT_COUNTER,
+            line_length_unit: LineLengthUnit::Width,
+        }
+    }
+
+    /// Creates a limit on the length of the longest line.
+    #[must_use]
+    pub const fn line_length(max: usize) -> Self {
+        Self {
+            metric: Metric::LineLength,
+            max,
+            counter: DEFAULT_COUNTER,
+            line_length_unit: LineLengthUnit::Width,
+        }
+    }
+
+    /// Returns the limit with tokens counted by `counter`. Limits in other
+    /// metrics are returned unchanged.
+    #[must_use]
+    pub const fn counted_by(self, counter: TokenCounter) -> Self {
+        match self.metric {
+            Metric::Tokens => Self { counter, ..self },
+            _ => self,
+        }
+    }
+
+    /// Returns the limit with line length measured in `unit`. Limits in
+    /// other metrics are returned unchanged.
+    #[must_use]
+    pub const fn measured_in(self, unit: LineLengthUnit) -> Self {
+        match self.metric {
+            Metric::LineLength => Self {
+                line_length_unit: unit,
+                ..self
+            },
+            _ => self,
+        }
+    }
+
+    /// Returns the singular unit this limit's values are counted in, e.g.
+    /// `line`, or `column` for line length measured in display width.
+    #[must_use]
+    pub const fn unit(self) -> &'static str {
+        match self.metric {
+            Metric::Lines => "line",
+            Metric::CodeLines => "code line",
+            Metric::SourceLines => "source line",
+            Metric::Tokens => "token",
+            Metric::Bytes => "byte",
+            Metric::LineLength => match self.line_length_unit {
+                LineLengthUnit::Width => "column",
+                LineLengthUnit::Bytes => "byte",
+            },
+        }
+    }
+
+    /// Returns the estimate this limit's tokens are counted with, or `None`
+    /// for exact counts and other metrics.
+    #[must_use]
+    pub const fn token_estimate(self) -> Option<TokenEstimate> {
+        match (self.metric, self.counter) {
+            (Metric::Tokens, TokenCounter::Estimate(estimate)) => Some(estimate),
+            _ => None,
         }
     }
 
     /// Returns true when measurements for this limit are approximate.
     #[must_use]
     pub const fn is_approximate(self) -> bool {
-        self.metric.is_approximate()
+        self.metric.is_approximate() && matches!(self.counter, TokenCounter::Estimate(_))
+    }
+
+    /// Returns true when `actual` is within budget but above the `warn_at`
+    /// fraction of it, e.g. 0.9 for 90%.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn is_near(self, actual: usize, warn_at: f64) -> bool {
+        actual <= self.max && actual as f64 > self.max as f64 * warn_at
+    }
+}
+
+const BYTE_UNITS: [(&str, usize); 4] = [
+    ("GiB", 1 << 30),
+    ("MiB", 1 << 20),
+    ("KiB", 1 << 10),
+    ("B", 1),
+];
+
+/// Parses a byte size such as `2048`, `"64 KiB"` or `"1.5 MiB"`.
+///
+/// Units are binary (`KiB` = 1024 bytes) and case-insensitive.
+///
+/// # Errors
+///
+/// Returns a description of the problem if `text` is not a size.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn parse_byte_size(text: &str) -> Result<usize, String> {
+    let text = text.trim();
+    let split = text
+        .find(|ch: char| !(ch.is_ascii_digit() || ch == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let unit = unit.trim_start();
+    let scale = if unit.is_empty() {
+        Some(1)
+    } else {
+        BYTE_UNITS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+            .map(|(_, scale)| *scale)
+    };
+    match (number.parse::<f64>(), scale) {
+        (Ok(value), Some(scale)) => Ok((value * scale as f64).round() as usize),
+        _ => Err(format!(
+            "invalid size '{text}', expected bytes or a number with a B, KiB, MiB or GiB suffix"
+        )),
+    }
+}
+
+/// Formats a byte count with the largest binary unit it reaches, e.g. `1.5 MiB`.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: usize) -> String {
+    let (unit, scale) = BYTE_UNITS
+        .iter()
+        .find(|(_, scale)| bytes >= *scale)
+        .copied()
+        .unwrap_or(("B", 1));
+    if scale == 1 {
+        return format!("{bytes} B");
+    }
+    let value = format!("{:.1}", bytes as f64 / scale as f64);
+    format!("{} {unit}", value.trim_end_matches(".0"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn near_means_above_fraction_and_within_budget() {
+        let limit = Limit::lines(100);
+        assert!(!limit.is_near(90, 0.9));
+        assert!(limit.is_near(91, 0.9));
+        assert!(limit.is_near(100, 0.9));
+        assert!(!limit.is_near(101, 0.9));
+        assert!(!limit.is_near(100, 1.0));
+    }
+
+    #[test]
+    fn byte_sizes_parse_with_binary_units() {
+        assert_eq!(parse_byte_size("2048"), Ok(2048));
+        assert_eq!(parse_byte_size("512 B"), Ok(512));
+        assert_eq!(parse_byte_size("64KiB"), Ok(64 * 1024));
+        assert_eq!(parse_byte_size("1.5 mib"), Ok(1024 * 1024 * 3 / 2));
+        assert_eq!(parse_byte_size("2 GiB"), Ok(2 << 30));
+        assert!(parse_byte_size("64 KB").is_err());
+        assert!(parse_byte_size("lots").is_err());
+        assert!(parse_byte_size("").is_err());
+    }
+
+    #[test]
+    fn bytes_format_with_largest_unit() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 20), "3 MiB");
+        assert_eq!(format_bytes(5 << 30), "5 GiB");
     }
 }
@@ -1,176 +1,453 @@
 //! TOML configuration parsing with validation.
 //!
-//! Parses `loq.toml` files and detects unknown keys with suggestions.
+//! Parses `loq.toml` files and loq tables embedded in `pyproject.toml` or
+//! `Cargo.toml`, detects unknown keys with suggestions, and resolves
+//! `extends` inheritance between config files.
 
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
-use serde::{Deserialize, Deserializer};
+use crate::config::{ConfigError, ConfigLayer, LoqConfig, Rule};
+use crate::diagnostic::DiagnosticKind;
+use crate::{Limit, Metric, PatternSyntax, TokenEstimate};
 
-use crate::config::{ConfigError, LoqConfig, Rule, DEFAULT_RESPECT_GITIGNORE};
-use crate::Limit;
+mod aggregate;
+mod problems;
+mod raw;
 
-#[derive(Deserialize)]
-struct RawConfig {
-    default_max_lines: Option<usize>,
-    default_max_tokens: Option<usize>,
-    #[serde(default = "default_respect_gitignore")]
-    respect_gitignore: bool,
-    #[serde(default)]
-    exclude: Vec<String>,
-    #[serde(default)]
-    rules: Vec<RawRule>,
-    #[serde(default)]
-    fix_guidance: Option<String>,
+use aggregate::{parse_bundles, parse_directory_rules};
+use problems::Problems;
+use raw::{deserialize_raw, RawRule};
+
+/// Parses a `loq.toml` file and validates its structure.
+///
+/// `pyproject.toml` and `Cargo.toml` are read from their embedded loq table;
+/// see [`crate::host`].
+///
+/// Returns an error if the TOML is malformed or contains unknown keys.
+/// Unknown keys trigger a suggestion if a similar valid key exists.
+///
+/// Files named in `extends` are read relative to `path` and inherited
+/// depth-first, in list order, before this file's own settings apply.
+pub fn parse_config(path: &Path, text: &str) -> Result<LoqConfig, ConfigError> {
+    let mut chain = vec![normalize_path(path)];
+    Ok(resolve_layer(path, text, &mut chain)?.into_config())
 }
 
-#[derive(Deserialize)]
-struct RawRule {
-    #[serde(deserialize_with = "deserialize_string_or_vec")]
-    path: Vec<String>,
-    max_lines: Option<usize>,
-    max_tokens: Option<usize>,
+/// Parses command-line `KEY=VALUE` overrides into a config layer.
+///
+/// Values are TOML, e.g. `default_max_lines=300` or `exclude=["gen/**"]`, and
+/// go through the same validation as config files. Later assignments win.
+pub fn parse_overrides(assignments: &[String]) -> Result<ConfigLayer, ConfigError> {
+    let mut overrides = ConfigLayer::default();
+    for assignment in assignments {
+        let invalid = |message: String| ConfigError::Override {
+            assignment: assignment.clone(),
+            message,
+        };
+        let Some((key, value)) = assignment.split_once('=') else {
+            return Err(invalid("expected KEY=VALUE".to_string()));
+        };
+        if key.trim() == "extends" {
+            return Err(invalid("extends cannot be overridden".to_string()));
+        }
+        let text = format!("{} = {value}\n", key.trim());
+        let (layer, _) =
+            parse_layer(Path::new("--set"), &text).map_err(|err| invalid(override_message(err)))?;
+        overrides = layer.inherit(overrides);
+    }
+    Ok(overrides)
 }
 
-fn deserialize_string_or_vec<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StringOrVec {
-        String(String),
-        Vec(Vec<String>),
+/// Describes a config error without the file location, which is meaningless
+/// for a one-line override.
+fn override_message(err: ConfigError) -> String {
+    match err {
+        ConfigError::Diagnostics { diagnostics, .. } => diagnostics
+            .iter()
+            .map(|diagnostic| match &diagnostic.help {
+                Some(help) => format!("{}, {help}", diagnostic.message),
+                None => diagnostic.message.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("; "),
+        other => other.to_string(),
     }
+}
 
-    match StringOrVec::deserialize(deserializer)? {
-        StringOrVec::String(value) => Ok(vec![value]),
-        StringOrVec::Vec(values) => Ok(values),
+fn resolve_layer(
+    path: &Path,
+    text: &str,
+    chain: &mut Vec<PathBuf>,
+) -> Result<ConfigLayer, ConfigError> {
+    let (layer, extends) = parse_layer(path, text)?;
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut inherited = ConfigLayer::default();
+    for target in extends {
+        let parent_path = normalize_path(&dir.join(target));
+        let is_cycle = chain.contains(&parent_path);
+        chain.push(parent_path.clone());
+        if is_cycle {
+            return Err(ConfigError::ExtendsCycle {
+                chain: chain.clone(),
+            });
+        }
+        let Ok(parent_text) = std::fs::read_to_string(&parent_path) else {
+            return Err(ConfigError::ExtendsMissing {
+                chain: chain.clone(),
+            });
+        };
+        let parent = resolve_layer(&parent_path, &parent_text, chain)?;
+        chain.pop();
+        inherited = parent.inherit(inherited);
     }
+
+    Ok(layer.inherit(inherited))
 }
 
-const fn default_respect_gitignore() -> bool {
-    DEFAULT_RESPECT_GITIGNORE
+/// Resolves `relative` against the directory of the config file at `path`,
+/// so a setting inherited through `extends` keeps pointing at the same file.
+/// The result is relative exactly when `path` is.
+fn declared_path(path: &Path, relative: &str) -> PathBuf {
+    path.parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(relative)
 }
 
-/// Parses a `loq.toml` file and validates its structure.
-///
-/// Returns an error if the TOML is malformed or contains unknown keys.
-/// Unknown keys trigger a suggestion if a similar valid key exists.
-pub fn parse_config(path: &Path, text: &str) -> Result<LoqConfig, ConfigError> {
-    let deserializer = toml::Deserializer::new(text);
-    let mut unknown = Vec::new();
-    let raw: RawConfig = serde_ignored::deserialize(deserializer, |path| {
-        if let Some(key) = extract_unknown_key_name(&path) {
-            unknown.push(key);
+/// Normalizes `.` and `..` components without touching the filesystem.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) {
+                    normalized.pop();
+                } else {
+                    normalized.push(component);
+                }
+            }
+            _ => normalized.push(component),
         }
-    })
-    .map_err(|err| ConfigError::Toml {
-        path: path.to_path_buf(),
-        message: err.to_string(),
-        line_col: err
-            .span()
-            .and_then(|span| line_col_from_offset(text, span.start)),
-    })?;
+    }
+    normalized
+}
+
+fn parse_layer(path: &Path, text: &str) -> Result<(ConfigLayer, Vec<String>), ConfigError> {
+    let (raw, table, unknown) = deserialize_raw(path, text)?;
+    let mut problems = Problems::new(text, table);
 
-    if let Some(key) = unknown.into_iter().next() {
-        let line_col = find_key_location(text, &key);
+    for (segments, key) in unknown {
+        let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+        let span = problems.spans.key(&segments);
+        let message = format!("unknown key '{key}'");
         let suggestion = suggest_key(&key);
-        return Err(ConfigError::UnknownKey {
-            path: path.to_path_buf(),
-            key,
-            line_col,
-            suggestion,
-        });
+        problems.push(
+            DiagnosticKind::UnknownKey { key, suggestion },
+            message,
+            span,
+        );
     }
 
-    let default_limit = match (raw.default_max_lines, raw.default_max_tokens) {
-        (Some(_), Some(_)) => {
-            return Err(ConfigError::InvalidLimit {
-                path: path.to_path_buf(),
-                message: "set only one of default_max_lines or default_max_tokens".to_string(),
-            });
+    let defaults = [
+        ("default_max_lines", raw.default_max_lines.map(Limit::lines)),
+        (
+            "default_max_code_lines",
+            raw.default_max_code_lines.map(Limit::code_lines),
+        ),
+        (
+            "default_max_source_lines",
+            raw.default_max_source_lines.map(Limit::source_lines),
+        ),
+        (
+            "default_max_tokens",
+            raw.default_max_tokens.map(Limit::tokens),
+        ),
+        ("default_max_bytes", raw.default_max_bytes.map(Limit::bytes)),
+        (
+            "default_max_line_length",
+            raw.default_max_line_length.map(Limit::line_length),
+        ),
+    ];
+    let default_limit = match set_values(&defaults).as_slice() {
+        [] => None,
+        [(_, limit)] => Some(*limit),
+        [_, (key, _), ..] => {
+            let span = problems.spans.key(&[key]);
+            problems.invalid(format!("set only one of {}", one_of(&defaults)), span);
+            None
         }
-        (Some(lines), None) => Some(Limit::lines(lines)),
-        (None, Some(tokens)) => Some(Limit::tokens(tokens)),
-        (None, None) => None,
     };
-
-    let mut rules = Vec::with_capacity(raw.rules.len());
-    for raw_rule in raw.rules {
-        let limit = match (raw_rule.max_lines, raw_rule.max_tokens) {
-            (Some(lines), None) => Limit::lines(lines),
-            (None, Some(tokens)) => Limit::tokens(tokens),
-            (Some(_), Some(_)) => {
-                return Err(ConfigError::InvalidLimit {
-                    path: path.to_path_buf(),
-                    message: format!(
-                        "rule for '{}' must set only one of max_lines or max_tokens",
-                        raw_rule.path.join(", ")
-                    ),
-                });
-            }
-            (None, None) => {
-                return Err(ConfigError::InvalidLimit {
-                    path: path.to_path_buf(),
-                    message: format!(
-                        "rule for '{}' must set max_lines or max_tokens",
-                        raw_rule.path.join(", ")
-                    ),
-                });
-            }
-        };
-        rules.push(Rule {
-            paths: raw_rule.path,
-            limit,
-        });
+    problems.check_warn_at(raw.warn_at, "warn_at", &["warn_at"]);
+    let estimates = estimate_keys(
+        raw.bytes_per_token,
+        raw.chars_per_token,
+        raw.tokens_per_word,
+    );
+    let token_estimate = parse_token_estimate(&mut problems, &estimates, &[], "");
+    if let (Some(_), Some(estimate)) = (&raw.tokenizer, token_estimate) {
+        let span = problems.spans.key(&[estimate.key()]);
+        problems.invalid(
+            format!("set only one of tokenizer, {}", one_of(&estimates)),
+            span,
+        );
+    }
+    let syntax = raw.pattern_syntax.unwrap_or_default();
+    for (key, patterns) in [
+        ("include", raw.include.as_deref().unwrap_or_default()),
+        ("exclude", &raw.exclude),
+    ] {
+        for (index, pattern) in patterns.iter().enumerate() {
+            let span = problems.spans.value(&[key, &index.to_string()]);
+            problems.check_glob(pattern, syntax, span);
+        }
     }
 
-    Ok(LoqConfig {
+    let rules = raw
+        .rules
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, raw_rule)| {
+            parse_rule(&mut problems, &index.to_string(), raw_rule, syntax)
+        })
+        .collect();
+    let directory_rules = parse_directory_rules(&mut problems, raw.directory_rules);
+    let bundles = parse_bundles(&mut problems, raw.bundles, syntax);
+    problems.finish(path)?;
+
+    let layer = ConfigLayer {
         default_limit,
+        default_severity: raw.default_severity,
         respect_gitignore: raw.respect_gitignore,
+        pattern_syntax: raw.pattern_syntax,
+        include: raw.include,
         exclude: raw.exclude,
         rules,
+        directory_rules,
+        bundles,
+        warn_at: raw.warn_at,
         fix_guidance: raw.fix_guidance,
-    })
+        tokenizer: raw.tokenizer.map(|vocab| declared_path(path, &vocab)),
+        token_estimate,
+        line_length_unit: raw.line_length_unit,
+    };
+    Ok((layer, raw.extends))
 }
 
-fn extract_unknown_key_name(path: &serde_ignored::Path) -> Option<String> {
-    let path_str = path.to_string();
-    let mut last = path_str.split('.').next_back().unwrap_or(&path_str);
-    if let Some(pos) = last.find('[') {
-        last = &last[..pos];
+/// Validates one `[[rules]]` entry, or returns `None` if it cannot be used.
+fn parse_rule(
+    problems: &mut Problems,
+    index: &str,
+    raw_rule: RawRule,
+    syntax: PatternSyntax,
+) -> Option<Rule> {
+    let paths = raw_rule.path.join(", ");
+    problems.check_warn_at(
+        raw_rule.warn_at,
+        &format!("warn_at for '{paths}'"),
+        &["rules", index, "warn_at"],
+    );
+    let mut positive = Vec::new();
+    let mut exclude = Vec::new();
+    for (key, patterns) in [("path", &raw_rule.path), ("exclude", &raw_rule.exclude)] {
+        for (nth, pattern) in patterns.iter().enumerate() {
+            let span = problems
+                .spans
+                .value(&["rules", index, key, &nth.to_string()])
+                .or_else(|| problems.spans.value(&["rules", index, key]));
+            let negated = pattern.strip_prefix('!');
+            let glob = negated.unwrap_or(pattern);
+            problems.check_glob(glob, syntax, span);
+            if key == "path" && negated.is_none() {
+                positive.push(pattern.clone());
+            } else {
+                exclude.push(glob.to_string());
+            }
+        }
     }
-    if last.is_empty() {
-        None
-    } else {
-        Some(last.to_string())
+    if positive.is_empty() {
+        let span = problems.spans.value(&["rules", index, "path"]);
+        problems.invalid(
+            format!("rule for '{paths}' needs a path that is not negated with '!'"),
+            span,
+        );
+        return None;
+    }
+    let keys = [
+        ("max_lines", raw_rule.max_lines.map(Limit::lines)),
+        (
+            "max_code_lines",
+            raw_rule.max_code_lines.map(Limit::code_lines),
+        ),
+        (
+            "max_source_lines",
+            raw_rule.max_source_lines.map(Limit::source_lines),
+        ),
+        ("max_tokens", raw_rule.max_tokens.map(Limit::tokens)),
+        ("max_bytes", raw_rule.max_bytes.map(Limit::bytes)),
+        (
+            "max_line_length",
+            raw_rule.max_line_length.map(Limit::line_length),
+        ),
+    ];
+    let limits = set_values(&keys)
+        .into_iter()
+        .map(|(_, limit)| limit)
+        .collect::<Vec<_>>();
+    if limits.is_empty() {
+        let span = problems.spans.key(&["rules", index, "path"]);
+        problems.invalid(
+            format!(
+                "rule for '{paths}' must set at least one of {}",
+                one_of(&keys)
+            ),
+            span,
+        );
+        return None;
+    }
+    let estimates = estimate_keys(
+        raw_rule.bytes_per_token,
+        raw_rule.chars_per_token,
+        raw_rule.tokens_per_word,
+    );
+    let context = format!(" for '{paths}'");
+    let token_estimate = parse_token_estimate(problems, &estimates, &["rules", index], &context);
+    if let Some(estimate) =
+        token_estimate.filter(|_| limits.iter().all(|limit| limit.metric != Metric::Tokens))
+    {
+        let span = problems.spans.key(&["rules", index, estimate.key()]);
+        problems.invalid(
+            format!(
+                "rule for '{paths}' sets {} but not max_tokens",
+                estimate.key()
+            ),
+            span,
+        );
     }
+    Some(Rule {
+        paths: positive,
+        exclude,
+        limits,
+        severity: raw_rule.severity,
+        warn_at: raw_rule.warn_at,
+        fix_guidance: raw_rule.fix_guidance,
+        token_estimate,
+        follow_imports: raw_rule.follow_imports,
+    })
 }
 
-fn find_key_location(text: &str, key: &str) -> Option<(usize, usize)> {
-    for (line_idx, line) in text.lines().enumerate() {
-        let trimmed = line.trim_start();
-        if let Some(rest) = trimmed.strip_prefix(key) {
-            if rest.trim_start().starts_with('=') {
-                let leading = line.len().saturating_sub(trimmed.len());
-                return Some((line_idx + 1, leading + 1));
-            }
+/// Pairs each token estimate key with the estimate it sets.
+fn estimate_keys(
+    bytes_per_token: Option<f64>,
+    chars_per_token: Option<f64>,
+    tokens_per_word: Option<f64>,
+) -> [(&'static str, Option<TokenEstimate>); 3] {
+    [
+        (
+            "bytes_per_token",
+            bytes_per_token.map(TokenEstimate::BytesPerToken),
+        ),
+        (
+            "chars_per_token",
+            chars_per_token.map(TokenEstimate::CharsPerToken),
+        ),
+        (
+            "tokens_per_word",
+            tokens_per_word.map(TokenEstimate::TokensPerWord),
+        ),
+    ]
+}
+
+/// Validates the token estimate keys of the table at `table`, returning the
+/// estimate it sets. `context` follows the key in messages, e.g. ` for 'a'`.
+fn parse_token_estimate(
+    problems: &mut Problems,
+    estimates: &[(&str, Option<TokenEstimate>)],
+    table: &[&str],
+    context: &str,
+) -> Option<TokenEstimate> {
+    let set = set_values(estimates);
+    for (key, estimate) in &set {
+        let factor = estimate.factor();
+        if !(factor.is_finite() && factor > 0.0) {
+            let span = problems.spans.value(&[table, &[key]].concat());
+            problems.invalid(
+                format!("{key}{context} must be greater than 0, got {factor}"),
+                span,
+            );
+        }
+    }
+    match set.as_slice() {
+        [] => None,
+        [(_, estimate)] => Some(*estimate),
+        [_, (key, _), ..] => {
+            let span = problems.spans.key(&[table, &[key]].concat());
+            problems.invalid(
+                format!("set only one of {}{context}", one_of(estimates)),
+                span,
+            );
+            None
         }
     }
-    None
+}
+
+/// Returns the values a table sets, with the key that sets each.
+fn set_values<'a, T: Copy>(values: &[(&'a str, Option<T>)]) -> Vec<(&'a str, T)> {
+    values
+        .iter()
+        .filter_map(|(key, value)| Some((*key, (*value)?)))
+        .collect()
+}
+
+/// Lists the keys of `values` as alternatives, e.g. `a, b or c`.
+fn one_of<T>(values: &[(&str, Option<T>)]) -> String {
+    let keys = values.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+    match keys.split_last() {
+        Some((last, [])) => (*last).to_string(),
+        Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+        None => String::new(),
+    }
 }
 
 fn suggest_key(key: &str) -> Option<String> {
     let candidates = [
+        "extends",
         "default_max_lines",
+        "default_max_code_lines",
+        "default_max_source_lines",
         "default_max_tokens",
+        "default_max_bytes",
+        "default_max_line_length",
+        "default_severity",
         "respect_gitignore",
+        "pattern_syntax",
+        "include",
         "exclude",
         "rules",
+        "directory_rules",
+        "bundles",
+        "warn_at",
         "path",
         "max_lines",
+        "max_code_lines",
+        "max_source_lines",
         "max_tokens",
+        "max_bytes",
+        "max_line_length",
+        "max_files",
+        "max_total_lines",
+        "follow_imports",
+        "name",
+        "severity",
         "fix_guidance",
+        "tokenizer",
+        "bytes_per_token",
+        "chars_per_token",
+        "tokens_per_word",
+        "line_length_unit",
     ];
     let mut best = None;
     let mut best_score = usize::MAX;
@@ -188,255 +465,5 @@ fn suggest_key(key: &str) -> Option<String> {
     }
 }
 
-fn line_col_from_offset(text: &str, offset: usize) -> Option<(usize, usize)> {
-    if offset > text.len() {
-        return None;
-    }
-    let mut line = 1usize;
-    let mut col = 1usize;
-    for (idx, ch) in text.char_indices() {
-        if idx >= offset {
-            break;
-        }
-        if ch == '\n' {
-            line += 1;
-            col = 1;
-        } else {
-            col += 1;
-        }
-    }
-    Some((line, col))
-}
-
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn unknown_key_detection() {
-        let text = "default_max_lines = 500\nmax_line = 10\n";
-        let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
-        match err {
-            ConfigError::UnknownKey {
-                key, suggestion, ..
-            } => {
-                assert_eq!(key, "max_line");
-                assert_eq!(suggestion, Some("max_lines".to_string()));
-            }
-            _ => panic!("expected unknown key"),
-        }
-    }
-
-    #[test]
-    fn rule_parsed_correctly() {
-        let text = "default_max_lines = 500\n[[rules]]\npath = \"**/*.rs\"\nmax_lines = 10\n";
-        let config = parse_config(Path::new("loq.toml"), text).unwrap();
-        assert_eq!(config.rules.len(), 1);
-        assert_eq!(config.rules[0].limit, Limit::lines(10));
-    }
-
-    #[test]
-    fn token_rule_parsed_correctly() {
-        let text =
-            "default_max_lines = 500\n[[rules]]\npath = \"prompts/**/*.md\"\nmax_tokens = 8000\n";
-        let config = parse_config(Path::new("loq.toml"), text).unwrap();
-        assert_eq!(config.rules.len(), 1);
-        assert_eq!(config.rules[0].limit, Limit::tokens(8000));
-    }
-
-    #[test]
-    fn both_default_budgets_are_invalid() {
-        let text = "default_max_lines = 500\ndefault_max_tokens = 2000\n";
-        let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
-        assert!(err
-            .to_string()
-            .contains("only one of default_max_lines or default_max_tokens"));
-    }
-
-    #[test]
-    fn rule_with_both_budgets_is_invalid() {
-        let text = "[[rules]]\npath = \"**/*.md\"\nmax_lines = 100\nmax_tokens = 1000\n";
-        let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
-        assert!(err
-            .to_string()
-            .contains("only one of max_lines or max_tokens"));
-    }
-
-    #[test]
-    fn rule_without_budget_is_invalid() {
-        let text = "[[rules]]\npath = \"**/*.md\"\n";
-        let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
-        assert!(err.to_string().contains("must set max_lines or max_tokens"));
-    }
-
-    #[test]
-    fn respect_gitignore_defaults_true() {
-        let text = "default_max_lines = 500\n";
-        let config = parse_config(Path::new("loq.toml"), text).unwrap();
-        assert!(config.respect_gitignore);
-    }
-
-    #[test]
-    fn invalid_toml_reports_error() {
-        let text = "default_max_lines =\n";
-        let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
-        match err {
-            ConfigError::Toml { .. } => {}
-            _ => panic!("expected toml error"),
-        }
-    }
-
-    #[test]
-    fn unknown_key_without_location() {
-        let text = "rules = [{ path = \"src/*.rs\", max_lines = 10, max_line = 20 }]\n";
-        let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
-        match err {
-            ConfigError::UnknownKey { line_col, .. } => {
-                assert!(line_col.is_none());
-            }
-            _ => panic!("expected unknown key"),
-        }
-    }
-
-    #[test]
-    fn unknown_key_without_suggestion() {
-        let text = "banana = 1\n";
-        let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
-        match err {
-            ConfigError::UnknownKey { suggestion, .. } => {
-                assert!(suggestion.is_none());
-            }
-            _ => panic!("expected unknown key"),
-        }
-    }
-
-    #[test]
-    fn line_col_from_offset_handles_newlines() {
-        let text = "line1\nline2\nline3";
-        let (line, col) = line_col_from_offset(text, 6).unwrap();
-        assert_eq!(line, 2);
-        assert_eq!(col, 1);
-    }
-
-    #[test]
-    fn line_col_from_offset_out_of_bounds() {
-        let text = "short";
-        assert!(line_col_from_offset(text, 100).is_none());
-    }
-
-    #[test]
-    fn extract_unknown_key_name_with_array_index() {
-        let path = serde_ignored::Path::Map {
-            parent: &serde_ignored::Path::Root,
-            key: "rules[0]".to_string(),
-        };
-        let key = extract_unknown_key_name(&path);
-        assert_eq!(key, Some("rules".to_string()));
-    }
-
-    #[test]
-    fn extract_unknown_key_name_empty_returns_none() {
-        let path = serde_ignored::Path::Map {
-            parent: &serde_ignored::Path::Root,
-            key: "[0]".to_string(),
-        };
-        let key = extract_unknown_key_name(&path);
-        assert!(key.is_none());
-    }
-
-    #[test]
-    fn find_key_location_finds_key() {
-        let text = "  typo_key = 1\n";
-        let loc = find_key_location(text, "typo_key");
-        assert_eq!(loc, Some((1, 3)));
-    }
-
-    #[test]
-    fn find_key_location_not_found() {
-        let text = "other = 1\n";
-        let loc = find_key_location(text, "missing");
-        assert!(loc.is_none());
-    }
-
-    #[test]
-    fn negative_max_lines_reports_error() {
-        let text = "default_max_lines = -1\n";
-        let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
-        match err {
-            ConfigError::Toml { .. } => {}
-            _ => panic!("expected Toml error, got {err:?}"),
-        }
-    }
-
-    #[test]
-    fn rule_path_accepts_string() {
-        let text = r#"
-[[rules]]
-path = "**/*.rs"
-max_lines = 100
-"#;
-        let config = parse_config(Path::new("loq.toml"), text).unwrap();
-        assert_eq!(config.rules.len(), 1);
-        assert_eq!(config.rules[0].paths, vec!["**/*.rs"]);
-    }
-
-    #[test]
-    fn rule_path_accepts_array() {
-        let text = r#"
-[[rules]]
-path = ["src/a.rs", "src/b.rs"]
-max_lines = 100
-"#;
-        let config = parse_config(Path::new("loq.toml"), text).unwrap();
-        assert_eq!(config.rules.len(), 1);
-        assert_eq!(config.rules[0].paths, vec!["src/a.rs", "src/b.rs"]);
-    }
-
-    #[test]
-    fn rule_path_array_single_element() {
-        let text = r#"
-[[rules]]
-path = ["only_one.rs"]
-max_lines = 100
-"#;
-        let config = parse_config(Path::new("loq.toml"), text).unwrap();
-        assert_eq!(config.rules[0].paths, vec!["only_one.rs"]);
-    }
-
-    #[test]
-    fn fix_guidance_parsed_correctly() {
-        let text = r#"
-default_max_lines = 500
-fix_guidance = "Split large files into smaller modules."
-"#;
-        let config = parse_config(Path::new("loq.toml"), text).unwrap();
-        assert_eq!(
-            config.fix_guidance,
-            Some("Split large files into smaller modules.".to_string())
-        );
-    }
-
-    #[test]
-    fn fix_guidance_multiline_string() {
-        let text = r#"
-default_max_lines = 500
-fix_guidance = """
-Consider splitting large files:
-- Extract functions into modules
-- Move tests to test files
-"""
-"#;
-        let config = parse_config(Path::new("loq.toml"), text).unwrap();
-        assert!(config.fix_guidance.is_some());
-        let guidance = config.fix_guidance.unwrap();
-        assert!(guidance.contains("Consider splitting large files:"));
-        assert!(guidance.contains("Extract functions into modules"));
-    }
-
-    #[test]
-    fn fix_guidance_defaults_to_none() {
-        let text = "default_max_lines = 500\n";
-        let config = parse_config(Path::new("loq.toml"), text).unwrap();
-        assert!(config.fix_guidance.is_none());
-    }
-}
+mod tests;
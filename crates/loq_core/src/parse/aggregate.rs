@@ -0,0 +1,87 @@
+//! Validation of entries that budget several files at once.
+
+use crate::bundle::Bundle;
+use crate::directory::DirectoryRule;
+use crate::PatternSyntax;
+
+use super::problems::Problems;
+use super::raw::{RawBundle, RawDirectoryRule};
+
+/// Validates the `[[directory_rules]]` entries, dropping those that cannot be used.
+pub(super) fn parse_directory_rules(
+    problems: &mut Problems,
+    raw_rules: Vec<RawDirectoryRule>,
+) -> Vec<DirectoryRule> {
+    raw_rules
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, raw_rule)| {
+            parse_directory_rule(problems, &index.to_string(), raw_rule)
+        })
+        .collect()
+}
+
+/// Validates the `[[bundles]]` entries' patterns.
+pub(super) fn parse_bundles(
+    problems: &mut Problems,
+    raw_bundles: Vec<RawBundle>,
+    syntax: PatternSyntax,
+) -> Vec<Bundle> {
+    raw_bundles
+        .into_iter()
+        .enumerate()
+        .map(|(index, raw_bundle)| parse_bundle(problems, &index.to_string(), raw_bundle, syntax))
+        .collect()
+}
+
+/// Validates one `[[directory_rules]]` entry, or returns `None` if it cannot be used.
+fn parse_directory_rule(
+    problems: &mut Problems,
+    index: &str,
+    raw_rule: RawDirectoryRule,
+) -> Option<DirectoryRule> {
+    for (nth, pattern) in raw_rule.path.iter().enumerate() {
+        let span = problems
+            .spans
+            .value(&["directory_rules", index, "path", &nth.to_string()])
+            .or_else(|| problems.spans.value(&["directory_rules", index, "path"]));
+        problems.check_glob(pattern, PatternSyntax::Glob, span);
+    }
+    if raw_rule.max_files.is_none() && raw_rule.max_total_lines.is_none() {
+        let span = problems.spans.key(&["directory_rules", index, "path"]);
+        problems.invalid(
+            format!(
+                "directory rule for '{}' must set at least one of max_files or max_total_lines",
+                raw_rule.path.join(", ")
+            ),
+            span,
+        );
+        return None;
+    }
+    Some(DirectoryRule {
+        paths: raw_rule.path,
+        max_files: raw_rule.max_files,
+        max_total_lines: raw_rule.max_total_lines,
+    })
+}
+
+/// Validates one `[[bundles]]` entry's patterns.
+fn parse_bundle(
+    problems: &mut Problems,
+    index: &str,
+    raw_bundle: RawBundle,
+    syntax: PatternSyntax,
+) -> Bundle {
+    for (nth, pattern) in raw_bundle.path.iter().enumerate() {
+        let span = problems
+            .spans
+            .value(&["bundles", index, "path", &nth.to_string()])
+            .or_else(|| problems.spans.value(&["bundles", index, "path"]));
+        problems.check_glob(pattern, syntax, span);
+    }
+    Bundle {
+        name: raw_bundle.name,
+        paths: raw_bundle.path,
+        max_tokens: raw_bundle.max_tokens,
+    }
+}
@@ -0,0 +1,85 @@
+//! Validation problems collected while parsing one config file.
+
+use std::ops::Range;
+use std::path::Path;
+
+use crate::config::ConfigError;
+use crate::diagnostic::{Diagnostic, DiagnosticKind, SourceSpans};
+use crate::pattern::{Matcher, PatternSyntax};
+
+/// Problems collected while validating one config file.
+pub(super) struct Problems<'a> {
+    text: &'a str,
+    pub(super) spans: SourceSpans,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Problems<'a> {
+    pub(super) fn new(text: &'a str, table: &[&str]) -> Self {
+        Self {
+            text,
+            spans: SourceSpans::new(text, table),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub(super) fn push(
+        &mut self,
+        kind: DiagnosticKind,
+        message: String,
+        span: Option<Range<usize>>,
+    ) {
+        self.diagnostics
+            .push(Diagnostic::new(kind, message, span, self.text));
+    }
+
+    pub(super) fn invalid(&mut self, message: impl Into<String>, span: Option<Range<usize>>) {
+        self.push(DiagnosticKind::InvalidValue, message.into(), span);
+    }
+
+    /// Checks that a `warn_at` fraction lies in `(0, 1]`.
+    pub(super) fn check_warn_at(&mut self, warn_at: Option<f64>, context: &str, key: &[&str]) {
+        if let Some(value) = warn_at.filter(|value| !(*value > 0.0 && *value <= 1.0)) {
+            let span = self.spans.value(key);
+            self.invalid(
+                format!("{context} must be greater than 0 and at most 1, got {value}"),
+                span,
+            );
+        }
+    }
+
+    pub(super) fn check_glob(
+        &mut self,
+        pattern: &str,
+        syntax: PatternSyntax,
+        span: Option<Range<usize>>,
+    ) {
+        if let Err(err) = Matcher::new(pattern, syntax) {
+            self.push(
+                DiagnosticKind::InvalidGlob {
+                    pattern: pattern.to_string(),
+                },
+                format!("invalid glob '{pattern}': {}", err.kind()),
+                span,
+            );
+        }
+    }
+
+    /// Fails with every collected problem, in file order.
+    pub(super) fn finish(mut self, path: &Path) -> Result<(), ConfigError> {
+        if self.diagnostics.is_empty() {
+            return Ok(());
+        }
+        self.diagnostics.sort_by_key(|diagnostic| {
+            diagnostic
+                .span
+                .as_ref()
+                .map_or(usize::MAX, |span| span.start)
+        });
+        Err(ConfigError::Diagnostics {
+            path: path.to_path_buf(),
+            text: self.text.to_string(),
+            diagnostics: self.diagnostics,
+        })
+    }
+}
@@ -0,0 +1,255 @@
+//! Raw TOML shapes of a config and their deserialization.
+//!
+//! Deserializing records every key serde ignores, so validation can report
+//! unknown keys with their location.
+
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+
+use crate::config::ConfigError;
+use crate::diagnostic::{Diagnostic, DiagnosticKind};
+use crate::host::{ConfigFormat, CARGO_PACKAGE_TABLE, CARGO_WORKSPACE_TABLE, PYPROJECT_TABLE};
+use crate::limit::parse_byte_size;
+use crate::{LineLengthUnit, PatternSyntax, Severity};
+
+#[derive(Deserialize)]
+pub(super) struct RawConfig {
+    #[serde(default, deserialize_with = "deserialize_string_or_vec")]
+    pub(super) extends: Vec<String>,
+    pub(super) default_max_lines: Option<usize>,
+    pub(super) default_max_code_lines: Option<usize>,
+    pub(super) default_max_source_lines: Option<usize>,
+    pub(super) default_max_tokens: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_byte_size")]
+    pub(super) default_max_bytes: Option<usize>,
+    pub(super) default_max_line_length: Option<usize>,
+    pub(super) default_severity: Option<Severity>,
+    pub(super) respect_gitignore: Option<bool>,
+    pub(super) pattern_syntax: Option<PatternSyntax>,
+    pub(super) include: Option<Vec<String>>,
+    #[serde(default)]
+    pub(super) exclude: Vec<String>,
+    #[serde(default)]
+    pub(super) rules: Vec<RawRule>,
+    #[serde(default)]
+    pub(super) directory_rules: Vec<RawDirectoryRule>,
+    #[serde(default)]
+    pub(super) bundles: Vec<RawBundle>,
+    pub(super) warn_at: Option<f64>,
+    #[serde(default)]
+    pub(super) fix_guidance: Option<String>,
+    pub(super) tokenizer: Option<String>,
+    pub(super) bytes_per_token: Option<f64>,
+    pub(super) chars_per_token: Option<f64>,
+    pub(super) tokens_per_word: Option<f64>,
+    pub(super) line_length_unit: Option<LineLengthUnit>,
+}
+
+#[derive(Deserialize)]
+struct PyprojectHost {
+    tool: Option<LoqTable>,
+}
+
+#[derive(Deserialize)]
+struct CargoHost {
+    package: Option<MetadataTable>,
+    workspace: Option<MetadataTable>,
+}
+
+#[derive(Deserialize)]
+struct MetadataTable {
+    metadata: Option<LoqTable>,
+}
+
+#[derive(Deserialize)]
+struct LoqTable {
+    loq: Option<RawConfig>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RawRule {
+    #[serde(deserialize_with = "deserialize_string_or_vec")]
+    pub(super) path: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_vec")]
+    pub(super) exclude: Vec<String>,
+    pub(super) max_lines: Option<usize>,
+    pub(super) max_code_lines: Option<usize>,
+    pub(super) max_source_lines: Option<usize>,
+    pub(super) max_tokens: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_byte_size")]
+    pub(super) max_bytes: Option<usize>,
+    pub(super) max_line_length: Option<usize>,
+    #[serde(default)]
+    pub(super) severity: Severity,
+    pub(super) warn_at: Option<f64>,
+    pub(super) fix_guidance: Option<String>,
+    pub(super) bytes_per_token: Option<f64>,
+    pub(super) chars_per_token: Option<f64>,
+    pub(super) tokens_per_word: Option<f64>,
+    #[serde(default)]
+    pub(super) follow_imports: bool,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RawDirectoryRule {
+    #[serde(deserialize_with = "deserialize_string_or_vec")]
+    pub(super) path: Vec<String>,
+    pub(super) max_files: Option<usize>,
+    pub(super) max_total_lines: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RawBundle {
+    pub(super) name: Option<String>,
+    #[serde(deserialize_with = "deserialize_string_or_vec")]
+    pub(super) path: Vec<String>,
+    pub(super) max_tokens: usize,
+}
+
+fn deserialize_string_or_vec<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrVec {
+        String(String),
+        Vec(Vec<String>),
+    }
+
+    match StringOrVec::deserialize(deserializer)? {
+        StringOrVec::String(value) => Ok(vec![value]),
+        StringOrVec::Vec(values) => Ok(values),
+    }
+}
+
+fn deserialize_byte_size<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ByteSize {
+        Bytes(usize),
+        Text(String),
+    }
+
+    match ByteSize::deserialize(deserializer)? {
+        ByteSize::Bytes(bytes) => Ok(Some(bytes)),
+        ByteSize::Text(text) => parse_byte_size(&text)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// An unknown key: its path below the loq table, ending with the key itself.
+pub(super) type UnknownKey = (Vec<String>, String);
+
+/// Deserializes the loq table from `text`, wherever the host file keeps it.
+///
+/// Also returns the loq table's key path and every unknown key found in it.
+pub(super) fn deserialize_raw(
+    path: &Path,
+    text: &str,
+) -> Result<(RawConfig, &'static [&'static str], Vec<UnknownKey>), ConfigError> {
+    let format = ConfigFormat::from_path(path);
+    let mut ignored = Vec::new();
+    let (raw, table) = match format {
+        ConfigFormat::Loq => {
+            let raw: RawConfig = deserialize_tracking(path, text, &mut ignored)?;
+            (Some(raw), &[][..])
+        }
+        ConfigFormat::Pyproject => {
+            let host: PyprojectHost = deserialize_tracking(path, text, &mut ignored)?;
+            (host.tool.and_then(|tool| tool.loq), PYPROJECT_TABLE)
+        }
+        ConfigFormat::Cargo => {
+            let host: CargoHost = deserialize_tracking(path, text, &mut ignored)?;
+            let package = host.package.and_then(|package| package.metadata);
+            let workspace = host.workspace.and_then(|workspace| workspace.metadata);
+            match (
+                package.and_then(|meta| meta.loq),
+                workspace.and_then(|meta| meta.loq),
+            ) {
+                (Some(raw), _) => (Some(raw), CARGO_PACKAGE_TABLE),
+                (None, Some(raw)) => (Some(raw), CARGO_WORKSPACE_TABLE),
+                (None, None) => (None, format.default_table_path(text)),
+            }
+        }
+    };
+
+    let Some(raw) = raw else {
+        return Err(ConfigError::MissingTable {
+            path: path.to_path_buf(),
+            table: table.join("."),
+        });
+    };
+
+    let unknown = ignored
+        .into_iter()
+        .filter(|(keys, _)| keys.len() > table.len() && keys.iter().zip(table).all(|(a, b)| a == b))
+        .map(|(keys, key)| (keys[table.len()..].to_vec(), key))
+        .collect();
+    Ok((raw, table, unknown))
+}
+
+/// Deserializes `text`, recording each ignored key's full key path and name.
+fn deserialize_tracking<T: DeserializeOwned>(
+    path: &Path,
+    text: &str,
+    ignored: &mut Vec<UnknownKey>,
+) -> Result<T, ConfigError> {
+    let deserializer = toml::Deserializer::new(text);
+    serde_ignored::deserialize(deserializer, |ignored_path| {
+        if let Some(key) = extract_unknown_key_name(&ignored_path) {
+            ignored.push((key_segments(&ignored_path), key));
+        }
+    })
+    .map_err(|err| {
+        let message = err.message().lines().collect::<Vec<_>>().join("; ");
+        ConfigError::Diagnostics {
+            path: path.to_path_buf(),
+            text: text.to_string(),
+            diagnostics: vec![Diagnostic::new(
+                DiagnosticKind::Syntax,
+                message,
+                err.span(),
+                text,
+            )],
+        }
+    })
+}
+
+fn key_segments(path: &serde_ignored::Path) -> Vec<String> {
+    match path {
+        serde_ignored::Path::Root => Vec::new(),
+        serde_ignored::Path::Seq { parent, index } => {
+            let mut segments = key_segments(parent);
+            segments.push(index.to_string());
+            segments
+        }
+        serde_ignored::Path::Map { parent, key } => {
+            let mut segments = key_segments(parent);
+            segments.push(key.clone());
+            segments
+        }
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => key_segments(parent),
+    }
+}
+
+pub(super) fn extract_unknown_key_name(path: &serde_ignored::Path) -> Option<String> {
+    let path_str = path.to_string();
+    let mut last = path_str.split('.').next_back().unwrap_or(&path_str);
+    if let Some(pos) = last.find('[') {
+        last = &last[..pos];
+    }
+    if last.is_empty() {
+        None
+    } else {
+        Some(last.to_string())
+    }
+}
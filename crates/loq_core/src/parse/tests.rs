@@ -0,0 +1,452 @@
+use super::raw::extract_unknown_key_name;
+use super::*;
+use crate::diagnostic::{Diagnostic, DiagnosticKind};
+
+mod bundles;
+mod bytes;
+mod code_lines;
+mod diagnostics;
+mod directory_rules;
+mod follow_imports;
+mod host;
+mod include;
+mod line_length;
+mod negation;
+mod overrides;
+mod pattern_syntax;
+mod schema;
+mod severity;
+mod token_estimate;
+mod warn_at;
+
+/// Returns the diagnostics of a validation error.
+fn diagnostics(err: ConfigError) -> Vec<Diagnostic> {
+    match err {
+        ConfigError::Diagnostics { diagnostics, .. } => diagnostics,
+        other => panic!("expected diagnostics, got {other:?}"),
+    }
+}
+
+/// Returns the only diagnostic of a validation error.
+fn single_diagnostic(err: ConfigError) -> Diagnostic {
+    let mut diagnostics = diagnostics(err);
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    diagnostics.remove(0)
+}
+
+#[test]
+fn unknown_key_detection() {
+    let text = "default_max_lines = 500\nmax_line = 10\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert_eq!(
+        single_diagnostic(err).kind,
+        DiagnosticKind::UnknownKey {
+            key: "max_line".to_string(),
+            suggestion: Some("max_lines".to_string()),
+        }
+    );
+}
+
+#[test]
+fn rule_parsed_correctly() {
+    let text = "default_max_lines = 500\n[[rules]]\npath = \"**/*.rs\"\nmax_lines = 10\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.rules.len(), 1);
+    assert_eq!(config.rules[0].limits, [Limit::lines(10)]);
+}
+
+#[test]
+fn token_rule_parsed_correctly() {
+    let text =
+        "default_max_lines = 500\n[[rules]]\npath = \"prompts/**/*.md\"\nmax_tokens = 8000\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.rules.len(), 1);
+    assert_eq!(config.rules[0].limits, [Limit::tokens(8000)]);
+}
+
+#[test]
+fn both_default_budgets_are_invalid() {
+    let text = "default_max_lines = 500\ndefault_max_tokens = 2000\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("only one of default_max_lines, default_max_code_lines, default_max_source_lines, default_max_tokens, default_max_bytes or default_max_line_length"));
+}
+
+#[test]
+fn rule_can_set_several_budgets() {
+    let text = "[[rules]]\npath = \"**/*.md\"\nmax_lines = 100\nmax_tokens = 1000\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(
+        config.rules[0].limits,
+        [Limit::lines(100), Limit::tokens(1000)]
+    );
+}
+
+#[test]
+fn rule_without_budget_is_invalid() {
+    let text = "[[rules]]\npath = \"**/*.md\"\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("must set at least one of max_lines, max_code_lines, max_source_lines, max_tokens, max_bytes or max_line_length"));
+}
+
+#[test]
+fn respect_gitignore_defaults_true() {
+    let text = "default_max_lines = 500\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert!(config.respect_gitignore);
+}
+
+#[test]
+fn invalid_toml_reports_error() {
+    let text = "default_max_lines =\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert_eq!(single_diagnostic(err).kind, DiagnosticKind::Syntax);
+}
+
+#[test]
+fn unknown_key_in_inline_table_is_located() {
+    let text = "rules = [{ path = \"src/*.rs\", max_lines = 10, max_line = 20 }]\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(diagnostic.line_col, Some((1, 47)));
+    assert_eq!(&text[diagnostic.span.unwrap()], "max_line");
+}
+
+#[test]
+fn unknown_key_without_suggestion() {
+    let text = "banana = 1\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert!(matches!(
+        diagnostic.kind,
+        DiagnosticKind::UnknownKey {
+            suggestion: None,
+            ..
+        }
+    ));
+    assert!(diagnostic.help.is_none());
+}
+
+#[test]
+fn extract_unknown_key_name_with_array_index() {
+    let path = serde_ignored::Path::Map {
+        parent: &serde_ignored::Path::Root,
+        key: "rules[0]".to_string(),
+    };
+    let key = extract_unknown_key_name(&path);
+    assert_eq!(key, Some("rules".to_string()));
+}
+
+#[test]
+fn extract_unknown_key_name_empty_returns_none() {
+    let path = serde_ignored::Path::Map {
+        parent: &serde_ignored::Path::Root,
+        key: "[0]".to_string(),
+    };
+    let key = extract_unknown_key_name(&path);
+    assert!(key.is_none());
+}
+
+#[test]
+fn indented_unknown_key_is_located() {
+    let text = "  typo_key = 1\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert_eq!(single_diagnostic(err).line_col, Some((1, 3)));
+}
+
+#[test]
+fn negative_max_lines_reports_error() {
+    let text = "default_max_lines = -1\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert_eq!(single_diagnostic(err).kind, DiagnosticKind::Syntax);
+}
+
+#[test]
+fn rule_path_accepts_string() {
+    let text = r#"
+[[rules]]
+path = "**/*.rs"
+max_lines = 100
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.rules.len(), 1);
+    assert_eq!(config.rules[0].paths, vec!["**/*.rs"]);
+}
+
+#[test]
+fn rule_path_accepts_array() {
+    let text = r#"
+[[rules]]
+path = ["src/a.rs", "src/b.rs"]
+max_lines = 100
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.rules.len(), 1);
+    assert_eq!(config.rules[0].paths, vec!["src/a.rs", "src/b.rs"]);
+}
+
+#[test]
+fn rule_path_array_single_element() {
+    let text = r#"
+[[rules]]
+path = ["only_one.rs"]
+max_lines = 100
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.rules[0].paths, vec!["only_one.rs"]);
+}
+
+#[test]
+fn fix_guidance_parsed_correctly() {
+    let text = r#"
+default_max_lines = 500
+fix_guidance = "Split large files into smaller modules."
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(
+        config.fix_guidance,
+        Some("Split large files into smaller modules.".to_string())
+    );
+}
+
+#[test]
+fn fix_guidance_multiline_string() {
+    let text = r#"
+default_max_lines = 500
+fix_guidance = """
+Consider splitting large files:
+- Extract functions into modules
+- Move tests to test files
+"""
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert!(config.fix_guidance.is_some());
+    let guidance = config.fix_guidance.unwrap();
+    assert!(guidance.contains("Consider splitting large files:"));
+    assert!(guidance.contains("Extract functions into modules"));
+}
+
+#[test]
+fn fix_guidance_defaults_to_none() {
+    let text = "default_max_lines = 500\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert!(config.fix_guidance.is_none());
+}
+
+#[test]
+fn rule_fix_guidance_parsed() {
+    let text = r#"
+[[rules]]
+path = "**/*.tsx"
+max_lines = 300
+fix_guidance = "Move hooks out of {path}."
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(
+        config.rules[0].fix_guidance.as_deref(),
+        Some("Move hooks out of {path}.")
+    );
+}
+
+fn write_config(dir: &Path, name: &str, text: &str) -> PathBuf {
+    let path = dir.join(name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+#[test]
+fn extends_inherits_parent_settings() {
+    let temp = tempfile::TempDir::new().unwrap();
+    write_config(
+        temp.path(),
+        "base.toml",
+        r#"
+default_max_lines = 300
+respect_gitignore = false
+exclude = ["vendor/**"]
+fix_guidance = "Split it."
+
+[[rules]]
+path = "**/*.rs"
+max_lines = 200
+"#,
+    );
+    let child = write_config(
+        temp.path(),
+        "repo/loq.toml",
+        r#"
+extends = "../base.toml"
+exclude = ["vendor/**", "gen/**"]
+
+[[rules]]
+path = "src/big.rs"
+max_lines = 900
+"#,
+    );
+
+    let text = std::fs::read_to_string(&child).unwrap();
+    let config = parse_config(&child, &text).unwrap();
+
+    assert_eq!(config.default_limit, Some(Limit::lines(300)));
+    assert!(!config.respect_gitignore);
+    assert_eq!(config.exclude, vec!["vendor/**", "gen/**"]);
+    assert_eq!(config.fix_guidance.as_deref(), Some("Split it."));
+    let paths: Vec<_> = config
+        .rules
+        .iter()
+        .map(|rule| rule.paths[0].as_str())
+        .collect();
+    assert_eq!(paths, vec!["**/*.rs", "src/big.rs"]);
+}
+
+#[test]
+fn extends_child_scalars_override_parent() {
+    let temp = tempfile::TempDir::new().unwrap();
+    write_config(
+        temp.path(),
+        "base.toml",
+        "default_max_lines = 300\nfix_guidance = \"parent\"\n",
+    );
+    let child = write_config(
+        temp.path(),
+        "loq.toml",
+        "extends = \"base.toml\"\ndefault_max_tokens = 2000\nfix_guidance = \"child\"\n",
+    );
+
+    let text = std::fs::read_to_string(&child).unwrap();
+    let config = parse_config(&child, &text).unwrap();
+
+    assert_eq!(config.default_limit, Some(Limit::tokens(2000)));
+    assert_eq!(config.fix_guidance.as_deref(), Some("child"));
+}
+
+#[test]
+fn extends_list_applies_parents_in_order() {
+    let temp = tempfile::TempDir::new().unwrap();
+    write_config(temp.path(), "a.toml", "default_max_lines = 100\n");
+    write_config(
+        temp.path(),
+        "b.toml",
+        "default_max_lines = 200\n[[rules]]\npath = \"b\"\nmax_lines = 1\n",
+    );
+    let child = write_config(
+        temp.path(),
+        "loq.toml",
+        "extends = [\"a.toml\", \"./b.toml\"]\n",
+    );
+
+    let text = std::fs::read_to_string(&child).unwrap();
+    let config = parse_config(&child, &text).unwrap();
+
+    assert_eq!(config.default_limit, Some(Limit::lines(200)));
+    assert_eq!(config.rules.len(), 1);
+}
+
+#[test]
+fn extends_cycle_reports_chain() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_config(root, "a.toml", "extends = \"b.toml\"\n");
+    write_config(root, "b.toml", "extends = \"a.toml\"\n");
+    let child = write_config(root, "loq.toml", "extends = \"a.toml\"\n");
+
+    let text = std::fs::read_to_string(&child).unwrap();
+    let err = parse_config(&child, &text).unwrap_err();
+
+    match &err {
+        ConfigError::ExtendsCycle { chain } => {
+            assert_eq!(
+                chain,
+                &vec![
+                    root.join("loq.toml"),
+                    root.join("a.toml"),
+                    root.join("b.toml"),
+                    root.join("a.toml"),
+                ]
+            );
+        }
+        other => panic!("expected extends cycle, got {other:?}"),
+    }
+    assert!(err.to_string().starts_with("extends cycle: "));
+}
+
+#[test]
+fn extends_self_is_a_cycle() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let child = write_config(temp.path(), "loq.toml", "extends = \"loq.toml\"\n");
+
+    let text = std::fs::read_to_string(&child).unwrap();
+    let err = parse_config(&child, &text).unwrap_err();
+
+    assert!(matches!(err, ConfigError::ExtendsCycle { chain } if chain.len() == 2));
+}
+
+#[test]
+fn extends_missing_parent_reports_chain() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_config(root, "base.toml", "extends = \"org/missing.toml\"\n");
+    let child = write_config(root, "loq.toml", "extends = \"base.toml\"\n");
+
+    let text = std::fs::read_to_string(&child).unwrap();
+    let err = parse_config(&child, &text).unwrap_err();
+
+    match &err {
+        ConfigError::ExtendsMissing { chain } => {
+            assert_eq!(
+                chain,
+                &vec![
+                    root.join("loq.toml"),
+                    root.join("base.toml"),
+                    root.join("org/missing.toml"),
+                ]
+            );
+        }
+        other => panic!("expected missing parent, got {other:?}"),
+    }
+    let message = err.to_string();
+    assert!(message.contains("missing.toml - extended config not found"));
+    assert!(message.contains("extends chain: "));
+}
+
+#[test]
+fn extends_parent_errors_name_the_parent_file() {
+    let temp = tempfile::TempDir::new().unwrap();
+    write_config(temp.path(), "base.toml", "max_line = 1\n");
+    let child = write_config(temp.path(), "loq.toml", "extends = \"base.toml\"\n");
+
+    let text = std::fs::read_to_string(&child).unwrap();
+    let err = parse_config(&child, &text).unwrap_err();
+
+    match err {
+        ConfigError::Diagnostics {
+            path, diagnostics, ..
+        } => {
+            assert_eq!(path, temp.path().join("base.toml"));
+            assert_eq!(diagnostics[0].message, "unknown key 'max_line'");
+        }
+        other => panic!("expected diagnostics, got {other:?}"),
+    }
+}
+
+#[test]
+fn normalize_path_resolves_dot_components() {
+    assert_eq!(
+        normalize_path(Path::new("/repo/pkg/../base.toml")),
+        PathBuf::from("/repo/base.toml")
+    );
+    assert_eq!(
+        normalize_path(Path::new("./a/./b.toml")),
+        PathBuf::from("a/b.toml")
+    );
+    assert_eq!(
+        normalize_path(Path::new("../base.toml")),
+        PathBuf::from("../base.toml")
+    );
+}
@@ -0,0 +1,38 @@
+use super::*;
+use crate::Bundle;
+
+#[test]
+fn bundles_are_parsed() {
+    let text = r#"
+[[bundles]]
+name = "agent context"
+path = ["AGENTS.md", "docs/architecture/*.md"]
+max_tokens = 12000
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(
+        config.bundles,
+        [Bundle {
+            name: Some("agent context".to_string()),
+            paths: vec![
+                "AGENTS.md".to_string(),
+                "docs/architecture/*.md".to_string()
+            ],
+            max_tokens: 12000,
+        }]
+    );
+}
+
+#[test]
+fn bundle_without_budget_is_rejected() {
+    let text = "[[bundles]]\npath = \"AGENTS.md\"\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(err.to_string().contains("max_tokens"), "{err}");
+}
+
+#[test]
+fn bundle_with_invalid_glob_is_reported() {
+    let text = "[[bundles]]\npath = \"docs/[\"\nmax_tokens = 10\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(err.to_string().contains("invalid glob 'docs/['"), "{err}");
+}
@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn max_bytes_accepts_integers_and_units() {
+    let text = r#"
+default_max_bytes = 2048
+
+[[rules]]
+path = "assets/**"
+max_bytes = "1.5 MiB"
+
+[[rules]]
+path = "docs/**"
+max_bytes = "64kib"
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::bytes(2048)));
+    assert_eq!(config.rules[0].limits, [Limit::bytes(1024 * 1024 * 3 / 2)]);
+    assert_eq!(config.rules[1].limits, [Limit::bytes(64 * 1024)]);
+}
+
+#[test]
+fn invalid_byte_size_is_reported() {
+    let text = "[[rules]]\npath = \"*.bin\"\nmax_bytes = \"64 KB\"\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(err.to_string().contains("invalid size '64 KB'"), "{err}");
+}
+
+#[test]
+fn max_bytes_combines_with_other_limits() {
+    let text = "[[rules]]\npath = \"*.md\"\nmax_tokens = 10\nmax_bytes = 100\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(
+        config.rules[0].limits,
+        [Limit::tokens(10), Limit::bytes(100)]
+    );
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn max_code_lines_parsed_for_default_and_rules() {
+    let text = r#"
+default_max_code_lines = 400
+
+[[rules]]
+path = "src/**/*.rs"
+max_code_lines = 300
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::code_lines(400)));
+    assert_eq!(config.rules[0].limits, [Limit::code_lines(300)]);
+}
+
+#[test]
+fn max_code_lines_conflicts_with_max_lines() {
+    let text = "default_max_lines = 500\ndefault_max_code_lines = 400\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(err.to_string().contains("set only one of"), "{err}");
+}
+
+#[test]
+fn max_source_lines_parsed_for_default_and_rules() {
+    let text =
+        "default_max_source_lines = 300\n[[rules]]\npath = \"*.rs\"\nmax_source_lines = 200\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::source_lines(300)));
+    assert_eq!(config.rules[0].limits, [Limit::source_lines(200)]);
+}
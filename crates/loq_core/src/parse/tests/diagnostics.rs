@@ -0,0 +1,92 @@
+use super::*;
+
+#[test]
+fn every_problem_is_collected() {
+    let text = r#"default_max_line = 500
+exclude = ["ok/**", "bad/[**"]
+warn_at = 2.0
+
+[[rules]]
+path = "src/**"
+max_lines = 10
+max_tokens = 10
+chars_per_token = 0
+
+[[rules]]
+path = ["docs/**", "{unclosed"]
+max_tokenz = 5
+"#;
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let messages = diagnostics(err)
+        .into_iter()
+        .map(|diagnostic| (diagnostic.line_col.unwrap().0, diagnostic.message))
+        .collect::<Vec<_>>();
+    assert_eq!(messages.len(), 7, "{messages:?}");
+    assert_eq!(
+        messages[0],
+        (1, "unknown key 'default_max_line'".to_string())
+    );
+    assert_eq!(messages[1].0, 2);
+    assert!(messages[1].1.starts_with("invalid glob 'bad/[**'"));
+    assert_eq!(messages[2].0, 3);
+    assert!(messages[2].1.starts_with("warn_at must be greater than 0"));
+    assert_eq!(
+        messages[3],
+        (
+            9,
+            "chars_per_token for 'src/**' must be greater than 0, got 0".to_string()
+        )
+    );
+    assert_eq!(
+        messages[4],
+        (
+            12,
+            "rule for 'docs/**, {unclosed' must set at least one of max_lines, max_code_lines, max_source_lines, max_tokens, max_bytes or max_line_length".to_string()
+        )
+    );
+    assert!(messages[5].1.starts_with("invalid glob '{unclosed'"));
+    assert_eq!(messages[6], (13, "unknown key 'max_tokenz'".to_string()));
+}
+
+#[test]
+fn glob_span_points_at_the_pattern() {
+    let text = "exclude = [\"ok/**\", \"bad/[**\"]\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(
+        diagnostic.kind,
+        DiagnosticKind::InvalidGlob {
+            pattern: "bad/[**".to_string()
+        }
+    );
+    assert_eq!(&text[diagnostic.span.unwrap()], "\"bad/[**\"");
+}
+
+#[test]
+fn syntax_error_has_span() {
+    let text = "default_max_lines =\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(diagnostic.kind, DiagnosticKind::Syntax);
+    assert!(diagnostic.span.is_some());
+    assert!(!diagnostic.message.contains('\n'));
+}
+
+#[test]
+fn error_message_renders_code_frames() {
+    let text = "default_max_line = 500\nwarn_at = 0\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "loq.toml:1:1 - unknown key 'default_max_line'
+  |
+1 | default_max_line = 500
+  | ^^^^^^^^^^^^^^^^
+  = help: did you mean 'default_max_lines'?
+loq.toml:2:11 - warn_at must be greater than 0 and at most 1, got 0
+  |
+2 | warn_at = 0
+  |           ^
+2 problems found in loq.toml"
+    );
+}
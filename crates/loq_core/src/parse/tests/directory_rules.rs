@@ -0,0 +1,54 @@
+use super::*;
+use crate::DirectoryRule;
+
+#[test]
+fn directory_rules_are_parsed() {
+    let text = r#"
+[[directory_rules]]
+path = "src/components/*"
+max_files = 40
+max_total_lines = 8000
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(
+        config.directory_rules,
+        [DirectoryRule {
+            paths: vec!["src/components/*".to_string()],
+            max_files: Some(40),
+            max_total_lines: Some(8000),
+        }]
+    );
+}
+
+#[test]
+fn directory_rule_without_budget_is_reported() {
+    let text = "[[directory_rules]]\npath = \"src/*\"\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "directory rule for 'src/*' must set at least one of max_files or max_total_lines"
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn directory_rules_are_inherited() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        temp.path().join("base.toml"),
+        "[[directory_rules]]\npath = \"src\"\nmax_files = 10\n",
+    )
+    .unwrap();
+    let path = temp.path().join("loq.toml");
+    let text = "extends = \"base.toml\"\n\n[[directory_rules]]\npath = \"docs\"\nmax_files = 5\n";
+
+    let config = parse_config(&path, text).unwrap();
+
+    let paths = config
+        .directory_rules
+        .iter()
+        .map(|rule| rule.paths[0].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, ["src", "docs"]);
+}
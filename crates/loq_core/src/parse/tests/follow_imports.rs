@@ -0,0 +1,18 @@
+use super::*;
+
+#[test]
+fn follow_imports_is_parsed_per_rule() {
+    let text = r#"
+[[rules]]
+path = "CLAUDE.md"
+max_tokens = 8000
+follow_imports = true
+
+[[rules]]
+path = "docs/**"
+max_lines = 300
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert!(config.rules[0].follow_imports);
+    assert!(!config.rules[1].follow_imports);
+}
@@ -0,0 +1,132 @@
+use super::*;
+
+#[test]
+fn pyproject_reads_tool_loq_table() {
+    let text = r#"[project]
+name = "demo"
+
+[tool.ruff]
+line-length = 88
+
+[tool.loq]
+default_max_lines = 300
+exclude = ["migrations/**"]
+
+[[tool.loq.rules]]
+path = "**/*.py"
+max_lines = 200
+"#;
+    let config = parse_config(Path::new("pyproject.toml"), text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::lines(300)));
+    assert_eq!(config.exclude, vec!["migrations/**"]);
+    assert_eq!(config.rules.len(), 1);
+    assert_eq!(config.rules[0].limits, [Limit::lines(200)]);
+}
+
+#[test]
+fn pyproject_without_loq_table_is_an_error() {
+    let text = "[project]\nname = \"demo\"\n";
+    let err = parse_config(Path::new("pyproject.toml"), text).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "pyproject.toml - no [tool.loq] table found"
+    );
+}
+
+#[test]
+fn pyproject_unknown_key_reports_host_line() {
+    let text = "[project]\nname = \"demo\"\n\n[tool.loq]\nmax_line = 10\n";
+    let err = parse_config(Path::new("pyproject.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(
+        diagnostic.kind,
+        DiagnosticKind::UnknownKey {
+            key: "max_line".to_string(),
+            suggestion: Some("max_lines".to_string()),
+        }
+    );
+    assert_eq!(diagnostic.line_col, Some((5, 1)));
+}
+
+#[test]
+fn pyproject_type_error_reports_host_line() {
+    let text = "[project]\nname = \"demo\"\n\n[tool.loq]\ndefault_max_lines = \"many\"\n";
+    let err = parse_config(Path::new("pyproject.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(diagnostic.kind, DiagnosticKind::Syntax);
+    assert_eq!(diagnostic.line_col.map(|(line, _)| line), Some(5));
+}
+
+#[test]
+fn cargo_reads_package_metadata() {
+    let text = r#"[package]
+name = "demo"
+version = "0.1.0"
+
+[package.metadata.loq]
+default_max_lines = 400
+
+[dependencies]
+serde = "1"
+"#;
+    let config = parse_config(Path::new("Cargo.toml"), text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::lines(400)));
+}
+
+#[test]
+fn cargo_reads_workspace_metadata() {
+    let text = "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.metadata.loq]\ndefault_max_tokens = 900\n";
+    let config = parse_config(Path::new("Cargo.toml"), text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::tokens(900)));
+}
+
+#[test]
+fn cargo_package_metadata_wins_over_workspace() {
+    let text = "[package.metadata.loq]\ndefault_max_lines = 1\n\n[workspace.metadata.loq]\ndefault_max_lines = 2\n";
+    let config = parse_config(Path::new("Cargo.toml"), text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::lines(1)));
+}
+
+#[test]
+fn cargo_unknown_key_in_other_tables_is_ignored() {
+    let text = "[package]\nname = \"demo\"\nmax_line = 3\n\n[package.metadata.loq]\ndefault_max_lines = 10\n";
+    let config = parse_config(Path::new("Cargo.toml"), text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::lines(10)));
+}
+
+#[test]
+fn cargo_unknown_rule_key_is_reported() {
+    let text = "[workspace.metadata.loq]\n\n[[workspace.metadata.loq.rules]]\npath = \"*.rs\"\nmax_lines = 5\nmax_tokenz = 5\n";
+    let err = parse_config(Path::new("Cargo.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(diagnostic.message, "unknown key 'max_tokenz'");
+    assert_eq!(diagnostic.line_col, Some((6, 1)));
+}
+
+#[test]
+fn cargo_without_loq_table_names_expected_table() {
+    let text = "[workspace]\nmembers = []\n";
+    let err = parse_config(Path::new("Cargo.toml"), text).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Cargo.toml - no [workspace.metadata.loq] table found"
+    );
+}
+
+#[test]
+fn extends_can_target_host_manifest() {
+    let temp = tempfile::TempDir::new().unwrap();
+    write_config(
+        temp.path(),
+        "pyproject.toml",
+        "[project]\nname = \"demo\"\n\n[tool.loq]\ndefault_max_lines = 42\n",
+    );
+    let child = write_config(
+        temp.path(),
+        "sub/loq.toml",
+        "extends = \"../pyproject.toml\"\n",
+    );
+    let text = std::fs::read_to_string(&child).unwrap();
+    let config = parse_config(&child, &text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::lines(42)));
+}
@@ -0,0 +1,28 @@
+use super::*;
+
+#[test]
+fn include_parsed() {
+    let text = "include = [\"src/**\", \"prompts/**\"]\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.include, vec!["src/**", "prompts/**"]);
+}
+
+#[test]
+fn include_defaults_to_every_file() {
+    let config = parse_config(Path::new("loq.toml"), "default_max_lines = 500\n").unwrap();
+    assert!(config.include.is_empty());
+}
+
+#[test]
+fn invalid_include_glob_points_at_pattern() {
+    let text = "include = [\"src/**\", \"bad/[**\"]\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(
+        diagnostic.kind,
+        DiagnosticKind::InvalidGlob {
+            pattern: "bad/[**".to_string()
+        }
+    );
+    assert_eq!(diagnostic.line_col, Some((1, 22)));
+}
@@ -0,0 +1,25 @@
+use super::*;
+use crate::LineLengthUnit;
+
+#[test]
+fn max_line_length_is_a_limit() {
+    let text = r#"
+default_max_line_length = 120
+line_length_unit = "bytes"
+
+[[rules]]
+path = "*.min.js"
+max_line_length = 400
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.default_limit, Some(Limit::line_length(120)));
+    assert_eq!(config.line_length_unit, LineLengthUnit::Bytes);
+    assert_eq!(config.rules[0].limits, [Limit::line_length(400)]);
+}
+
+#[test]
+fn unknown_line_length_unit_is_reported() {
+    let text = "line_length_unit = \"columns\"\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(err.to_string().contains("columns"), "{err}");
+}
@@ -0,0 +1,42 @@
+use super::*;
+
+#[test]
+fn negated_paths_and_rule_exclude_become_excludes() {
+    let text = r#"
+[[rules]]
+path = ["**/*.py", "!**/migrations/**"]
+exclude = "**/conftest.py"
+max_lines = 300
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    let rule = &config.rules[0];
+    assert_eq!(rule.paths, vec!["**/*.py"]);
+    assert_eq!(rule.exclude, vec!["**/migrations/**", "**/conftest.py"]);
+}
+
+#[test]
+fn rule_with_only_negated_paths_is_rejected() {
+    let text = "[[rules]]\npath = [\"!gen/**\"]\nmax_lines = 10\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(diagnostic.kind, DiagnosticKind::InvalidValue);
+    assert_eq!(
+        diagnostic.message,
+        "rule for '!gen/**' needs a path that is not negated with '!'"
+    );
+    assert_eq!(diagnostic.line_col, Some((2, 8)));
+}
+
+#[test]
+fn invalid_rule_exclude_glob_points_at_pattern() {
+    let text = "[[rules]]\npath = \"src/**\"\nexclude = [\"ok/**\", \"bad/[**\"]\nmax_lines = 10\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(
+        diagnostic.kind,
+        DiagnosticKind::InvalidGlob {
+            pattern: "bad/[**".to_string()
+        }
+    );
+    assert_eq!(diagnostic.line_col, Some((3, 21)));
+}
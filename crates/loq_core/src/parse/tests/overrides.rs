@@ -0,0 +1,87 @@
+use super::*;
+
+fn assignments(items: &[&str]) -> Vec<String> {
+    items.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn overrides_parse_toml_values() {
+    let layer = parse_overrides(&assignments(&[
+        "default_max_lines=300",
+        "respect_gitignore = false",
+        "exclude=[\"gen/**\"]",
+        "fix_guidance=\"split it\"",
+        "rules=[{ path = \"src/**\", max_tokens = 900 }]",
+    ]))
+    .unwrap();
+    assert_eq!(layer.default_limit, Some(Limit::lines(300)));
+    assert_eq!(layer.respect_gitignore, Some(false));
+    assert_eq!(layer.exclude, vec!["gen/**"]);
+    assert_eq!(layer.fix_guidance.as_deref(), Some("split it"));
+    assert_eq!(layer.rules.len(), 1);
+    assert_eq!(layer.rules[0].limits, [Limit::tokens(900)]);
+}
+
+#[test]
+fn later_overrides_win() {
+    let layer = parse_overrides(&assignments(&[
+        "default_max_lines=300",
+        "default_max_tokens=2000",
+    ]))
+    .unwrap();
+    assert_eq!(layer.default_limit, Some(Limit::tokens(2000)));
+}
+
+#[test]
+fn overrides_apply_on_top_of_config() {
+    let text = "default_max_lines = 500\nexclude = [\"a/**\"]\n\n[[rules]]\npath = \"*.md\"\nmax_lines = 50\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    let layer = parse_overrides(&assignments(&[
+        "default_max_lines=100",
+        "exclude=[\"b/**\"]",
+        "rules=[{ path = \"*.md\", max_lines = 10 }]",
+    ]))
+    .unwrap();
+
+    let config = config.with_overrides(&layer);
+    assert_eq!(config.default_limit, Some(Limit::lines(100)));
+    assert_eq!(config.exclude, vec!["a/**", "b/**"]);
+    assert_eq!(config.rules.len(), 2);
+    assert_eq!(config.rules[1].limits, [Limit::lines(10)]);
+    assert!(config.respect_gitignore);
+}
+
+#[test]
+fn override_without_equals_is_an_error() {
+    let err = parse_overrides(&assignments(&["default_max_lines"])).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid override 'default_max_lines': expected KEY=VALUE"
+    );
+}
+
+#[test]
+fn override_unknown_key_suggests_fix() {
+    let err = parse_overrides(&assignments(&["default_max_line=3"])).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid override 'default_max_line=3': unknown key 'default_max_line', did you mean 'default_max_lines'?"
+    );
+}
+
+#[test]
+fn override_bad_value_names_assignment() {
+    let err = parse_overrides(&assignments(&["default_max_lines=lots"])).unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.starts_with("invalid override 'default_max_lines=lots': "),
+        "{message}"
+    );
+    assert!(!message.contains('\n'), "{message}");
+}
+
+#[test]
+fn extends_cannot_be_overridden() {
+    let err = parse_overrides(&assignments(&["extends=\"base.toml\""])).unwrap_err();
+    assert!(err.to_string().contains("extends cannot be overridden"));
+}
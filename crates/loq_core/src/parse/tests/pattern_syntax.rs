@@ -0,0 +1,28 @@
+use super::*;
+use crate::PatternSyntax;
+
+#[test]
+fn pattern_syntax_defaults_to_glob() {
+    let config = parse_config(Path::new("loq.toml"), "default_max_lines = 500\n").unwrap();
+    assert_eq!(config.pattern_syntax, PatternSyntax::Glob);
+}
+
+#[test]
+fn gitignore_pattern_syntax_parsed() {
+    let text = "pattern_syntax = \"gitignore\"\nexclude = [\"build/\", \"/dist\"]\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.pattern_syntax, PatternSyntax::Gitignore);
+}
+
+#[test]
+fn unknown_pattern_syntax_is_rejected() {
+    let text = "pattern_syntax = \"regex\"\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let diagnostic = single_diagnostic(err);
+    assert_eq!(diagnostic.kind, DiagnosticKind::Syntax);
+    assert!(
+        diagnostic.message.contains("regex"),
+        "{}",
+        diagnostic.message
+    );
+}
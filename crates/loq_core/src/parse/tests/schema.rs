@@ -0,0 +1,106 @@
+use std::collections::BTreeSet;
+
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer};
+
+use super::*;
+use crate::parse::raw::{RawBundle, RawConfig, RawDirectoryRule, RawRule};
+use crate::CONFIG_SCHEMA;
+
+/// A deserializer that records the field names a struct asks for.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("expected a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("fields recorded"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+fn serde_fields<T: DeserializeOwned>() -> BTreeSet<String> {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields.iter().map(ToString::to_string).collect()
+}
+
+fn schema_properties(schema: &serde_json::Value) -> BTreeSet<String> {
+    schema["properties"]
+        .as_object()
+        .expect("schema object has properties")
+        .keys()
+        .cloned()
+        .collect()
+}
+
+fn schema() -> serde_json::Value {
+    serde_json::from_str(CONFIG_SCHEMA).expect("schema is valid JSON")
+}
+
+#[test]
+fn schema_matches_config_keys() {
+    assert_eq!(schema_properties(&schema()), serde_fields::<RawConfig>());
+}
+
+#[test]
+fn schema_matches_rule_keys() {
+    let schema = schema();
+    assert_eq!(
+        schema_properties(&schema["definitions"]["rule"]),
+        serde_fields::<RawRule>()
+    );
+}
+
+#[test]
+fn schema_matches_directory_rule_keys() {
+    let schema = schema();
+    assert_eq!(
+        schema_properties(&schema["definitions"]["directory_rule"]),
+        serde_fields::<RawDirectoryRule>()
+    );
+}
+
+#[test]
+fn schema_matches_bundle_keys() {
+    let schema = schema();
+    assert_eq!(
+        schema_properties(&schema["definitions"]["bundle"]),
+        serde_fields::<RawBundle>()
+    );
+}
+
+#[test]
+fn every_schema_key_can_be_suggested() {
+    let schema = schema();
+    let keys = schema_properties(&schema)
+        .into_iter()
+        .chain(schema_properties(&schema["definitions"]["rule"]))
+        .chain(schema_properties(&schema["definitions"]["directory_rule"]))
+        .chain(schema_properties(&schema["definitions"]["bundle"]));
+    for key in keys {
+        assert_eq!(suggest_key(&key).as_deref(), Some(key.as_str()));
+    }
+}
+
+#[test]
+fn schema_rejects_unknown_keys() {
+    let schema = schema();
+    assert_eq!(schema["additionalProperties"], false);
+    assert_eq!(schema["definitions"]["rule"]["additionalProperties"], false);
+}
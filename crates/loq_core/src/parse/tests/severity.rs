@@ -0,0 +1,46 @@
+use super::*;
+use crate::Severity;
+
+#[test]
+fn severity_defaults_to_error() {
+    let text = "default_max_lines = 500\n[[rules]]\npath = \"*.rs\"\nmax_lines = 10\n";
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.default_severity, Severity::Error);
+    assert_eq!(config.rules[0].severity, Severity::Error);
+}
+
+#[test]
+fn severity_parsed_for_rules_and_default() {
+    let text = r#"
+default_max_lines = 500
+default_severity = "warning"
+
+[[rules]]
+path = "src/**"
+max_lines = 300
+severity = "warning"
+
+[[rules]]
+path = "src/core/**"
+max_lines = 400
+severity = "error"
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.default_severity, Severity::Warning);
+    assert_eq!(config.rules[0].severity, Severity::Warning);
+    assert_eq!(config.rules[1].severity, Severity::Error);
+}
+
+#[test]
+fn unknown_severity_is_rejected() {
+    let text = "default_max_lines = 500\ndefault_severity = \"warn\"\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("unknown variant `warn`"), "{message}");
+}
+
+#[test]
+fn default_severity_overridable() {
+    let layer = parse_overrides(&["default_severity=\"warning\"".to_string()]).unwrap();
+    assert_eq!(layer.default_severity, Some(Severity::Warning));
+}
@@ -0,0 +1,130 @@
+use super::*;
+use crate::TokenEstimate;
+
+#[test]
+fn token_estimates_parsed_globally_and_per_rule() {
+    let text = r#"
+default_max_tokens = 8000
+bytes_per_token = 3.2
+
+[[rules]]
+path = "docs/zh/**"
+max_tokens = 4000
+chars_per_token = 1.5
+
+[[rules]]
+path = "notes/**"
+max_tokens = 4000
+tokens_per_word = 1.3
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(
+        config.token_estimate,
+        Some(TokenEstimate::BytesPerToken(3.2))
+    );
+    assert_eq!(
+        config.rules[0].token_estimate,
+        Some(TokenEstimate::CharsPerToken(1.5))
+    );
+    assert_eq!(
+        config.rules[1].token_estimate,
+        Some(TokenEstimate::TokensPerWord(1.3))
+    );
+}
+
+#[test]
+fn token_estimate_must_be_positive() {
+    for value in ["0", "-1.5", "nan", "inf"] {
+        let text = format!("default_max_tokens = 10\nbytes_per_token = {value}\n");
+        let err = parse_config(Path::new("loq.toml"), &text).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("bytes_per_token must be greater than 0"),
+            "{err}"
+        );
+    }
+}
+
+#[test]
+fn only_one_token_estimate_may_be_set() {
+    let text = "default_max_tokens = 10\nbytes_per_token = 3\nchars_per_token = 2\n";
+    let diagnostic = single_diagnostic(parse_config(Path::new("loq.toml"), text).unwrap_err());
+    assert_eq!(
+        diagnostic.message,
+        "set only one of bytes_per_token, chars_per_token or tokens_per_word"
+    );
+    assert_eq!(diagnostic.line_col, Some((3, 1)));
+}
+
+#[test]
+fn tokenizer_and_token_estimate_conflict() {
+    let text = "tokenizer = \"vocab.tiktoken\"\ntokens_per_word = 1.3\n";
+    let diagnostic = single_diagnostic(parse_config(Path::new("loq.toml"), text).unwrap_err());
+    assert_eq!(
+        diagnostic.message,
+        "set only one of tokenizer, bytes_per_token, chars_per_token or tokens_per_word"
+    );
+    assert_eq!(diagnostic.line_col, Some((2, 1)));
+}
+
+#[test]
+fn rule_token_estimate_needs_a_token_limit() {
+    let text = "[[rules]]\npath = \"src/**\"\nmax_lines = 10\nchars_per_token = 2\n";
+    let diagnostic = single_diagnostic(parse_config(Path::new("loq.toml"), text).unwrap_err());
+    assert_eq!(
+        diagnostic.message,
+        "rule for 'src/**' sets chars_per_token but not max_tokens"
+    );
+}
+
+#[test]
+fn rule_token_estimate_conflict_names_rule() {
+    let text =
+        "[[rules]]\npath = \"src/**\"\nmax_tokens = 10\nchars_per_token = 2\ntokens_per_word = 1\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "set only one of bytes_per_token, chars_per_token or tokens_per_word for 'src/**'"
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn inherited_tokenizer_is_relative_to_the_declaring_config() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(temp.path().join("base")).unwrap();
+    std::fs::create_dir_all(temp.path().join("p2")).unwrap();
+    std::fs::write(
+        temp.path().join("base/shared.toml"),
+        "tokenizer = \"vocab.tiktoken\"\n",
+    )
+    .unwrap();
+    let path = temp.path().join("p2/loq.toml");
+    let text = "extends = [\"../base/shared.toml\"]\n";
+
+    let config = parse_config(&path, text).unwrap();
+
+    assert_eq!(
+        config.tokenizer,
+        Some(temp.path().join("base/vocab.tiktoken"))
+    );
+}
+
+#[test]
+fn child_token_estimate_replaces_parent_tokenizer() {
+    let parent = ConfigLayer {
+        tokenizer: Some(PathBuf::from("vocab.tiktoken")),
+        ..ConfigLayer::default()
+    };
+    let child = ConfigLayer {
+        token_estimate: Some(TokenEstimate::CharsPerToken(2.0)),
+        ..ConfigLayer::default()
+    };
+    let config = child.inherit(parent).into_config();
+    assert_eq!(config.tokenizer, None);
+    assert_eq!(
+        config.token_estimate,
+        Some(TokenEstimate::CharsPerToken(2.0))
+    );
+}
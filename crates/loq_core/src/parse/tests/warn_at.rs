@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn warn_at_parsed_globally_and_per_rule() {
+    let text = r#"
+default_max_lines = 500
+warn_at = 0.9
+
+[[rules]]
+path = "src/**"
+max_lines = 300
+warn_at = 0.8
+"#;
+    let config = parse_config(Path::new("loq.toml"), text).unwrap();
+    assert_eq!(config.warn_at, Some(0.9));
+    assert_eq!(config.rules[0].warn_at, Some(0.8));
+}
+
+#[test]
+fn warn_at_must_be_a_fraction() {
+    for value in ["0", "1.5", "-0.1"] {
+        let text = format!("default_max_lines = 500\nwarn_at = {value}\n");
+        let err = parse_config(Path::new("loq.toml"), &text).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("warn_at must be greater than 0 and at most 1"),
+            "{err}"
+        );
+    }
+}
+
+#[test]
+fn rule_warn_at_error_names_rule() {
+    let text = "[[rules]]\npath = \"src/**\"\nmax_lines = 10\nwarn_at = 2\n";
+    let err = parse_config(Path::new("loq.toml"), text).unwrap_err();
+    assert!(err.to_string().contains("warn_at for 'src/**'"), "{err}");
+}
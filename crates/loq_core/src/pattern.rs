@@ -0,0 +1,191 @@
+//! Glob pattern syntaxes.
+//!
+//! Config patterns are matched against root-relative match keys. By default
+//! they are plain globs; `pattern_syntax = "gitignore"` reads them the way
+//! `.gitignore` does instead.
+
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use serde::Deserialize;
+
+/// How glob patterns in a config are interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternSyntax {
+    /// Patterns are globs matched against the whole match key.
+    #[default]
+    Glob,
+    /// Patterns follow `.gitignore`: a leading `/` anchors the pattern to the
+    /// config root, a trailing `/` matches directories, and names without a
+    /// `/` match at any depth.
+    Gitignore,
+}
+
+impl PatternSyntax {
+    /// Returns the name used in config files.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Glob => "glob",
+            Self::Gitignore => "gitignore",
+        }
+    }
+}
+
+/// One config pattern compiled into the globs it stands for.
+#[derive(Debug, Clone)]
+pub(crate) struct Matcher {
+    globs: Vec<GlobMatcher>,
+}
+
+impl Matcher {
+    /// Compiles `pattern` under `syntax`.
+    pub(crate) fn new(pattern: &str, syntax: PatternSyntax) -> Result<Self, globset::Error> {
+        let globs = match syntax {
+            PatternSyntax::Glob => vec![pattern.to_string()],
+            PatternSyntax::Gitignore => gitignore_globs(pattern),
+        };
+        let globs = globs
+            .iter()
+            .map(|glob| build_glob(glob).map(|glob| glob.compile_matcher()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { globs })
+    }
+
+    /// Tests if `path` matches the pattern.
+    pub(crate) fn is_match(&self, path: &str) -> bool {
+        self.globs.iter().any(|glob| glob.is_match(path))
+    }
+
+    /// Tests if the pattern could match a path below the directory `dir`.
+    ///
+    /// Only the literal directories a glob starts with are compared, so this
+    /// errs towards `true` once a wildcard is reached.
+    pub(crate) fn could_match_under(&self, dir: &str) -> bool {
+        self.globs
+            .iter()
+            .any(|glob| could_match_under(glob.glob().glob(), dir))
+    }
+}
+
+fn could_match_under(glob: &str, dir: &str) -> bool {
+    let mut components = glob.split('/');
+    for name in dir.split('/').filter(|name| !name.is_empty()) {
+        match components.next() {
+            Some(component) if component.contains(['*', '?', '[', '{', '\\']) => return true,
+            Some(component) if same_name(component, name) => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    if cfg!(windows) {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Translates a `.gitignore`-style pattern into equivalent globs.
+///
+/// A pattern names files and directories alike, so unless it ends in `/` it
+/// also matches everything below a directory of that name.
+fn gitignore_globs(pattern: &str) -> Vec<String> {
+    let (pattern, dir_only) = pattern
+        .strip_suffix('/')
+        .map_or((pattern, false), |pattern| (pattern, true));
+    let body = pattern.strip_prefix('/').unwrap_or(pattern);
+    let base = if pattern.contains('/') {
+        body.to_string()
+    } else {
+        format!("**/{body}")
+    };
+    if base.ends_with("**") {
+        return vec![base];
+    }
+    let contents = format!("{base}/**");
+    if dir_only {
+        vec![contents]
+    } else {
+        vec![base, contents]
+    }
+}
+
+/// Builds a glob with loq's matching options.
+fn build_glob(pattern: &str) -> Result<Glob, globset::Error> {
+    let mut builder = GlobBuilder::new(pattern);
+    #[cfg(windows)]
+    {
+        builder.case_insensitive(true);
+    }
+    builder.literal_separator(true);
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        Matcher::new(pattern, PatternSyntax::Gitignore)
+            .unwrap()
+            .is_match(path)
+    }
+
+    #[test]
+    fn glob_syntax_matches_whole_key() {
+        let matcher = Matcher::new("build", PatternSyntax::Glob).unwrap();
+        assert!(matcher.is_match("build"));
+        assert!(!matcher.is_match("a/build"));
+        assert!(!matcher.is_match("build/out.rs"));
+    }
+
+    #[test]
+    fn bare_names_match_at_any_depth() {
+        assert!(matches("build", "build"));
+        assert!(matches("build", "pkg/build"));
+        assert!(matches("build", "pkg/build/out.rs"));
+        assert!(matches("*.log", "logs/today.log"));
+        assert!(!matches("build", "builds/out.rs"));
+    }
+
+    #[test]
+    fn leading_slash_anchors_to_root() {
+        assert!(matches("/dist", "dist/app.js"));
+        assert!(!matches("/dist", "web/dist/app.js"));
+        assert!(matches("docs/*.md", "docs/guide.md"));
+        assert!(!matches("docs/*.md", "pkg/docs/guide.md"));
+    }
+
+    #[test]
+    fn trailing_slash_matches_directories_only() {
+        assert!(matches("build/", "build/out.rs"));
+        assert!(matches("build/", "pkg/build/out.rs"));
+        assert!(!matches("build/", "build"));
+        assert!(matches("/vendor/", "vendor/lib.rs"));
+        assert!(!matches("/vendor/", "src/vendor/lib.rs"));
+    }
+
+    #[test]
+    fn directories_are_ruled_out_by_literal_prefixes() {
+        let matcher = Matcher::new("src/app/**/*.rs", PatternSyntax::Glob).unwrap();
+        assert!(matcher.could_match_under(""));
+        assert!(matcher.could_match_under("src"));
+        assert!(matcher.could_match_under("src/app/models"));
+        assert!(!matcher.could_match_under("vendor"));
+        assert!(!matcher.could_match_under("src/lib"));
+
+        let matcher = Matcher::new("src/main.rs", PatternSyntax::Glob).unwrap();
+        assert!(!matcher.could_match_under("src/main.rs/nested"));
+
+        let matcher = Matcher::new("*.rs", PatternSyntax::Gitignore).unwrap();
+        assert!(matcher.could_match_under("vendor/deep"));
+    }
+
+    #[test]
+    fn double_star_patterns_are_kept() {
+        assert!(matches("src/**", "src/a/b.rs"));
+        assert!(matches("**/migrations/**", "app/migrations/0001.py"));
+    }
+}
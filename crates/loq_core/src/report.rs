@@ -2,8 +2,14 @@
 //!
 //! Collects file check outcomes and generates structured reports.
 
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bundle::{BundleMember, BundleViolation};
 use crate::decide::MatchBy;
-use crate::Limit;
+use crate::directory::{DirectoryMetric, DirectoryViolation};
+use crate::{Limit, Severity};
 
 /// The result of checking a single file.
 #[derive(Debug)]
@@ -12,8 +18,14 @@ pub struct FileOutcome {
     pub display_path: String,
     /// Path relative to config root for rule matching and managed exact-path rules.
     pub match_key: String,
+    /// Config file that governed this file, or `None` for built-in defaults.
+    pub config_path: Option<PathBuf>,
     /// What happened when checking the file.
     pub kind: OutcomeKind,
+    /// Total lines, when the file was read as text.
+    pub lines: Option<usize>,
+    /// Tokens counted toward `[[bundles]]`, when the file is in one.
+    pub bundle_tokens: Option<usize>,
 }
 
 /// What happened when checking a file.
@@ -30,26 +42,106 @@ pub enum OutcomeKind {
     },
     /// File appears to be binary (contains null bytes).
     Binary,
-    /// File exceeds its configured budget.
+    /// File exceeds at least one of its configured budgets.
     Violation {
-        /// The configured budget.
-        limit: Limit,
-        /// Actual measured value.
-        actual: usize,
-        /// How the limit was determined.
+        /// The file's measurement against each budget, in config order.
+        measurements: Vec<Measurement>,
+        /// How the limits were determined.
         matched_by: MatchBy,
+        /// Whether exceeding a budget fails the check.
+        severity: Severity,
     },
-    /// File is within its configured budget.
+    /// File is within all of its configured budgets.
     Pass {
-        /// The configured budget.
-        limit: Limit,
-        /// Actual measured value.
-        actual: usize,
-        /// How the limit was determined.
+        /// The file's measurement against each budget, in config order.
+        measurements: Vec<Measurement>,
+        /// How the limits were determined.
         matched_by: MatchBy,
     },
 }
 
+/// A file's measurement against one of its budgets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    /// The configured budget.
+    pub limit: Limit,
+    /// Actual measured value.
+    pub actual: usize,
+    /// Whether a value within budget is above the `warn_at` fraction of it.
+    pub near_limit: bool,
+    /// The matched rule's guidance, rendered for an exceeded budget.
+    pub fix_guidance: Option<String>,
+    /// Lines over a line length budget.
+    pub long_lines: LongLines,
+    /// The file and everything it imports, depth first, when its rule
+    /// follows imports.
+    pub imports: Vec<Import>,
+}
+
+/// Lines over a line length budget.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LongLines {
+    /// One-based numbers of the first few long lines.
+    pub first: Vec<usize>,
+    /// How many lines are long, including those not in `first`.
+    pub count: usize,
+}
+
+impl LongLines {
+    /// Returns true when no line is long.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns how many long lines are not in `first`.
+    #[must_use]
+    pub fn hidden(&self) -> usize {
+        self.count.saturating_sub(self.first.len())
+    }
+}
+
+/// A file in the import tree of a measurement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    /// Path relative to working directory for display.
+    pub path: String,
+    /// Levels below the checked file, which is at depth 0.
+    pub depth: usize,
+    /// The file's own value for the measured metric.
+    pub actual: usize,
+}
+
+impl Measurement {
+    /// Creates a measurement that is not yet flagged or given guidance.
+    #[must_use]
+    pub const fn new(limit: Limit, actual: usize) -> Self {
+        Self {
+            limit,
+            actual,
+            near_limit: false,
+            fix_guidance: None,
+            long_lines: LongLines {
+                first: Vec::new(),
+                count: 0,
+            },
+            imports: Vec::new(),
+        }
+    }
+
+    /// Returns true when the value exceeds its budget.
+    #[must_use]
+    pub const fn is_over(&self) -> bool {
+        self.actual > self.limit.max
+    }
+
+    /// Returns the budget left, or zero once it is exceeded.
+    #[must_use]
+    pub const fn headroom(&self) -> usize {
+        self.limit.max.saturating_sub(self.actual)
+    }
+}
+
 /// Why a file was skipped (for warnings).
 #[derive(Debug)]
 pub enum SkipReason {
@@ -61,17 +153,50 @@ pub enum SkipReason {
     Missing,
 }
 
-/// A reportable finding (violation or skip warning).
+/// A reportable finding (violation, warning, directory or bundle violation, or
+/// skip warning).
 #[derive(Debug)]
 pub enum FindingKind {
-    /// File exceeded its configured budget.
+    /// File exceeded at least one of its configured budgets.
     Violation {
+        /// The file's measurement against each budget, in config order.
+        measurements: Vec<Measurement>,
+        /// How the limits were determined.
+        matched_by: MatchBy,
+    },
+    /// File exceeded a budget with `warning` severity.
+    Warning {
+        /// The file's measurement against each budget, in config order.
+        measurements: Vec<Measurement>,
+        /// How the limits were determined.
+        matched_by: MatchBy,
+    },
+    /// File is within its budgets but above the `warn_at` fraction of one.
+    NearLimit {
+        /// The file's measurement against each budget, in config order.
+        measurements: Vec<Measurement>,
+        /// How the limits were determined.
+        matched_by: MatchBy,
+    },
+    /// Directory exceeded a `[[directory_rules]]` budget.
+    DirectoryViolation {
+        /// What the budget counts.
+        metric: DirectoryMetric,
+        /// Actual total.
+        actual: usize,
         /// The configured budget.
+        max: usize,
+        /// The directory rule pattern that matched.
+        pattern: String,
+    },
+    /// Bundle's files exceeded their combined token budget.
+    BundleViolation {
+        /// The combined token budget.
         limit: Limit,
-        /// Actual measured value.
+        /// Combined tokens of the members.
         actual: usize,
-        /// How the limit was determined.
-        matched_by: MatchBy,
+        /// Every member, largest first.
+        members: Vec<BundleMember>,
     },
     /// File was skipped with a warning.
     SkipWarning {
@@ -80,6 +205,22 @@ pub enum FindingKind {
     },
 }
 
+impl FindingKind {
+    /// Returns the least budget left among a near-limit file's budgets above
+    /// their `warn_at` fraction, or `None` for other findings.
+    #[must_use]
+    pub fn headroom(&self) -> Option<usize> {
+        match self {
+            Self::NearLimit { measurements, .. } => measurements
+                .iter()
+                .filter(|measurement| measurement.near_limit)
+                .map(Measurement::headroom)
+                .min(),
+            _ => None,
+        }
+    }
+}
+
 /// A single finding to report.
 #[derive(Debug)]
 pub struct Finding {
@@ -100,12 +241,16 @@ pub struct Summary {
     pub passed: usize,
     /// Files with violations.
     pub errors: usize,
+    /// Files over a budget with `warning` severity.
+    pub warnings: usize,
+    /// Passing files above their `warn_at` fraction (also counted in `passed`).
+    pub near_limit: usize,
 }
 
 /// The complete report from a check run.
 #[derive(Debug)]
 pub struct Report {
-    /// All findings (skip warnings first, then violations by overage).
+    /// All findings (skip warnings, near-limit files, then warnings and violations by overage).
     pub findings: Vec<Finding>,
     /// Summary statistics.
     pub summary: Summary,
@@ -113,13 +258,20 @@ pub struct Report {
     pub fix_guidance: Option<String>,
 }
 
-/// Builds a report from file outcomes.
+/// Builds a report from file outcomes and directory and bundle violations.
 ///
-/// Aggregates outcomes into findings and summary statistics.
-/// Findings are sorted with skip warnings first, then violations by overage.
-/// If `fix_guidance` is provided and there are violations, it will be included in the report.
+/// Aggregates outcomes into findings and summary statistics; each directory
+/// or bundle violation counts as a violation.
+/// Findings are sorted with skip warnings first, then near-limit files, then
+/// warnings and violations by overage.
+/// If `fix_guidance` is provided and any file is over budget, it will be included in the report.
 #[must_use]
-pub fn build_report(outcomes: &[FileOutcome], fix_guidance: Option<String>) -> Report {
+pub fn build_report(
+    outcomes: &[FileOutcome],
+    directories: &[DirectoryViolation],
+    bundles: &[BundleViolation],
+    fix_guidance: Option<String>,
+) -> Report {
     let mut findings = Vec::new();
     let mut summary = Summary {
         total: outcomes.len(),
@@ -147,24 +299,62 @@ pub fn build_report(outcomes: &[FileOutcome], fix_guidance: Option<String>) -> R
                 summary.skipped += 1;
                 push_skip_warning(&mut findings, outcome, SkipReason::Binary);
             }
-            OutcomeKind::Pass { .. } => {
+            OutcomeKind::Pass {
+                measurements,
+                matched_by,
+            } => {
                 summary.passed += 1;
+                if measurements.iter().any(|m| m.near_limit) {
+                    summary.near_limit += 1;
+                    findings.push(Finding {
+                        path: outcome.display_path.clone(),
+                        kind: FindingKind::NearLimit {
+                            measurements: measurements.clone(),
+                            matched_by: matched_by.clone(),
+                        },
+                    });
+                }
             }
             OutcomeKind::Violation {
-                limit,
-                actual,
+                measurements,
                 matched_by,
+                severity,
             } => {
-                push_violation(&mut findings, outcome, *limit, *actual, matched_by.clone());
-                summary.errors += 1;
+                let measurements = measurements.clone();
+                let matched_by = matched_by.clone();
+                let kind = match severity {
+                    Severity::Error => {
+                        summary.errors += 1;
+                        FindingKind::Violation {
+                            measurements,
+                            matched_by,
+                        }
+                    }
+                    Severity::Warning => {
+                        summary.warnings += 1;
+                        FindingKind::Warning {
+                            measurements,
+                            matched_by,
+                        }
+                    }
+                };
+                findings.push(Finding {
+                    path: outcome.display_path.clone(),
+                    kind,
+                });
             }
         }
     }
 
+    summary.errors += directories.len();
+    findings.extend(directories.iter().map(directory_finding));
+    summary.errors += bundles.len();
+    findings.extend(bundles.iter().map(bundle_finding));
+
     sort_findings(&mut findings);
 
-    // Only include guidance if there are violations
-    let fix_guidance = if summary.errors > 0 {
+    // Only include guidance if some file is over budget
+    let fix_guidance = if summary.errors + summary.warnings > 0 {
         fix_guidance
     } else {
         None
@@ -184,24 +374,31 @@ fn push_skip_warning(findings: &mut Vec<Finding>, outcome: &FileOutcome, reason:
     });
 }
 
-fn push_violation(
-    findings: &mut Vec<Finding>,
-    outcome: &FileOutcome,
-    limit: Limit,
-    actual: usize,
-    matched_by: MatchBy,
-) {
-    findings.push(Finding {
-        path: outcome.display_path.clone(),
-        kind: FindingKind::Violation {
-            limit,
-            actual,
-            matched_by,
+fn directory_finding(directory: &DirectoryViolation) -> Finding {
+    Finding {
+        path: directory.display_path.clone(),
+        kind: FindingKind::DirectoryViolation {
+            metric: directory.metric,
+            actual: directory.actual,
+            max: directory.max,
+            pattern: directory.pattern.clone(),
         },
-    });
+    }
+}
+
+fn bundle_finding(bundle: &BundleViolation) -> Finding {
+    Finding {
+        path: bundle.name.clone(),
+        kind: FindingKind::BundleViolation {
+            limit: bundle.limit,
+            actual: bundle.actual,
+            members: bundle.members.clone(),
+        },
+    }
 }
 
-/// Sorts findings with skip warnings first, then violations by overage.
+/// Sorts findings with skip warnings first, then near-limit files by headroom,
+/// then warnings and violations by overage.
 pub fn sort_findings(findings: &mut [Finding]) {
     findings.sort_by(|a, b| {
         let rank_a = finding_rank(&a.kind);
@@ -209,177 +406,40 @@ pub fn sort_findings(findings: &mut [Finding]) {
         if rank_a != rank_b {
             return rank_a.cmp(&rank_b);
         }
-        match (&a.kind, &b.kind) {
-            (
-                FindingKind::Violation {
-                    limit: a_limit,
-                    actual: a_actual,
-                    ..
-                },
-                FindingKind::Violation {
-                    limit: b_limit,
-                    actual: b_actual,
-                    ..
-                },
-            ) => (a_actual - a_limit.max)
-                .cmp(&(b_actual - b_limit.max))
-                .then_with(|| a.path.cmp(&b.path)),
-            _ => a.path.cmp(&b.path),
-        }
+        let by_size = match (a.kind.headroom(), b.kind.headroom()) {
+            // Least headroom last, next to the files already over budget.
+            (Some(a_headroom), Some(b_headroom)) => b_headroom.cmp(&a_headroom),
+            _ => overage(&a.kind).cmp(&overage(&b.kind)),
+        };
+        by_size.then_with(|| a.path.cmp(&b.path))
     });
 }
 
 const fn finding_rank(kind: &FindingKind) -> u8 {
     match kind {
         FindingKind::SkipWarning { .. } => 0,
-        FindingKind::Violation { .. } => 1,
+        FindingKind::NearLimit { .. } => 1,
+        FindingKind::Warning { .. } => 2,
+        FindingKind::Violation { .. }
+        | FindingKind::DirectoryViolation { .. }
+        | FindingKind::BundleViolation { .. } => 3,
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::Limit;
-
-    #[test]
-    fn summary_counts_each_file_once() {
-        let outcomes = vec![
-            FileOutcome {
-                display_path: "a".into(),
-                match_key: "a".into(),
-                kind: OutcomeKind::Pass {
-                    limit: Limit::lines(10),
-                    actual: 5,
-                    matched_by: MatchBy::Default,
-                },
-            },
-            FileOutcome {
-                display_path: "b".into(),
-                match_key: "b".into(),
-                kind: OutcomeKind::Violation {
-                    limit: Limit::lines(10),
-                    actual: 20,
-                    matched_by: MatchBy::Default,
-                },
-            },
-            FileOutcome {
-                display_path: "c".into(),
-                match_key: "c".into(),
-                kind: OutcomeKind::Violation {
-                    limit: Limit::lines(10),
-                    actual: 12,
-                    matched_by: MatchBy::Default,
-                },
-            },
-            FileOutcome {
-                display_path: "d".into(),
-                match_key: "d".into(),
-                kind: OutcomeKind::Missing,
-            },
-            FileOutcome {
-                display_path: "e".into(),
-                match_key: "e".into(),
-                kind: OutcomeKind::Binary,
-            },
-            FileOutcome {
-                display_path: "f".into(),
-                match_key: "f".into(),
-                kind: OutcomeKind::Unreadable {
-                    error: "denied".into(),
-                },
-            },
-        ];
-        let report = build_report(&outcomes, None);
-        assert_eq!(report.summary.total, 6);
-        assert_eq!(report.summary.passed, 1);
-        assert_eq!(report.summary.errors, 2);
-        assert_eq!(report.summary.skipped, 3);
-    }
-
-    #[test]
-    fn findings_sorted_by_overage() {
-        let mut findings = vec![
-            Finding {
-                path: "b".into(),
-                kind: FindingKind::Violation {
-                    limit: Limit::lines(10),
-                    actual: 12,
-                    matched_by: MatchBy::Default,
-                },
-            },
-            Finding {
-                path: "a".into(),
-                kind: FindingKind::Violation {
-                    limit: Limit::lines(10),
-                    actual: 20,
-                    matched_by: MatchBy::Default,
-                },
-            },
-            Finding {
-                path: "c".into(),
-                kind: FindingKind::SkipWarning {
-                    reason: SkipReason::Missing,
-                },
-            },
-        ];
-        sort_findings(&mut findings);
-        // Skip warnings first, then violations sorted by overage (smallest first)
-        assert_eq!(findings[0].path, "c");
-        assert_eq!(findings[1].path, "b");
-        assert_eq!(findings[2].path, "a");
-    }
-
-    #[test]
-    fn nolimit_is_skipped() {
-        let outcomes = vec![FileOutcome {
-            display_path: "nolimit.js".into(),
-            match_key: "nolimit.js".into(),
-            kind: OutcomeKind::NoLimit,
-        }];
-        let report = build_report(&outcomes, None);
-        assert_eq!(report.summary.total, 1);
-        assert_eq!(report.summary.skipped, 1);
-        assert_eq!(report.summary.passed, 0);
-        assert_eq!(report.summary.errors, 0);
-        // No findings for nolimit
-        assert!(report.findings.is_empty());
-    }
-
-    #[test]
-    fn fix_guidance_included_when_violations_exist() {
-        let outcomes = vec![FileOutcome {
-            display_path: "big.rs".into(),
-            match_key: "big.rs".into(),
-            kind: OutcomeKind::Violation {
-                limit: Limit::lines(100),
-                actual: 150,
-                matched_by: MatchBy::Default,
-            },
-        }];
-        let guidance = Some("Split large files into smaller modules.".to_string());
-        let report = build_report(&outcomes, guidance);
-        assert_eq!(report.summary.errors, 1);
-        assert!(report.fix_guidance.is_some());
-        assert_eq!(
-            report.fix_guidance.unwrap(),
-            "Split large files into smaller modules."
-        );
-    }
-
-    #[test]
-    fn fix_guidance_excluded_when_no_violations() {
-        let outcomes = vec![FileOutcome {
-            display_path: "small.rs".into(),
-            match_key: "small.rs".into(),
-            kind: OutcomeKind::Pass {
-                limit: Limit::lines(100),
-                actual: 50,
-                matched_by: MatchBy::Default,
-            },
-        }];
-        let guidance = Some("Split large files into smaller modules.".to_string());
-        let report = build_report(&outcomes, guidance);
-        assert_eq!(report.summary.errors, 0);
-        assert!(report.fix_guidance.is_none());
+fn overage(kind: &FindingKind) -> usize {
+    match kind {
+        FindingKind::Violation { measurements, .. } | FindingKind::Warning { measurements, .. } => {
+            measurements
+                .iter()
+                .map(|m| m.actual.saturating_sub(m.limit.max))
+                .max()
+                .unwrap_or(0)
+        }
+        FindingKind::BundleViolation { limit, actual, .. } => actual.saturating_sub(limit.max),
+        FindingKind::DirectoryViolation { actual, max, .. } => actual.saturating_sub(*max),
+        FindingKind::NearLimit { .. } | FindingKind::SkipWarning { .. } => 0,
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,274 @@
+use super::*;
+use crate::Limit;
+
+#[test]
+fn summary_counts_each_file_once() {
+    let outcomes = vec![
+        FileOutcome {
+            display_path: "a".into(),
+            match_key: "a".into(),
+            config_path: None,
+            kind: OutcomeKind::Pass {
+                measurements: vec![Measurement::new(Limit::lines(10), 5)],
+                matched_by: MatchBy::Default,
+            },
+            lines: None,
+            bundle_tokens: None,
+        },
+        FileOutcome {
+            display_path: "b".into(),
+            match_key: "b".into(),
+            config_path: None,
+            kind: OutcomeKind::Violation {
+                measurements: vec![Measurement::new(Limit::lines(10), 20)],
+                matched_by: MatchBy::Default,
+                severity: Severity::Error,
+            },
+            lines: None,
+            bundle_tokens: None,
+        },
+        FileOutcome {
+            display_path: "c".into(),
+            match_key: "c".into(),
+            config_path: None,
+            kind: OutcomeKind::Violation {
+                measurements: vec![Measurement::new(Limit::lines(10), 12)],
+                matched_by: MatchBy::Default,
+                severity: Severity::Error,
+            },
+            lines: None,
+            bundle_tokens: None,
+        },
+        FileOutcome {
+            display_path: "d".into(),
+            match_key: "d".into(),
+            config_path: None,
+            kind: OutcomeKind::Missing,
+            lines: None,
+            bundle_tokens: None,
+        },
+        FileOutcome {
+            display_path: "e".into(),
+            match_key: "e".into(),
+            config_path: None,
+            kind: OutcomeKind::Binary,
+            lines: None,
+            bundle_tokens: None,
+        },
+        FileOutcome {
+            display_path: "f".into(),
+            match_key: "f".into(),
+            config_path: None,
+            kind: OutcomeKind::Unreadable {
+                error: "denied".into(),
+            },
+            lines: None,
+            bundle_tokens: None,
+        },
+    ];
+    let report = build_report(&outcomes, &[], &[], None);
+    assert_eq!(report.summary.total, 6);
+    assert_eq!(report.summary.passed, 1);
+    assert_eq!(report.summary.errors, 2);
+    assert_eq!(report.summary.skipped, 3);
+}
+
+#[test]
+fn findings_sorted_by_overage() {
+    let mut findings = vec![
+        Finding {
+            path: "b".into(),
+            kind: FindingKind::Violation {
+                measurements: vec![Measurement::new(Limit::lines(10), 12)],
+                matched_by: MatchBy::Default,
+            },
+        },
+        Finding {
+            path: "a".into(),
+            kind: FindingKind::Violation {
+                measurements: vec![Measurement::new(Limit::lines(10), 20)],
+                matched_by: MatchBy::Default,
+            },
+        },
+        Finding {
+            path: "c".into(),
+            kind: FindingKind::SkipWarning {
+                reason: SkipReason::Missing,
+            },
+        },
+    ];
+    sort_findings(&mut findings);
+    // Skip warnings first, then violations sorted by overage (smallest first)
+    assert_eq!(findings[0].path, "c");
+    assert_eq!(findings[1].path, "b");
+    assert_eq!(findings[2].path, "a");
+}
+
+#[test]
+fn nolimit_is_skipped() {
+    let outcomes = vec![FileOutcome {
+        display_path: "nolimit.js".into(),
+        match_key: "nolimit.js".into(),
+        config_path: None,
+        kind: OutcomeKind::NoLimit,
+        lines: None,
+        bundle_tokens: None,
+    }];
+    let report = build_report(&outcomes, &[], &[], None);
+    assert_eq!(report.summary.total, 1);
+    assert_eq!(report.summary.skipped, 1);
+    assert_eq!(report.summary.passed, 0);
+    assert_eq!(report.summary.errors, 0);
+    // No findings for nolimit
+    assert!(report.findings.is_empty());
+}
+
+#[test]
+fn fix_guidance_included_when_violations_exist() {
+    let outcomes = vec![FileOutcome {
+        display_path: "big.rs".into(),
+        match_key: "big.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![Measurement::new(Limit::lines(100), 150)],
+            matched_by: MatchBy::Default,
+            severity: Severity::Error,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+    let guidance = Some("Split large files into smaller modules.".to_string());
+    let report = build_report(&outcomes, &[], &[], guidance);
+    assert_eq!(report.summary.errors, 1);
+    assert!(report.fix_guidance.is_some());
+    assert_eq!(
+        report.fix_guidance.unwrap(),
+        "Split large files into smaller modules."
+    );
+}
+
+#[test]
+fn warnings_counted_apart_from_errors() {
+    let over = |path: &str, actual, severity| FileOutcome {
+        display_path: path.into(),
+        match_key: path.into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![Measurement::new(Limit::lines(10), actual)],
+            matched_by: MatchBy::Default,
+            severity,
+        },
+        lines: None,
+        bundle_tokens: None,
+    };
+    let outcomes = vec![
+        over("error.rs", 11, Severity::Error),
+        over("warn.rs", 30, Severity::Warning),
+    ];
+    let report = build_report(&outcomes, &[], &[], Some("Split it.".to_string()));
+    assert_eq!(report.summary.errors, 1);
+    assert_eq!(report.summary.warnings, 1);
+    assert!(matches!(
+        report.findings[0].kind,
+        FindingKind::Warning { .. }
+    ));
+    assert!(matches!(
+        report.findings[1].kind,
+        FindingKind::Violation { .. }
+    ));
+
+    let report = build_report(&outcomes[1..], &[], &[], Some("Split it.".to_string()));
+    assert_eq!(report.summary.errors, 0);
+    assert_eq!(report.fix_guidance.as_deref(), Some("Split it."));
+}
+
+#[test]
+fn fix_guidance_excluded_when_no_violations() {
+    let outcomes = vec![FileOutcome {
+        display_path: "small.rs".into(),
+        match_key: "small.rs".into(),
+        config_path: None,
+        kind: OutcomeKind::Pass {
+            measurements: vec![Measurement::new(Limit::lines(100), 50)],
+            matched_by: MatchBy::Default,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+    let guidance = Some("Split large files into smaller modules.".to_string());
+    let report = build_report(&outcomes, &[], &[], guidance);
+    assert_eq!(report.summary.errors, 0);
+    assert!(report.fix_guidance.is_none());
+}
+
+#[test]
+fn near_limit_passes_are_reported_with_headroom() {
+    let pass = |path: &str, actual, near_limit| FileOutcome {
+        display_path: path.into(),
+        match_key: path.into(),
+        config_path: None,
+        kind: OutcomeKind::Pass {
+            measurements: vec![Measurement {
+                near_limit,
+                ..Measurement::new(Limit::lines(100), actual)
+            }],
+            matched_by: MatchBy::Default,
+        },
+        lines: None,
+        bundle_tokens: None,
+    };
+    let outcomes = vec![
+        pass("ok.rs", 50, false),
+        pass("close.rs", 99, true),
+        pass("closer.rs", 92, true),
+    ];
+    let report = build_report(&outcomes, &[], &[], Some("Split it.".to_string()));
+    assert_eq!(report.summary.passed, 3);
+    assert_eq!(report.summary.near_limit, 2);
+    assert_eq!(report.summary.errors, 0);
+    assert!(report.fix_guidance.is_none());
+
+    let headroom: Vec<_> = report
+        .findings
+        .iter()
+        .map(|finding| (finding.path.as_str(), finding.kind.headroom()))
+        .collect();
+    assert_eq!(
+        headroom,
+        vec![("closer.rs", Some(8)), ("close.rs", Some(1))]
+    );
+}
+
+#[test]
+fn file_over_several_limits_is_one_finding() {
+    let outcomes = vec![FileOutcome {
+        display_path: "AGENTS.md".into(),
+        match_key: "AGENTS.md".into(),
+        config_path: None,
+        kind: OutcomeKind::Violation {
+            measurements: vec![
+                Measurement::new(Limit::lines(300), 320),
+                Measurement::new(Limit::tokens(6000), 5000),
+                Measurement::new(Limit::bytes(20_000), 24_000),
+            ],
+            matched_by: MatchBy::Default,
+            severity: Severity::Error,
+        },
+        lines: None,
+        bundle_tokens: None,
+    }];
+    let report = build_report(&outcomes, &[], &[], None);
+
+    assert_eq!(report.summary.errors, 1);
+    assert_eq!(report.findings.len(), 1);
+    let FindingKind::Violation { measurements, .. } = &report.findings[0].kind else {
+        panic!("expected violation, got {:?}", report.findings[0].kind);
+    };
+    let over: Vec<_> = measurements
+        .iter()
+        .filter(|m| m.is_over())
+        .map(|m| m.limit.metric)
+        .collect();
+    assert_eq!(over, [crate::Metric::Lines, crate::Metric::Bytes]);
+    assert_eq!(measurements.len(), 3);
+}
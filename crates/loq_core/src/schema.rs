@@ -0,0 +1,8 @@
+//! JSON Schema for loq config files.
+//!
+//! Editors with a TOML language server use it to complete keys and flag
+//! invalid settings. A test in [`crate::parse`] keeps it in sync with the
+//! keys the parser accepts.
+
+/// JSON Schema (draft-07) for `loq.toml` and embedded loq tables.
+pub const CONFIG_SCHEMA: &str = include_str!("loq.schema.json");
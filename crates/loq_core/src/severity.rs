@@ -0,0 +1,25 @@
+//! How seriously a limit is enforced.
+
+use serde::Deserialize;
+
+/// Whether exceeding a limit fails the check or only warns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Exceeding the limit is a violation and fails the check.
+    #[default]
+    Error,
+    /// Exceeding the limit is reported but does not fail the check.
+    Warning,
+}
+
+impl Severity {
+    /// Returns the name used in config files and JSON output.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
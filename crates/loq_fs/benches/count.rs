@@ -5,7 +5,8 @@ use std::io::Write;
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use tempfile::NamedTempFile;
 
-use loq_fs::count::inspect_file;
+use loq_fs::count::{inspect_file, Tokens};
+use loq_fs::line_length::LineLength;
 
 fn create_test_file(lines: usize) -> NamedTempFile {
     let mut file = NamedTempFile::new().unwrap();
@@ -25,7 +26,14 @@ fn bench_line_counting(c: &mut Criterion) {
     for size in [100, 1000, 10_000, 100_000] {
         let file = create_test_file(size);
         group.bench_with_input(BenchmarkId::new("inspect_file", size), &file, |b, file| {
-            b.iter(|| inspect_file(black_box(file.path())));
+            b.iter(|| {
+                inspect_file(
+                    black_box(file.path()),
+                    None,
+                    Tokens::default(),
+                    LineLength::default(),
+                )
+            });
         });
     }
 
@@ -13,8 +13,9 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
 use loq_core::config::CompiledConfig;
+use loq_core::LongLines;
 
-const CACHE_VERSION: u32 = 2; // Bumped for CachedResult enum
+const CACHE_VERSION: u32 = 8; // Bumped for the limits each entry was measured for
 const CACHE_FILE: &str = ".loq_cache";
 
 /// On-disk cache format (for deserialization).
@@ -37,14 +38,39 @@ struct CacheFileRef<'a> {
 ///
 /// Only cacheable results are included - missing/unreadable files can't be cached
 /// because we need an mtime for cache invalidation, and `metadata()` fails for those.
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum CachedResult {
-    /// File is text with the measured value for the active metric.
-    Text(usize),
+    /// File is text with the measured value for each of its limits, in order.
+    Text {
+        /// Measured values, one per limit.
+        actuals: Vec<usize>,
+        /// Lines over a line length limit.
+        #[serde(default, skip_serializing_if = "LongLines::is_empty")]
+        long_lines: LongLines,
+        /// Total lines, for directory budgets.
+        lines: usize,
+        /// Hash of the limits the values were measured for.
+        limits_hash: u64,
+    },
     /// File is binary.
     Binary,
 }
 
+impl CachedResult {
+    #[cfg(test)]
+    pub(crate) const fn text(actuals: Vec<usize>) -> Self {
+        Self::Text {
+            actuals,
+            long_lines: LongLines {
+                first: Vec::new(),
+                count: 0,
+            },
+            lines: 0,
+            limits_hash: 0,
+        }
+    }
+}
+
 /// Single cache entry for a file.
 #[derive(Serialize, Deserialize, Clone)]
 struct CacheEntry {
@@ -111,7 +137,7 @@ impl Cache {
         let (secs, nanos) = mtime_to_parts(mtime);
 
         if entry.mtime_secs == secs && entry.mtime_nanos == nanos {
-            Some(entry.result)
+            Some(entry.result.clone())
         } else {
             None
         }
@@ -159,19 +185,28 @@ fn mtime_to_parts(mtime: SystemTime) -> (u64, u32) {
 }
 
 /// Computes a hash of the config for cache invalidation.
+///
+/// `tokenizer` is the hash of the tokenizer's vocabulary, so editing the file
+/// invalidates cached token counts.
 #[must_use]
-pub fn hash_config(config: &CompiledConfig) -> u64 {
+pub fn hash_config(config: &CompiledConfig, tokenizer: Option<u64>) -> u64 {
     let mut hasher = rustc_hash::FxHasher::default();
+    tokenizer.hash(&mut hasher);
 
     // Hash default limit.
     config.default_limit.hash(&mut hasher);
+    config.pattern_syntax.hash(&mut hasher);
+    config.line_length_unit.hash(&mut hasher);
 
     // Hash rules (patterns and limits)
     for rule in config.rules() {
-        rule.limit.hash(&mut hasher);
+        rule.limits.hash(&mut hasher);
         for pattern in &rule.patterns {
             pattern.hash(&mut hasher);
         }
+        for pattern in &rule.exclude {
+            pattern.hash(&mut hasher);
+        }
     }
 
     // Hash exclude patterns
@@ -209,11 +244,15 @@ mod tests {
         let mut cache = Cache::empty_with_hash(123);
         let mtime = SystemTime::now();
 
-        cache.insert("src/main.rs".to_string(), mtime, CachedResult::Text(42));
+        cache.insert(
+            "src/main.rs".to_string(),
+            mtime,
+            CachedResult::text(vec![42]),
+        );
 
         assert_eq!(
             cache.get("src/main.rs", mtime),
-            Some(CachedResult::Text(42))
+            Some(CachedResult::text(vec![42]))
         );
     }
 
@@ -233,7 +272,11 @@ mod tests {
         let mtime1 = SystemTime::UNIX_EPOCH;
         let mtime2 = SystemTime::now();
 
-        cache.insert("src/main.rs".to_string(), mtime1, CachedResult::Text(42));
+        cache.insert(
+            "src/main.rs".to_string(),
+            mtime1,
+            CachedResult::text(vec![42]),
+        );
 
         assert!(cache.get("src/main.rs", mtime2).is_none());
     }
@@ -246,13 +289,16 @@ mod tests {
         // Create and populate cache with different result types
         let mut cache = Cache::empty_with_hash(config_hash);
         let mtime = SystemTime::UNIX_EPOCH;
-        cache.insert("test.rs".to_string(), mtime, CachedResult::Text(100));
+        cache.insert("test.rs".to_string(), mtime, CachedResult::text(vec![100]));
         cache.insert("binary.dat".to_string(), mtime, CachedResult::Binary);
         cache.save(temp.path());
 
         // Load cache
         let loaded = Cache::load(temp.path(), config_hash);
-        assert_eq!(loaded.get("test.rs", mtime), Some(CachedResult::Text(100)));
+        assert_eq!(
+            loaded.get("test.rs", mtime),
+            Some(CachedResult::text(vec![100]))
+        );
         assert_eq!(loaded.get("binary.dat", mtime), Some(CachedResult::Binary));
     }
 
@@ -265,7 +311,7 @@ mod tests {
         cache.insert(
             "test.rs".to_string(),
             SystemTime::UNIX_EPOCH,
-            CachedResult::Text(100),
+            CachedResult::text(vec![100]),
         );
         cache.save(temp.path());
 
@@ -275,7 +321,7 @@ mod tests {
     }
 
     #[test]
-    fn old_cache_version_is_discarded_and_rebuilt_as_v2() {
+    fn old_cache_version_is_discarded_and_rebuilt_as_current() {
         let temp = TempDir::new().unwrap();
         let config_hash = 12345u64;
 
@@ -302,25 +348,28 @@ mod tests {
 
         // Insert new entry and save
         let mtime = SystemTime::UNIX_EPOCH;
-        loaded.insert("new.rs".to_string(), mtime, CachedResult::Text(50));
+        loaded.insert("new.rs".to_string(), mtime, CachedResult::text(vec![50]));
         loaded.save(temp.path());
 
-        // Verify the saved cache is v2 format
+        // Verify the saved cache is in the current format
         let contents = fs::read_to_string(temp.path().join(CACHE_FILE)).unwrap();
         let saved: serde_json::Value = serde_json::from_str(&contents).unwrap();
-        assert_eq!(saved["version"], 2, "saved cache should be v2");
+        assert_eq!(
+            saved["version"], CACHE_VERSION,
+            "saved cache should use the current version"
+        );
         assert!(
             saved["entries"]["new.rs"]["result"].is_object()
                 || saved["entries"]["new.rs"]["result"].is_string(),
-            "v2 cache should have 'result' field, not 'lines'"
+            "current cache should have 'result' field, not 'lines'"
         );
 
         // Verify it can be reloaded
         let reloaded = Cache::load(temp.path(), config_hash);
         assert_eq!(
             reloaded.get("new.rs", mtime),
-            Some(CachedResult::Text(50)),
-            "v2 cache should roundtrip correctly"
+            Some(CachedResult::text(vec![50])),
+            "current cache should roundtrip correctly"
         );
     }
 
@@ -330,15 +379,79 @@ mod tests {
         let config2 = make_config(Some(600));
         let config3 = make_config(None);
 
-        let hash1 = hash_config(&config1);
-        let hash2 = hash_config(&config2);
-        let hash3 = hash_config(&config3);
+        let hash1 = hash_config(&config1, None);
+        let hash2 = hash_config(&config2, None);
+        let hash3 = hash_config(&config3, None);
 
         assert_ne!(hash1, hash2);
         assert_ne!(hash1, hash3);
         assert_ne!(hash2, hash3);
     }
 
+    #[test]
+    fn hash_config_changes_with_metric() {
+        let lines = make_config(Some(500));
+        let bytes = compile_config(
+            PathBuf::from("."),
+            LoqConfig {
+                default_limit: Some(loq_core::Limit::bytes(500)),
+                ..LoqConfig::default()
+            },
+            None,
+        )
+        .unwrap();
+
+        assert_ne!(hash_config(&lines, None), hash_config(&bytes, None));
+    }
+
+    #[test]
+    fn hash_config_changes_with_tokenizer() {
+        let config = make_config(Some(500));
+        let first = hash_config(&config, Some(1));
+
+        assert_ne!(first, hash_config(&config, None));
+        assert_ne!(first, hash_config(&config, Some(2)));
+    }
+
+    #[test]
+    fn hash_config_changes_with_token_estimate() {
+        let config = |estimate| {
+            let config = LoqConfig {
+                default_limit: Some(loq_core::Limit::tokens(500)),
+                token_estimate: estimate,
+                ..LoqConfig::default()
+            };
+            compile_config(PathBuf::from("."), config, None).unwrap()
+        };
+        let default = hash_config(&config(None), None);
+        let bytes = hash_config(
+            &config(Some(loq_core::TokenEstimate::BytesPerToken(3.2))),
+            None,
+        );
+        let chars = hash_config(
+            &config(Some(loq_core::TokenEstimate::CharsPerToken(3.2))),
+            None,
+        );
+
+        assert_ne!(default, bytes);
+        assert_ne!(bytes, chars);
+    }
+
+    #[test]
+    fn hash_config_reflects_overrides() {
+        let base = LoqConfig::default();
+        let overrides = loq_core::parse_overrides(&[
+            "default_max_lines=100".to_string(),
+            "exclude=[\"gen/**\"]".to_string(),
+        ])
+        .unwrap();
+        let plain = compile_config(PathBuf::from("."), base.clone(), None).unwrap();
+        let overridden =
+            compile_config(PathBuf::from("."), base.with_overrides(&overrides), None).unwrap();
+
+        assert_ne!(hash_config(&plain, None), hash_config(&overridden, None));
+    }
+
     #[test]
     fn no_save_when_unchanged() {
         let temp = TempDir::new().unwrap();
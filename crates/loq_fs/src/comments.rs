@@ -0,0 +1,418 @@
+//! Comment-aware line counting.
+//!
+//! Counts the lines of a source file that hold code outside comments, using a
+//! table of comment syntaxes keyed by file extension. String literals are
+//! tracked so comment markers inside them are not mistaken for comments.
+
+use std::path::Path;
+
+/// How comments and strings are written in one family of languages.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CommentSyntax {
+    /// Markers that comment out the rest of a line, e.g. `//`.
+    line: &'static [&'static str],
+    /// Start and end markers of block comments, e.g. `/*` and `*/`.
+    block: &'static [(&'static str, &'static str)],
+    /// Whether block comments nest, as `/* /* */ */` does in Rust.
+    nested_blocks: bool,
+    /// String delimiters, longest first so `"""` wins over `"`.
+    strings: &'static [&'static str],
+    /// String delimiters whose strings may run onto following lines. Other
+    /// strings are assumed to close on the line they open.
+    multiline_strings: &'static [&'static str],
+    /// Whether Rust raw strings such as `r#"..."#` are recognized.
+    raw_strings: bool,
+    /// String delimiters that form a doc block when they open a statement.
+    docstrings: &'static [&'static str],
+    /// Whether `'x'` is a character literal rather than a string delimiter.
+    char_literals: bool,
+}
+
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    nested_blocks: false,
+    strings: &["\""],
+    multiline_strings: &["\""],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: true,
+};
+
+const RUST: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    nested_blocks: true,
+    strings: &["\""],
+    multiline_strings: &["\""],
+    raw_strings: true,
+    docstrings: &[],
+    char_literals: true,
+};
+
+const JAVASCRIPT: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    nested_blocks: false,
+    strings: &["\"", "'", "`"],
+    multiline_strings: &["`"],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: false,
+};
+
+const GO: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    nested_blocks: false,
+    strings: &["\"", "`"],
+    multiline_strings: &["`"],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: true,
+};
+
+const PYTHON: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    nested_blocks: false,
+    strings: &["\"\"\"", "'''", "\"", "'"],
+    multiline_strings: &["\"\"\"", "'''"],
+    raw_strings: false,
+    docstrings: &["\"\"\"", "'''"],
+    char_literals: false,
+};
+
+const HASH: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    nested_blocks: false,
+    strings: &["\"", "'"],
+    multiline_strings: &[],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: false,
+};
+
+const SQL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[("/*", "*/")],
+    nested_blocks: false,
+    strings: &["'", "\""],
+    multiline_strings: &[],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: false,
+};
+
+const LUA: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[("--[[", "]]")],
+    nested_blocks: false,
+    strings: &["\"", "'"],
+    multiline_strings: &[],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: false,
+};
+
+const HASKELL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[("{-", "-}")],
+    nested_blocks: true,
+    strings: &["\""],
+    multiline_strings: &[],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: true,
+};
+
+const CSS: CommentSyntax = CommentSyntax {
+    line: &[],
+    block: &[("/*", "*/")],
+    nested_blocks: false,
+    strings: &["\"", "'"],
+    multiline_strings: &[],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: false,
+};
+
+const SCSS: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    nested_blocks: false,
+    strings: &["\"", "'"],
+    multiline_strings: &[],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: false,
+};
+
+const MARKUP: CommentSyntax = CommentSyntax {
+    line: &[],
+    block: &[("<!--", "-->")],
+    nested_blocks: false,
+    strings: &[],
+    multiline_strings: &[],
+    raw_strings: false,
+    docstrings: &[],
+    char_literals: false,
+};
+
+/// Comment syntaxes by file extension.
+const SYNTAXES: &[(&[&str], &CommentSyntax)] = &[
+    (
+        &[
+            "c", "h", "cc", "cpp", "cxx", "hh", "hpp", "cs", "java", "kt", "kts", "scala", "swift",
+            "dart", "zig",
+        ],
+        &C_LIKE,
+    ),
+    (&["rs"], &RUST),
+    (
+        &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"],
+        &JAVASCRIPT,
+    ),
+    (&["go"], &GO),
+    (&["py", "pyi"], &PYTHON),
+    (
+        &[
+            "sh", "bash", "zsh", "fish", "rb", "pl", "r", "yaml", "yml", "toml", "ps1", "tf",
+        ],
+        &HASH,
+    ),
+    (&["sql"], &SQL),
+    (&["lua"], &LUA),
+    (&["hs"], &HASKELL),
+    (&["css"], &CSS),
+    (&["scss", "less"], &SCSS),
+    (&["html", "htm", "xml", "svg", "vue"], &MARKUP),
+];
+
+/// Returns the comment syntax for `path`'s extension, if loq knows it.
+#[must_use]
+pub fn syntax_for(path: &Path) -> Option<&'static CommentSyntax> {
+    let extension = path.extension()?.to_str()?;
+    SYNTAXES
+        .iter()
+        .find(|(extensions, _)| {
+            extensions
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(extension))
+        })
+        .map(|(_, syntax)| *syntax)
+}
+
+/// What the scanner is inside of when a line starts.
+#[derive(Clone, Copy)]
+enum State {
+    Code,
+    /// Inside a block comment with these markers, nested `depth` deep.
+    Block {
+        markers: (&'static str, &'static str),
+        depth: usize,
+    },
+    String(&'static str),
+    /// Inside a raw string closed by `"` and this many `#`s.
+    RawString(usize),
+    Docstring(&'static str),
+}
+
+/// Counts the lines of `text` that hold code outside comments.
+///
+/// Blank lines, comment-only lines and docstring lines are not counted.
+#[must_use]
+pub fn count_source_lines(text: &[u8], syntax: &CommentSyntax) -> usize {
+    let mut state = State::Code;
+    let mut count = 0;
+    for line in text.split(|byte| *byte == b'\n') {
+        let (has_code, next) = scan_line(line, state, syntax);
+        count += usize::from(has_code);
+        state = next;
+    }
+    // A trailing newline leaves an empty final segment, which has no code.
+    count
+}
+
+/// Scans one line starting in `state`, returning whether it holds code and
+/// the state the next line starts in.
+fn scan_line(line: &[u8], mut state: State, syntax: &CommentSyntax) -> (bool, State) {
+    let mut has_code = false;
+    let mut index = 0;
+    while index < line.len() {
+        let rest = &line[index..];
+        match state {
+            State::Block {
+                markers: (start, end),
+                depth,
+            } => {
+                if rest.starts_with(end.as_bytes()) {
+                    index += end.len();
+                    state = match depth {
+                        1 => State::Code,
+                        _ => State::Block {
+                            markers: (start, end),
+                            depth: depth - 1,
+                        },
+                    };
+                } else if syntax.nested_blocks && rest.starts_with(start.as_bytes()) {
+                    index += start.len();
+                    state = State::Block {
+                        markers: (start, end),
+                        depth: depth + 1,
+                    };
+                } else {
+                    index += 1;
+                }
+            }
+            State::Docstring(end) => match find(rest, end) {
+                Some(at) => {
+                    index += at + end.len();
+                    state = State::Code;
+                }
+                None => return (has_code, state),
+            },
+            State::String(end) => {
+                has_code = true;
+                match find_unescaped(rest, end) {
+                    Some(at) => {
+                        index += at + end.len();
+                        state = State::Code;
+                    }
+                    None if syntax.multiline_strings.contains(&end) => return (true, state),
+                    None => return (true, State::Code),
+                }
+            }
+            State::RawString(hashes) => {
+                has_code = true;
+                match find_raw_end(rest, hashes) {
+                    Some(len) => {
+                        index += len;
+                        state = State::Code;
+                    }
+                    None => return (true, state),
+                }
+            }
+            State::Code => {
+                if rest[0].is_ascii_whitespace() {
+                    index += 1;
+                } else if let Some(markers) = starts_with_any(rest, syntax.block) {
+                    index += markers.0.len();
+                    state = State::Block { markers, depth: 1 };
+                } else if let Some((len, hashes)) = raw_string_start(line, index, syntax) {
+                    has_code = true;
+                    index += len;
+                    state = State::RawString(hashes);
+                } else if syntax
+                    .line
+                    .iter()
+                    .any(|marker| rest.starts_with(marker.as_bytes()))
+                {
+                    return (has_code, State::Code);
+                } else if let Some(delimiter) = starts_with_one_of(rest, syntax.strings) {
+                    index += delimiter.len();
+                    state = if !has_code && syntax.docstrings.contains(&delimiter) {
+                        State::Docstring(delimiter)
+                    } else {
+                        has_code = true;
+                        State::String(delimiter)
+                    };
+                } else if syntax.char_literals && rest[0] == b'\'' {
+                    has_code = true;
+                    index += char_literal_len(rest);
+                } else {
+                    has_code = true;
+                    index += 1;
+                }
+            }
+        }
+    }
+    (has_code, state)
+}
+
+/// Returns the length of a Rust raw string opening such as `r#"` or `br"`
+/// at `line[index..]`, and the number of `#`s that close the string.
+fn raw_string_start(line: &[u8], index: usize, syntax: &CommentSyntax) -> Option<(usize, usize)> {
+    let in_word = index > 0 && (line[index - 1].is_ascii_alphanumeric() || line[index - 1] == b'_');
+    if !syntax.raw_strings || in_word {
+        return None;
+    }
+    let rest = &line[index..];
+    let prefix = match rest {
+        [b'b' | b'c', b'r', ..] => 2,
+        [b'r', ..] => 1,
+        _ => return None,
+    };
+    let hashes = rest[prefix..]
+        .iter()
+        .take_while(|byte| **byte == b'#')
+        .count();
+    (rest.get(prefix + hashes) == Some(&b'"')).then_some((prefix + hashes + 1, hashes))
+}
+
+/// Returns the length of `text` up to and including the `"` and `hashes`
+/// `#`s that close a raw string.
+fn find_raw_end(text: &[u8], hashes: usize) -> Option<usize> {
+    (0..text.len())
+        .find(|&at| {
+            text[at] == b'"'
+                && text
+                    .get(at + 1..at + 1 + hashes)
+                    .is_some_and(|tail| tail.iter().all(|byte| *byte == b'#'))
+        })
+        .map(|at| at + 1 + hashes)
+}
+
+fn starts_with_any(
+    text: &[u8],
+    pairs: &'static [(&'static str, &'static str)],
+) -> Option<(&'static str, &'static str)> {
+    pairs
+        .iter()
+        .find(|(start, _)| text.starts_with(start.as_bytes()))
+        .copied()
+}
+
+fn starts_with_one_of(text: &[u8], markers: &'static [&'static str]) -> Option<&'static str> {
+    markers
+        .iter()
+        .find(|marker| text.starts_with(marker.as_bytes()))
+        .copied()
+}
+
+fn find(text: &[u8], needle: &str) -> Option<usize> {
+    text.windows(needle.len())
+        .position(|window| window == needle.as_bytes())
+}
+
+/// Finds `needle` in `text`, skipping anything escaped with a backslash.
+fn find_unescaped(text: &[u8], needle: &str) -> Option<usize> {
+    let mut index = 0;
+    while index < text.len() {
+        if text[index] == b'\\' {
+            index += 2;
+        } else if text[index..].starts_with(needle.as_bytes()) {
+            return Some(index);
+        } else {
+            index += 1;
+        }
+    }
+    None
+}
+
+/// Returns the length of a character literal such as `'"'` or `'\''` at the
+/// start of `text`, or 1 for a lone quote such as a Rust lifetime.
+fn char_literal_len(text: &[u8]) -> usize {
+    match text {
+        [b'\'', b'\\', _, rest @ ..] => rest
+            .iter()
+            .position(|byte| *byte == b'\'')
+            .map_or(1, |at| at + 4),
+        [b'\'', _, b'\'', ..] => 3,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,142 @@
+use super::*;
+
+fn count(extension: &str, text: &str) -> usize {
+    let path = format!("file.{extension}");
+    let syntax = syntax_for(Path::new(&path)).expect("known extension");
+    count_source_lines(text.as_bytes(), syntax)
+}
+
+#[test]
+fn unknown_extensions_have_no_syntax() {
+    assert_eq!(syntax_for(Path::new("notes.txt")), None);
+    assert_eq!(syntax_for(Path::new("Makefile")), None);
+}
+
+#[test]
+fn extensions_match_case_insensitively() {
+    assert_eq!(syntax_for(Path::new("Main.RS")), Some(&RUST));
+}
+
+#[test]
+fn rust_doc_and_line_comments_are_skipped() {
+    let text =
+        "//! Module docs.\n\n/// Adds one.\nfn add(x: u8) -> u8 {\n    x + 1 // trailing\n}\n";
+    assert_eq!(count("rs", text), 3);
+}
+
+#[test]
+fn rust_block_comments_nest() {
+    let text = "/* outer /* inner */ still comment */\nlet a = 1; /* x /* y */ z */\n/* /*\n*/ */ let b = 2;\n";
+    assert_eq!(count("rs", text), 2);
+    // C block comments end at the first `*/`.
+    assert_eq!(count("c", "/* a /* b */ int x;\n"), 1);
+}
+
+#[test]
+fn c_block_comments_span_lines() {
+    let text = "/*\n * Header.\n */\nint a; /* note */\n/* a */ int b;\nint c; /* open\nstill comment */ int d;\n";
+    assert_eq!(count("c", text), 4);
+}
+
+#[test]
+fn comment_markers_inside_strings_are_code() {
+    let text = "let url = \"https://example.com\";\nlet s = \"/* not a comment\";\nlet t = 1;\n";
+    assert_eq!(count("rs", text), 3);
+}
+
+#[test]
+fn escaped_quotes_do_not_end_strings() {
+    let text = "let s = \"a \\\" // b\";\n// comment\n";
+    assert_eq!(count("rs", text), 1);
+}
+
+#[test]
+fn strings_carry_across_lines() {
+    let rust = "let s = \"first\n// inside string\n/* still */\";\n// comment\n";
+    assert_eq!(count("rs", rust), 3);
+    let c = "char *s = \"a \\\n// continued\";\n";
+    assert_eq!(count("c", c), 2);
+    let go = "s := `first\n/* raw */ \" not a string\n`\n// comment\n";
+    assert_eq!(count("go", go), 3);
+}
+
+#[test]
+fn rust_raw_strings_ignore_quotes_and_escapes() {
+    let text = "let a = r#\"a \" // b\"#;\nlet b = r\"C:\\\";\n// comment\nlet c = br##\"\n\"# // not closed\n\"##;\n";
+    assert_eq!(count("rs", text), 5);
+    // Identifiers ending in `r` do not open raw strings.
+    assert_eq!(count("rs", "let s = bar\"\";\n// comment\n"), 1);
+}
+
+#[test]
+fn rust_char_literals_and_lifetimes_are_code() {
+    let text = "let q = '\"'; // quote\nfn f<'a>(s: &'a str) {}\nlet e = '\\'';\n";
+    assert_eq!(count("rs", text), 3);
+}
+
+#[test]
+fn javascript_template_literals_span_lines() {
+    let text = "const s = `\n// inside template\n`;\n// comment\nconst t = 'a // b';\n";
+    assert_eq!(count("ts", text), 4);
+}
+
+#[test]
+fn go_raw_strings_span_lines() {
+    let text = "s := `\n/* raw */\n`\n// comment\n";
+    assert_eq!(count("go", text), 3);
+}
+
+#[test]
+fn python_docstrings_and_comments_are_skipped() {
+    let text = "\"\"\"Module docs.\n\nMore docs.\n\"\"\"\n# comment\ndef f():\n    '''Docstring.'''\n    return \"# not a comment\"\n";
+    assert_eq!(count("py", text), 2);
+}
+
+#[test]
+fn python_triple_quoted_values_are_code() {
+    let text = "query = \"\"\"\nSELECT 1 -- x\n\"\"\"\n";
+    assert_eq!(count("py", text), 3);
+}
+
+#[test]
+fn hash_comments_skip_quoted_hashes() {
+    let text = "# comment\necho \"#1\"\nkey: 'a # b' # trailing\n";
+    assert_eq!(count("sh", text), 2);
+    assert_eq!(count("yaml", "# top\nname: loq\n"), 1);
+}
+
+#[test]
+fn sql_dash_comments() {
+    let text = "-- comment\nSELECT '--not' FROM t; -- trailing\n/* block */\n";
+    assert_eq!(count("sql", text), 1);
+}
+
+#[test]
+fn lua_block_comments_win_over_line_comments() {
+    let text = "--[[\nblock\n]]\n-- line\nlocal x = 1\n";
+    assert_eq!(count("lua", text), 1);
+}
+
+#[test]
+fn haskell_block_comments() {
+    let text = "{- header\n-}\n-- line\nmain = putStrLn \"-- hi\"\n";
+    assert_eq!(count("hs", text), 1);
+}
+
+#[test]
+fn css_and_scss_comments() {
+    assert_eq!(count("css", "/* a */\nbody { color: red; }\n"), 1);
+    assert_eq!(count("scss", "// a\n$x: 1;\n"), 1);
+}
+
+#[test]
+fn markup_comments() {
+    let text = "<!--\n  note\n-->\n<p>hi</p>\n";
+    assert_eq!(count("html", text), 1);
+}
+
+#[test]
+fn blank_lines_are_not_counted() {
+    assert_eq!(count("rs", "\n   \nfn a() {}\n\n"), 1);
+    assert_eq!(count("rs", ""), 0);
+}
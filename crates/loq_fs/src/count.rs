@@ -1,15 +1,21 @@
 //! Line counting with binary detection.
 //!
 //! Efficiently counts lines in files using buffered reads and SIMD-accelerated
-//! newline detection. Detects binary files by checking for null bytes.
+//! newline detection, tallying non-blank lines in the same pass. Detects
+//! binary files by checking for null bytes.
 
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 
+use loq_core::{LongLines, TokenEstimate};
 use memchr::{memchr, memchr_iter};
 use thiserror::Error;
 
+use crate::comments::{count_source_lines, CommentSyntax};
+use crate::line_length::{LineLength, LineLengths};
+use crate::tokenizer::Tokenizer;
+
 /// Buffer size for reading files (8 KiB for fewer syscalls).
 const BUF_SIZE: usize = 8192;
 
@@ -22,8 +28,19 @@ pub enum FileInspection {
     Text {
         /// Number of lines (wc -l style: newline-terminated).
         lines: usize,
+        /// Number of lines with at least one non-whitespace character.
+        code_lines: usize,
+        /// Number of lines with code outside comments, or `code_lines` when
+        /// no comment syntax was given.
+        source_lines: usize,
+        /// Token count, exact or estimated as asked.
+        tokens: usize,
         /// Number of bytes read from the file.
         bytes: usize,
+        /// Length of the longest line.
+        line_length: usize,
+        /// Lines longer than the asked maximum.
+        long_lines: LongLines,
     },
 }
 
@@ -38,11 +55,59 @@ pub enum CountError {
     Unreadable(#[from] std::io::Error),
 }
 
+/// How [`inspect_file`] counts tokens.
+#[derive(Debug, Clone, Copy)]
+pub enum Tokens<'a> {
+    /// Estimated with a heuristic.
+    Estimate(TokenEstimate),
+    /// Counted exactly with a tokenizer.
+    Exact(&'a Tokenizer),
+}
+
+impl Default for Tokens<'_> {
+    fn default() -> Self {
+        Self::Estimate(TokenEstimate::DEFAULT)
+    }
+}
+
+impl Tokens<'_> {
+    /// Returns true when counting needs the file's text, not just its size.
+    const fn needs_text(self) -> bool {
+        !matches!(self, Self::Estimate(TokenEstimate::BytesPerToken(_)))
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn count(self, bytes: usize, text: &[u8]) -> usize {
+        let estimate = match self {
+            Self::Exact(tokenizer) => return tokenizer.count(text),
+            Self::Estimate(TokenEstimate::BytesPerToken(factor)) => bytes as f64 / factor,
+            Self::Estimate(TokenEstimate::CharsPerToken(factor)) => {
+                String::from_utf8_lossy(text).chars().count() as f64 / factor
+            }
+            Self::Estimate(TokenEstimate::TokensPerWord(factor)) => {
+                String::from_utf8_lossy(text).split_whitespace().count() as f64 * factor
+            }
+        };
+        estimate.ceil() as usize
+    }
+}
+
 /// Inspects a file to determine if it's binary or count its lines.
 ///
 /// Uses buffered reading for efficiency and checks for null bytes
-/// in the first chunk to detect binary files.
-pub fn inspect_file(path: &Path) -> Result<FileInspection, CountError> {
+/// in the first chunk to detect binary files. With a comment syntax, or
+/// tokens counted from text, the contents are kept to count those as well.
+/// Line lengths are measured in the same pass.
+pub fn inspect_file(
+    path: &Path,
+    comments: Option<&CommentSyntax>,
+    tokens: Tokens<'_>,
+    line_length: LineLength,
+) -> Result<FileInspection, CountError> {
     let mut file = File::open(path).map_err(|err| match err.kind() {
         std::io::ErrorKind::NotFound => CountError::Missing,
         _ => CountError::Unreadable(err),
@@ -51,7 +116,15 @@ pub fn inspect_file(path: &Path) -> Result<FileInspection, CountError> {
     let mut buf = [0u8; BUF_SIZE];
     let mut read = file.read(&mut buf).map_err(CountError::Unreadable)?;
     if read == 0 {
-        return Ok(FileInspection::Text { lines: 0, bytes: 0 });
+        return Ok(FileInspection::Text {
+            lines: 0,
+            code_lines: 0,
+            source_lines: 0,
+            tokens: 0,
+            bytes: 0,
+            line_length: 0,
+            long_lines: LongLines::default(),
+        });
     }
 
     if memchr(0, &buf[..read]).is_some() {
@@ -59,7 +132,12 @@ pub fn inspect_file(path: &Path) -> Result<FileInspection, CountError> {
     }
 
     let mut bytes = read;
-    let mut newlines = memchr_iter(b'\n', &buf[..read]).count();
+    let mut counter = LineCounter {
+        source: (comments.is_some() || tokens.needs_text()).then(Vec::new),
+        lengths: LineLengths::new(line_length),
+        ..LineCounter::default()
+    };
+    counter.feed(&buf[..read]);
     let mut last_byte = buf[read - 1];
 
     loop {
@@ -68,185 +146,72 @@ pub fn inspect_file(path: &Path) -> Result<FileInspection, CountError> {
             break;
         }
         bytes += read;
-        newlines += memchr_iter(b'\n', &buf[..read]).count();
+        counter.feed(&buf[..read]);
         last_byte = buf[read - 1];
     }
 
-    let mut lines = newlines;
+    let mut lines = counter.newlines;
+    let mut code_lines = counter.code_lines;
     if last_byte != b'\n' {
         lines += 1;
+        code_lines += usize::from(counter.line_has_code);
     }
-
-    Ok(FileInspection::Text { lines, bytes })
+    let source = counter.source.as_deref().unwrap_or_default();
+    let source_lines = comments.map_or(code_lines, |syntax| count_source_lines(source, syntax));
+    let tokens = tokens.count(bytes, source);
+    let mut lengths = counter.lengths;
+    if last_byte != b'\n' {
+        lengths.end_line();
+    }
+
+    Ok(FileInspection::Text {
+        lines,
+        code_lines,
+        source_lines,
+        tokens,
+        bytes,
+        line_length: lengths.longest,
+        long_lines: lengths.long_lines,
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::NamedTempFile;
-
-    fn write_temp(contents: &[u8]) -> NamedTempFile {
-        let mut file = NamedTempFile::new().unwrap();
-        file.write_all(contents).unwrap();
-        file
-    }
-
-    use std::io::Write;
-
-    fn text_lines(result: FileInspection) -> usize {
-        match result {
-            FileInspection::Text { lines, .. } => lines,
-            FileInspection::Binary => panic!("expected text file"),
-        }
-    }
-
-    #[test]
-    fn count_empty_file() {
-        let file = write_temp(b"");
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(text_lines(result), 0);
-    }
-
-    #[test]
-    fn count_trailing_newline() {
-        let file = write_temp(b"a\n");
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(text_lines(result), 1);
-    }
-
-    #[test]
-    fn count_no_trailing_newline() {
-        let file = write_temp(b"a");
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(text_lines(result), 1);
-    }
-
-    #[test]
-    fn count_tracks_bytes() {
-        let file = write_temp(b"abcd");
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(result, FileInspection::Text { lines: 1, bytes: 4 });
-    }
-
-    #[test]
-    #[should_panic(expected = "expected text file")]
-    fn text_lines_panics_for_binary() {
-        text_lines(FileInspection::Binary);
-    }
-
-    #[test]
-    fn count_multiple_lines() {
-        let file = write_temp(b"a\nb\n");
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(text_lines(result), 2);
-    }
-
-    #[test]
-    fn count_multiple_lines_no_trailing_newline() {
-        let file = write_temp(b"a\nb");
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(text_lines(result), 2);
-    }
-
-    #[test]
-    fn binary_detection_first_chunk() {
-        let file = write_temp(b"\0binary");
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(result, FileInspection::Binary);
-    }
-
-    #[test]
-    fn missing_file_returns_missing() {
-        let path = std::path::Path::new("does-not-exist.txt");
-        let err = inspect_file(path).unwrap_err();
-        assert!(matches!(err, CountError::Missing));
-    }
-
-    #[test]
-    fn unreadable_path_returns_unreadable() {
-        let dir = tempfile::TempDir::new().unwrap();
-        let err = inspect_file(dir.path()).unwrap_err();
-        assert!(matches!(err, CountError::Unreadable(_)));
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn unreadable_file_open_returns_unreadable() {
-        use std::os::unix::fs::PermissionsExt;
-
-        let file = write_temp(b"secret");
-        let original_permissions = std::fs::metadata(file.path()).unwrap().permissions();
-        let mut unreadable_permissions = original_permissions.clone();
-        unreadable_permissions.set_mode(0o000);
-        std::fs::set_permissions(file.path(), unreadable_permissions).unwrap();
-
-        let err = inspect_file(file.path()).unwrap_err();
-
-        std::fs::set_permissions(file.path(), original_permissions).unwrap();
-        assert!(matches!(err, CountError::Unreadable(_)));
-    }
+/// Running line tallies across buffer boundaries.
+#[derive(Default)]
+struct LineCounter {
+    newlines: usize,
+    code_lines: usize,
+    /// Whether the unterminated line so far has a non-whitespace byte.
+    line_has_code: bool,
+    /// The contents read so far, kept only for comment-aware counting and
+    /// tokens counted from text.
+    source: Option<Vec<u8>>,
+    lengths: LineLengths,
+}
 
-    #[test]
-    fn count_large_file_multiple_chunks() {
-        // Create file larger than BUF_SIZE to test multi-chunk reading
-        let mut content = Vec::new();
-        for i in 0..1000 {
-            content.extend_from_slice(format!("line number {i}\n").as_bytes());
+impl LineCounter {
+    fn feed(&mut self, chunk: &[u8]) {
+        if let Some(source) = &mut self.source {
+            source.extend_from_slice(chunk);
         }
-        let file = write_temp(&content);
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(text_lines(result), 1000);
-    }
-
-    #[test]
-    fn count_large_file_no_trailing_newline() {
-        // Test multi-chunk reading where last byte isn't newline
-        let mut content = Vec::new();
-        for i in 0..999 {
-            content.extend_from_slice(format!("line number {i}\n").as_bytes());
+        let mut start = 0;
+        for end in memchr_iter(b'\n', chunk) {
+            if self.line_has_code || has_code(&chunk[start..end]) {
+                self.code_lines += 1;
+            }
+            self.lengths.extend(&chunk[start..end]);
+            self.lengths.end_line();
+            self.newlines += 1;
+            self.line_has_code = false;
+            start = end + 1;
         }
-        content.extend_from_slice(b"final line without newline");
-        let file = write_temp(&content);
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(text_lines(result), 1000);
-    }
-
-    #[test]
-    fn binary_detection_only_checks_first_chunk() {
-        // Current behavior: null bytes are only detected in the first chunk (BUF_SIZE).
-        // A file with null bytes AFTER the first chunk is treated as text.
-        // This is a deliberate performance trade-off.
-        let mut content = vec![b'a'; super::BUF_SIZE]; // Fill first chunk with 'a'
-        content.push(0); // Null byte in second chunk
-        content.push(b'\n');
-        let file = write_temp(&content);
-        let result = inspect_file(file.path()).unwrap();
-        // This returns Text, not Binary - the null byte in chunk 2 is not detected
-        assert_eq!(text_lines(result), 1);
-    }
-
-    #[test]
-    fn crlf_line_endings_counted_by_lf() {
-        // Windows-style CRLF (\r\n) - we count \n only, so this is 3 lines
-        let file = write_temp(b"line1\r\nline2\r\nline3\r\n");
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(text_lines(result), 3);
-    }
-
-    #[test]
-    fn mixed_line_endings() {
-        // Mix of \n and \r\n - we only count \n
-        let file = write_temp(b"unix\nwindows\r\nmore unix\n");
-        let result = inspect_file(file.path()).unwrap();
-        assert_eq!(text_lines(result), 3);
+        self.line_has_code = self.line_has_code || has_code(&chunk[start..]);
+        self.lengths.extend(&chunk[start..]);
     }
+}
 
-    #[test]
-    fn cr_only_not_counted_as_line() {
-        // Old Mac style \r only - NOT counted as line endings
-        let file = write_temp(b"line1\rline2\rline3\r");
-        let result = inspect_file(file.path()).unwrap();
-        // No \n chars, but file doesn't end in \n, so we count 1 line
-        assert_eq!(text_lines(result), 1);
-    }
+fn has_code(line: &[u8]) -> bool {
+    line.iter().any(|byte| !byte.is_ascii_whitespace())
 }
+
+#[cfg(test)]
+mod tests;
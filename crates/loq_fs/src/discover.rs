@@ -1,21 +1,26 @@
 //! Configuration file discovery.
 //!
 //! Finds `loq.toml` by walking up the directory tree from a starting point.
+//! A `pyproject.toml` with `[tool.loq]` or a `Cargo.toml` with loq metadata
+//! also counts, with `loq.toml` taking precedence in the same directory.
 
 use std::path::{Path, PathBuf};
 
+use loq_core::{contains_config, CONFIG_FILE_NAMES};
+use rustc_hash::FxHashMap;
+
 /// Finds a config file in or above the given directory.
 ///
-/// Searches upward from `start_dir` looking for `loq.toml`.
+/// Searches upward from `start_dir` looking for `loq.toml`, or a host
+/// manifest carrying a loq table.
 /// Returns the path to the config file if found, or `None` if not found.
 #[must_use]
 pub fn find_config(start_dir: &Path) -> Option<PathBuf> {
     let mut current = Some(start_dir);
 
     while let Some(dir) = current {
-        let candidate = dir.join("loq.toml");
-        if candidate.is_file() {
-            return Some(candidate);
+        if let Some(found) = config_in_dir(dir) {
+            return Some(found);
         }
         current = dir.parent();
     }
@@ -23,6 +28,63 @@ pub fn find_config(start_dir: &Path) -> Option<PathBuf> {
     None
 }
 
+/// Returns the config file directly inside `dir`, honoring precedence.
+pub(crate) fn config_in_dir(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES.iter().find_map(|name| {
+        let candidate = dir.join(name);
+        if !candidate.is_file() {
+            return None;
+        }
+        if *name == "loq.toml" {
+            return Some(candidate);
+        }
+        let text = std::fs::read_to_string(&candidate).ok()?;
+        contains_config(&candidate, &text).then_some(candidate)
+    })
+}
+
+/// Memoized nearest-config lookup for many files.
+///
+/// Each directory visited while searching upward is cached, so resolving
+/// thousands of files in the same tree only touches each directory once.
+#[derive(Debug, Default)]
+pub struct ConfigLocator {
+    nearest: FxHashMap<PathBuf, Option<PathBuf>>,
+}
+
+impl ConfigLocator {
+    /// Creates an empty locator.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the nearest config file in or above `dir`.
+    pub fn find(&mut self, dir: &Path) -> Option<PathBuf> {
+        let mut visited = Vec::new();
+        let mut current = Some(dir);
+        let mut found = None;
+
+        while let Some(dir) = current {
+            if let Some(cached) = self.nearest.get(dir) {
+                found.clone_from(cached);
+                break;
+            }
+            visited.push(dir.to_path_buf());
+            if let Some(candidate) = config_in_dir(dir) {
+                found = Some(candidate);
+                break;
+            }
+            current = dir.parent();
+        }
+
+        for dir in visited {
+            self.nearest.insert(dir, found.clone());
+        }
+        found
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,4 +132,82 @@ mod tests {
         // Should NOT find the directory, returns None
         assert!(found.is_none());
     }
+
+    #[test]
+    fn finds_pyproject_with_loq_table() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::write(
+            root.join("pyproject.toml"),
+            "[tool.loq]\ndefault_max_lines = 10\n",
+        )
+        .unwrap();
+
+        let found = find_config(root);
+        assert_eq!(found.unwrap(), root.join("pyproject.toml"));
+    }
+
+    #[test]
+    fn host_manifest_without_loq_table_is_skipped() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let pkg = root.join("pkg");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::write(root.join("loq.toml"), "default_max_lines = 10").unwrap();
+        std::fs::write(pkg.join("pyproject.toml"), "[project]\nname = \"pkg\"\n").unwrap();
+        std::fs::write(pkg.join("Cargo.toml"), "[package]\nname = \"pkg\"\n").unwrap();
+
+        let found = find_config(&pkg);
+        assert_eq!(found.unwrap(), root.join("loq.toml"));
+    }
+
+    #[test]
+    fn precedence_within_one_directory() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace.metadata.loq]\ndefault_max_lines = 1\n",
+        )
+        .unwrap();
+        assert_eq!(find_config(root).unwrap(), root.join("Cargo.toml"));
+
+        std::fs::write(root.join("pyproject.toml"), "[tool.loq]\n").unwrap();
+        assert_eq!(find_config(root).unwrap(), root.join("pyproject.toml"));
+
+        std::fs::write(root.join("loq.toml"), "").unwrap();
+        assert_eq!(find_config(root).unwrap(), root.join("loq.toml"));
+    }
+
+    #[test]
+    fn locator_finds_nearest_config_per_directory() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let pkg = root.join("pkg/src");
+        let other = root.join("other");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::write(root.join("loq.toml"), "default_max_lines = 10").unwrap();
+        std::fs::write(root.join("pkg/loq.toml"), "default_max_lines = 20").unwrap();
+
+        let mut locator = ConfigLocator::new();
+
+        assert_eq!(locator.find(&pkg), Some(root.join("pkg/loq.toml")));
+        assert_eq!(locator.find(&other), Some(root.join("loq.toml")));
+        // Cached lookups return the same answers.
+        assert_eq!(locator.find(&pkg), Some(root.join("pkg/loq.toml")));
+        assert_eq!(locator.find(root), Some(root.join("loq.toml")));
+    }
+
+    #[test]
+    fn locator_caches_missing_configs() {
+        let temp = TempDir::new().unwrap();
+        let sub = temp.path().join("a/b");
+        std::fs::create_dir_all(&sub).unwrap();
+
+        let mut locator = ConfigLocator::new();
+
+        assert!(locator.find(&sub).is_none());
+        assert!(locator.find(&temp.path().join("a")).is_none());
+    }
 }
@@ -0,0 +1,173 @@
+//! Decision traces for a single path.
+//!
+//! Replays each step a check takes for one file (config selection,
+//! exclusion, rule matching and measurement) and records why each step
+//! turned out the way it did.
+
+use std::path::{Path, PathBuf};
+
+use loq_core::config::{CompiledConfig, ConfigLayer};
+use loq_core::decide::{decide, Decision};
+use loq_core::report::OutcomeKind;
+use loq_core::{Limit, Severity};
+
+use crate::cache::Cache;
+use crate::inspection::Inspector;
+use crate::walk::{gitignore_match, hardcoded_exclude, GitignoreMatch};
+use crate::{
+    check_file, default_config, discover, governing_config, load_config_from_path,
+    load_config_with_root, load_tokenizer, CheckConfig, FsError, PathIdentity,
+};
+
+/// Everything that decided how one path is checked.
+#[derive(Debug)]
+pub struct Explanation {
+    /// Config file governing the path, or `None` for built-in defaults.
+    pub config_path: Option<PathBuf>,
+    /// Display path and match key of the path.
+    pub identity: PathIdentity,
+    /// Why the path is skipped, if it is.
+    pub exclusion: Option<Exclusion>,
+    /// Every rule matching the path, in config order. The last one wins.
+    pub matched_rules: Vec<RuleMatch>,
+    /// The limit chosen for the path.
+    pub decision: Decision,
+    /// The measurement against that limit, or `None` if the path is excluded.
+    pub outcome: Option<OutcomeKind>,
+}
+
+/// Why a path is left out of a check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Exclusion {
+    /// A file or directory loq always skips, e.g. `.loq_cache`.
+    Hardcoded {
+        /// The skipped name.
+        name: &'static str,
+    },
+    /// An `exclude` pattern.
+    Pattern {
+        /// The matching pattern.
+        pattern: String,
+        /// Config file declaring the pattern, or `None` for built-in defaults.
+        config_path: Option<PathBuf>,
+    },
+    /// An `include` list that no pattern in matches the path.
+    NotIncluded {
+        /// The include patterns.
+        patterns: Vec<String>,
+        /// Config file declaring the patterns, or `None` for built-in defaults.
+        config_path: Option<PathBuf>,
+    },
+    /// A `.gitignore` pattern. Only directory walks honor it; naming the file
+    /// explicitly still checks it.
+    Gitignore(GitignoreMatch),
+}
+
+/// A rule whose patterns match the path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMatch {
+    /// Zero-based position of the rule in the config.
+    pub index: usize,
+    /// The rule's first pattern that matched.
+    pub pattern: String,
+    /// The rule's limits.
+    pub limits: Vec<Limit>,
+    /// The rule's severity.
+    pub severity: Severity,
+}
+
+/// Explains how `path` would be checked under `config`.
+///
+/// With [`CheckConfig::Discover`], the path's nearest config decides
+/// everything, exclusion included, as in [`crate::run_check`].
+pub fn explain(path: &Path, config: CheckConfig, cwd: &Path) -> Result<Explanation, FsError> {
+    let cwd_abs = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    let path = &cwd_abs.join(path);
+    let overrides = ConfigLayer::default();
+    let governing = match config {
+        CheckConfig::File(config_path) => load_config_from_path(&config_path, cwd, &overrides)?,
+        CheckConfig::Explicit(config_path) => {
+            load_config_with_root(&config_path, cwd_abs.clone(), &overrides)?
+        }
+        CheckConfig::Compiled(compiled) => *compiled,
+        CheckConfig::Discover => {
+            let mut locator = discover::ConfigLocator::new();
+            match governing_config(path, &cwd_abs, &mut locator) {
+                Some(config_path) => load_config_from_path(&config_path, &cwd_abs, &overrides)?,
+                None => default_config(&cwd_abs, &overrides)?,
+            }
+        }
+    };
+
+    let identity = PathIdentity::new(path, &cwd_abs, &governing.root_dir);
+    let exclusion = find_exclusion(path, &cwd_abs, &governing);
+    let matched_rules = governing
+        .rules()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, rule)| {
+            rule.matches(&identity.match_key).map(|pattern| RuleMatch {
+                index,
+                pattern: pattern.to_string(),
+                limits: rule.limits.clone(),
+                severity: rule.severity,
+            })
+        })
+        .collect();
+    let decision = decide(&governing, &identity.match_key);
+    let outcome = if exclusion.is_none() {
+        let inspector =
+            Inspector::new(Cache::empty()).with_tokenizer(load_tokenizer(&governing, &cwd_abs)?);
+        Some(check_file(path, &governing, &cwd_abs, &inspector).kind)
+    } else {
+        None
+    };
+
+    Ok(Explanation {
+        config_path: governing.source_path,
+        identity,
+        exclusion,
+        matched_rules,
+        decision,
+        outcome,
+    })
+}
+
+/// Applies the governing config's includes, excludes and gitignore setting.
+fn find_exclusion(path: &Path, cwd_abs: &Path, governing: &CompiledConfig) -> Option<Exclusion> {
+    let display = PathIdentity::new(path, cwd_abs, cwd_abs).display;
+    if let Some(name) = hardcoded_exclude(Path::new(&display)) {
+        return Some(Exclusion::Hardcoded { name });
+    }
+    excluded_by(path, cwd_abs, governing).or_else(|| {
+        governing
+            .respect_gitignore
+            .then(|| {
+                let absolute = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+                gitignore_match(&absolute)
+            })
+            .flatten()
+            .map(Exclusion::Gitignore)
+    })
+}
+
+fn excluded_by(path: &Path, cwd_abs: &Path, config: &CompiledConfig) -> Option<Exclusion> {
+    let identity = PathIdentity::new(path, cwd_abs, &config.root_dir);
+    if let Some(pattern) = config.exclude_patterns().matches(&identity.match_key) {
+        return Some(Exclusion::Pattern {
+            pattern: pattern.to_string(),
+            config_path: config.source_path.clone(),
+        });
+    }
+    (!config.includes(&identity.match_key)).then(|| Exclusion::NotIncluded {
+        patterns: config
+            .include_patterns()
+            .patterns()
+            .map(ToString::to_string)
+            .collect(),
+        config_path: config.source_path.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,145 @@
+use super::*;
+use loq_core::MatchBy;
+use tempfile::TempDir;
+
+fn write_file(dir: &TempDir, path: &str, contents: &str) {
+    let full = dir.path().join(path);
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(full, contents).unwrap();
+}
+
+fn explain_in(dir: &TempDir, path: &str) -> Explanation {
+    explain(Path::new(path), CheckConfig::Discover, dir.path()).unwrap()
+}
+
+#[test]
+fn lists_every_matching_rule_and_the_winner() {
+    let temp = TempDir::new().unwrap();
+    write_file(
+        &temp,
+        "loq.toml",
+        "default_max_lines = 500\n\n[[rules]]\npath = \"**/*.rs\"\nmax_lines = 800\n\n[[rules]]\npath = \"docs/**\"\nmax_lines = 5\n\n[[rules]]\npath = \"src/app.rs\"\nmax_lines = 2\n",
+    );
+    write_file(&temp, "src/app.rs", "a\nb\nc\n");
+
+    let explanation = explain_in(&temp, "src/app.rs");
+
+    let root = temp.path().canonicalize().unwrap();
+    assert_eq!(explanation.config_path, Some(root.join("loq.toml")));
+    assert_eq!(explanation.identity.match_key, "src/app.rs");
+    assert!(explanation.exclusion.is_none());
+    let indexes = explanation
+        .matched_rules
+        .iter()
+        .map(|rule| rule.index)
+        .collect::<Vec<_>>();
+    assert_eq!(indexes, vec![0, 2]);
+    assert_eq!(
+        explanation.decision,
+        Decision::Check {
+            limits: vec![Limit::lines(2)],
+            matched_by: MatchBy::Rule {
+                pattern: "src/app.rs".to_string()
+            },
+        }
+    );
+    match explanation.outcome {
+        Some(OutcomeKind::Violation { measurements, .. }) => {
+            assert_eq!(measurements[0].actual, 3);
+        }
+        other => panic!("expected Violation, got {other:?}"),
+    }
+}
+
+#[test]
+fn reports_the_exclude_pattern() {
+    let temp = TempDir::new().unwrap();
+    write_file(&temp, "loq.toml", "exclude = [\"gen/**\"]\n");
+    write_file(&temp, "gen/out.rs", "a\n");
+
+    let explanation = explain_in(&temp, "gen/out.rs");
+
+    assert!(matches!(
+        explanation.exclusion,
+        Some(Exclusion::Pattern { ref pattern, .. }) if pattern == "gen/**"
+    ));
+    assert!(explanation.outcome.is_none());
+}
+
+#[test]
+fn reports_the_gitignore_pattern() {
+    let temp = TempDir::new().unwrap();
+    write_file(&temp, ".gitignore", "# build output\ntarget/\n");
+    write_file(&temp, "target/debug/out.txt", "a\n");
+
+    let explanation = explain_in(&temp, "target/debug/out.txt");
+
+    match explanation.exclusion {
+        Some(Exclusion::Gitignore(found)) => {
+            assert_eq!(found.pattern, "target/");
+            let root = temp.path().canonicalize().unwrap();
+            assert_eq!(found.source, Some(root.join(".gitignore")));
+        }
+        other => panic!("expected gitignore exclusion, got {other:?}"),
+    }
+}
+
+#[test]
+fn reports_hardcoded_excludes() {
+    let temp = TempDir::new().unwrap();
+    write_file(&temp, ".loq_cache/data", "a\n");
+
+    let explanation = explain_in(&temp, ".loq_cache/data");
+
+    assert_eq!(
+        explanation.exclusion,
+        Some(Exclusion::Hardcoded { name: ".loq_cache" })
+    );
+}
+
+#[test]
+fn nested_config_governs_its_own_exclusions() {
+    let temp = TempDir::new().unwrap();
+    write_file(&temp, "loq.toml", "exclude = [\"pkg/*.txt\"]\n");
+    write_file(
+        &temp,
+        "pkg/loq.toml",
+        "default_max_lines = 1\nexclude = [\"vendor/**\"]\n",
+    );
+    write_file(&temp, "pkg/lib.txt", "a\n");
+    write_file(&temp, "pkg/vendor/dep.txt", "a\n");
+
+    let governed = explain_in(&temp, "pkg/lib.txt");
+    let root = temp.path().canonicalize().unwrap();
+    assert_eq!(governed.config_path, Some(root.join("pkg/loq.toml")));
+    assert_eq!(governed.identity.display, "pkg/lib.txt");
+    assert_eq!(governed.identity.match_key, "lib.txt");
+    assert!(governed.exclusion.is_none());
+
+    let vendored = explain_in(&temp, "pkg/vendor/dep.txt");
+    assert!(matches!(
+        vendored.exclusion,
+        Some(Exclusion::Pattern { config_path, .. }) if config_path == Some(root.join("pkg/loq.toml"))
+    ));
+}
+
+#[test]
+fn reports_paths_outside_every_include() {
+    let temp = TempDir::new().unwrap();
+    write_file(&temp, "loq.toml", "include = [\"src/**\"]\n");
+    write_file(&temp, "build.rs", "a\n");
+
+    let explanation = explain_in(&temp, "build.rs");
+
+    let root = temp.path().canonicalize().unwrap();
+    assert_eq!(
+        explanation.exclusion,
+        Some(Exclusion::NotIncluded {
+            patterns: vec!["src/**".to_string()],
+            config_path: Some(root.join("loq.toml")),
+        })
+    );
+    assert!(explanation.outcome.is_none());
+}
@@ -0,0 +1,185 @@
+//! Import resolution for agent instruction files.
+//!
+//! Files like `CLAUDE.md` and `AGENTS.md` pull other files into context with
+//! `@path/to/file.md` references and Markdown links. Both resolve relative to
+//! the file that contains them and are followed transitively.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// A file reached from a checked file through its imports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ImportedFile {
+    /// Path as resolved from the importing file.
+    pub(crate) path: PathBuf,
+    /// Levels below the checked file; its direct imports are at depth 1.
+    pub(crate) depth: usize,
+}
+
+/// Returns every file `entry` imports, transitively, in depth-first order.
+///
+/// Each file appears once, under the first file to import it, which also
+/// stops cycles. References to files that don't exist are ignored.
+pub(crate) fn resolve_imports(entry: &Path) -> Vec<ImportedFile> {
+    let mut seen = HashSet::new();
+    seen.insert(canonical(entry));
+    let mut imports = Vec::new();
+    visit(entry, 1, &mut seen, &mut imports);
+    imports
+}
+
+fn visit(path: &Path, depth: usize, seen: &mut HashSet<PathBuf>, imports: &mut Vec<ImportedFile>) {
+    let Ok(bytes) = std::fs::read(path) else {
+        return;
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    for reference in references(&text) {
+        let target = base.join(reference);
+        if !target.is_file() || !seen.insert(canonical(&target)) {
+            continue;
+        }
+        imports.push(ImportedFile {
+            path: target.clone(),
+            depth,
+        });
+        visit(&target, depth + 1, seen, imports);
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Returns the local paths referenced by `@path` imports and Markdown links,
+/// in the order they appear. Code blocks and code spans are skipped.
+fn references(text: &str) -> Vec<&str> {
+    let mut references = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = Some(marker);
+            continue;
+        }
+        // Odd segments between backticks are code spans.
+        for prose in line.split('`').step_by(2) {
+            at_references(prose, &mut references);
+            link_references(prose, &mut references);
+        }
+    }
+    references
+}
+
+/// Collects `@path` references that start a word, e.g. `see @docs/style.md`.
+fn at_references<'a>(text: &'a str, references: &mut Vec<&'a str>) {
+    for word in text.split_whitespace() {
+        let Some(path) = word.strip_prefix('@') else {
+            continue;
+        };
+        let path = path.trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+        if !path.is_empty() && !path.starts_with('~') {
+            references.push(path);
+        }
+    }
+}
+
+/// Collects the local targets of `[text](target)` links.
+fn link_references<'a>(text: &'a str, references: &mut Vec<&'a str>) {
+    let mut rest = text;
+    while let Some(start) = rest.find("](") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        let target = rest[..end].split_whitespace().next().unwrap_or_default();
+        let target = target.trim_start_matches('<').trim_end_matches('>');
+        let target = target.split(['#', '?']).next().unwrap_or_default();
+        if !target.is_empty() && !target.contains(':') {
+            references.push(target);
+        }
+        rest = &rest[end..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn resolved(root: &Path, entry: &str) -> Vec<(String, usize)> {
+        resolve_imports(&root.join(entry))
+            .into_iter()
+            .map(|import| {
+                let path = import.path.strip_prefix(root).unwrap();
+                (path.to_string_lossy().replace('\\', "/"), import.depth)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn references_include_at_imports_and_links() {
+        let text = "See @docs/style.md, and [the guide](guide.md#setup).\n\
+                    Mail me@example.com or visit [site](https://example.com).\n\
+                    Skip `@code.md` and [anchors](#top).\n\
+                    ```\n@fenced.md\n```\n\
+                    Then @./local.md";
+
+        assert_eq!(
+            references(text),
+            ["docs/style.md", "guide.md", "./local.md"]
+        );
+    }
+
+    #[test]
+    fn imports_are_followed_transitively_from_each_file() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "CLAUDE.md", "@docs/a.md\n[b](docs/b.md)\n");
+        write(temp.path(), "docs/a.md", "@nested/c.md\n");
+        write(temp.path(), "docs/nested/c.md", "leaf\n");
+        write(temp.path(), "docs/b.md", "leaf\n");
+
+        assert_eq!(
+            resolved(temp.path(), "CLAUDE.md"),
+            [
+                ("docs/a.md".to_string(), 1),
+                ("docs/nested/c.md".to_string(), 2),
+                ("docs/b.md".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn cycles_and_repeats_are_visited_once() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "AGENTS.md", "@a.md @b.md\n");
+        write(temp.path(), "a.md", "@AGENTS.md @b.md\n");
+        write(temp.path(), "b.md", "@a.md\n");
+
+        assert_eq!(
+            resolved(temp.path(), "AGENTS.md"),
+            [("a.md".to_string(), 1), ("b.md".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn missing_targets_are_ignored() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "CLAUDE.md", "@gone.md [dir](docs)\n");
+        std::fs::create_dir(temp.path().join("docs")).unwrap();
+
+        assert!(resolved(temp.path(), "CLAUDE.md").is_empty());
+    }
+}
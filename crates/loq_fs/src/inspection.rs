@@ -3,17 +3,56 @@
 //! Owns file metadata lookup, cache policy, line inspection, and conversion to
 //! check outcomes.
 
+use std::hash::{Hash, Hasher};
 use std::path::Path;
 use std::sync::Mutex;
 
-use loq_core::{Limit, MatchBy, Metric, OutcomeKind};
+use loq_core::{
+    Import, Limit, LongLines, MatchBy, Measurement, Metric, OutcomeKind, Severity, TokenCounter,
+};
 
 use crate::cache::{Cache, CachedResult};
-use crate::count::{self, FileInspection};
+use crate::comments;
+use crate::count::{self, FileInspection, Tokens};
+use crate::imports::ImportedFile;
+use crate::line_length::LineLength;
+use crate::tokenizer::Tokenizer;
+use crate::PathIdentity;
+
+/// A text file's values for a set of limits.
+struct Measured {
+    /// One value per limit, in the limits' order.
+    actuals: Vec<usize>,
+    /// Lines over a line length limit.
+    long_lines: LongLines,
+    /// Total lines.
+    lines: usize,
+}
+
+/// What inspecting a file found.
+pub(crate) struct Inspection {
+    /// The file's check outcome.
+    pub(crate) kind: OutcomeKind,
+    /// Total lines, when the file is text.
+    pub(crate) lines: Option<usize>,
+    /// The file's own tokens for its bundles, when asked for and text.
+    pub(crate) bundle_tokens: Option<usize>,
+}
+
+impl Inspection {
+    pub(crate) const fn unmeasured(kind: OutcomeKind) -> Self {
+        Self {
+            kind,
+            lines: None,
+            bundle_tokens: None,
+        }
+    }
+}
 
 /// Inspects files with a shared cache.
 pub(crate) struct Inspector {
     cache: Mutex<Cache>,
+    tokenizer: Option<Tokenizer>,
 }
 
 impl Inspector {
@@ -21,37 +60,223 @@ impl Inspector {
     pub(crate) const fn new(cache: Cache) -> Self {
         Self {
             cache: Mutex::new(cache),
+            tokenizer: None,
         }
     }
 
-    /// Inspects a file and returns its check outcome for the given limit.
+    /// Counts tokens with `tokenizer` for limits that ask for it.
+    pub(crate) fn with_tokenizer(self, tokenizer: Option<Tokenizer>) -> Self {
+        Self { tokenizer, ..self }
+    }
+
+    /// Inspects a file and returns its check outcome for the given limits.
+    /// With a `bundle` counter, the file's tokens are counted with it too.
     pub(crate) fn inspect(
         &self,
         path: &Path,
         cache_key: &str,
-        limit: Limit,
+        limits: &[Limit],
+        bundle: Option<TokenCounter>,
         matched_by: MatchBy,
-    ) -> OutcomeKind {
+    ) -> Inspection {
+        let measured = match self.measure_cached(path, cache_key, &with_bundle(limits, bundle)) {
+            Ok(measured) => measured,
+            Err(outcome) => return Inspection::unmeasured(outcome),
+        };
+        let (actuals, bundle_tokens) = measured.actuals.split_at(limits.len());
+        Inspection {
+            kind: outcome_for_measurements(actuals, &measured.long_lines, limits, matched_by),
+            lines: Some(measured.lines),
+            bundle_tokens: bundle_tokens.first().copied(),
+        }
+    }
+
+    /// Inspects a file together with the files it imports, given with their
+    /// identities.
+    ///
+    /// Every budget except line length measures the file and its readable
+    /// text imports combined, and lists each one's own value. Each file's
+    /// measurement is cached on its own; only the import graph is resolved
+    /// afresh, since imports can change while the importing file stays the
+    /// same. Bundle tokens are the file's own, without its imports.
+    pub(crate) fn inspect_with_imports(
+        &self,
+        path: &Path,
+        identity: &PathIdentity,
+        imports: &[(ImportedFile, PathIdentity)],
+        limits: &[Limit],
+        bundle: Option<TokenCounter>,
+        matched_by: MatchBy,
+    ) -> Inspection {
+        let all = with_bundle(limits, bundle);
+        let measured = match self.measure_cached(path, &identity.match_key, &all) {
+            Ok(measured) => measured,
+            Err(outcome) => return Inspection::unmeasured(outcome),
+        };
+        let (own, bundle_tokens) = measured.actuals.split_at(limits.len());
+        let mut totals = own.to_vec();
+        let mut trees = own
+            .iter()
+            .map(|&actual| {
+                vec![Import {
+                    path: identity.display.clone(),
+                    depth: 0,
+                    actual,
+                }]
+            })
+            .collect::<Vec<_>>();
+        for (import, import_identity) in imports {
+            let Ok(import_measured) =
+                self.measure_cached(&import.path, &import_identity.match_key, limits)
+            else {
+                continue;
+            };
+            for (index, limit) in limits.iter().enumerate() {
+                if limit.metric == Metric::LineLength {
+                    continue;
+                }
+                let actual = import_measured.actuals[index];
+                totals[index] += actual;
+                trees[index].push(Import {
+                    path: import_identity.display.clone(),
+                    depth: import.depth,
+                    actual,
+                });
+            }
+        }
+
+        let mut outcome =
+            outcome_for_measurements(&totals, &measured.long_lines, limits, matched_by);
+        if let OutcomeKind::Violation { measurements, .. }
+        | OutcomeKind::Pass { measurements, .. } = &mut outcome
+        {
+            for (measurement, tree) in measurements.iter_mut().zip(trees) {
+                if measurement.limit.metric != Metric::LineLength {
+                    measurement.imports = tree;
+                }
+            }
+        }
+        Inspection {
+            kind: outcome,
+            lines: Some(measured.lines),
+            bundle_tokens: bundle_tokens.first().copied(),
+        }
+    }
+
+    /// Measures a file for `limits` like [`Self::measure`], reusing its cache
+    /// entry when that holds values for the same limits.
+    fn measure_cached(
+        &self,
+        path: &Path,
+        cache_key: &str,
+        limits: &[Limit],
+    ) -> Result<Measured, OutcomeKind> {
         let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let limits_hash = hash_limits(limits);
+        match self.cached(cache_key, mtime) {
+            Some(CachedResult::Text {
+                actuals,
+                long_lines,
+                lines,
+                limits_hash: cached_hash,
+            }) if cached_hash == limits_hash => {
+                return Ok(Measured {
+                    actuals,
+                    long_lines,
+                    lines,
+                })
+            }
+            Some(CachedResult::Binary) => return Err(OutcomeKind::Binary),
+            _ => {}
+        }
 
-        if let Some(outcome) = self.cached_outcome(cache_key, mtime, limit, &matched_by) {
-            return outcome;
+        let measured = self.measure(path, limits);
+        match &measured {
+            Ok(measured) => self.cache_result(
+                cache_key,
+                mtime,
+                CachedResult::Text {
+                    actuals: measured.actuals.clone(),
+                    long_lines: measured.long_lines.clone(),
+                    lines: measured.lines,
+                    limits_hash,
+                },
+            ),
+            Err(OutcomeKind::Binary) => self.cache_result(cache_key, mtime, CachedResult::Binary),
+            Err(_) => {}
         }
+        measured
+    }
 
-        match count::inspect_file(path) {
-            Ok(FileInspection::Binary) => {
-                self.cache_result(cache_key, mtime, CachedResult::Binary);
-                OutcomeKind::Binary
+    /// Measures a file for `limits`, or returns the outcome of a file that
+    /// could not be measured.
+    fn measure(&self, path: &Path, limits: &[Limit]) -> Result<Measured, OutcomeKind> {
+        let comments = limits
+            .iter()
+            .any(|limit| limit.metric == Metric::SourceLines)
+            .then(|| comments::syntax_for(path))
+            .flatten();
+        let mut counters = Vec::new();
+        for limit in limits.iter().filter(|limit| limit.metric == Metric::Tokens) {
+            if !counters.contains(&limit.counter) {
+                counters.push(limit.counter);
             }
-            Ok(FileInspection::Text { lines, bytes }) => {
-                let actual = measurement_for_limit(lines, bytes, limit);
-                self.cache_result(cache_key, mtime, CachedResult::Text(actual));
-                outcome_for_measurement(actual, limit, matched_by)
+        }
+        let tokens = counters
+            .first()
+            .map_or_else(Tokens::default, |counter| self.tokens(*counter));
+        let line_length = limits
+            .iter()
+            .find(|limit| limit.metric == Metric::LineLength)
+            .map_or_else(LineLength::default, |limit| LineLength {
+                unit: limit.line_length_unit,
+                max: limit.max,
+            });
+        let FileInspection::Text {
+            lines,
+            code_lines,
+            source_lines,
+            tokens,
+            bytes,
+            line_length,
+            long_lines,
+        } = inspect_file(path, comments, tokens, line_length)?
+        else {
+            return Err(OutcomeKind::Binary);
+        };
+        // Each further token counter, such as a bundle's, takes its own pass.
+        let mut token_counts = vec![tokens];
+        for counter in counters.iter().skip(1) {
+            match inspect_file(path, None, self.tokens(*counter), LineLength::default())? {
+                FileInspection::Text { tokens, .. } => token_counts.push(tokens),
+                FileInspection::Binary => return Err(OutcomeKind::Binary),
             }
-            Err(count::CountError::Missing) => OutcomeKind::Missing,
-            Err(count::CountError::Unreadable(error)) => OutcomeKind::Unreadable {
-                error: error.to_string(),
-            },
+        }
+        Ok(Measured {
+            actuals: limits
+                .iter()
+                .map(|limit| match limit.metric {
+                    Metric::Lines => lines,
+                    Metric::CodeLines => code_lines,
+                    Metric::SourceLines => source_lines,
+                    Metric::Tokens => counters
+                        .iter()
+                        .position(|counter| *counter == limit.counter)
+                        .map_or(tokens, |index| token_counts[index]),
+                    Metric::Bytes => bytes,
+                    Metric::LineLength => line_length,
+                })
+                .collect(),
+            long_lines,
+            lines,
+        })
+    }
+
+    fn tokens(&self, counter: TokenCounter) -> Tokens<'_> {
+        match (counter, &self.tokenizer) {
+            (TokenCounter::Tokenizer, Some(tokenizer)) => Tokens::Exact(tokenizer),
+            (TokenCounter::Estimate(estimate), _) => Tokens::Estimate(estimate),
+            (TokenCounter::Tokenizer, None) => Tokens::default(),
         }
     }
 
@@ -60,17 +285,13 @@ impl Inspector {
         self.cache.into_inner().ok()
     }
 
-    fn cached_outcome(
+    fn cached(
         &self,
         cache_key: &str,
         mtime: Option<std::time::SystemTime>,
-        limit: Limit,
-        matched_by: &MatchBy,
-    ) -> Option<OutcomeKind> {
-        let mt = mtime?;
+    ) -> Option<CachedResult> {
         let cache = self.cache.lock().ok()?;
-        let result = cache.get(cache_key, mt)?;
-        Some(cached_result_to_outcome(result, limit, matched_by.clone()))
+        cache.get(cache_key, mtime?)
     }
 
     fn cache_result(
@@ -89,156 +310,69 @@ impl Inspector {
     }
 }
 
-fn cached_result_to_outcome(
-    result: CachedResult,
-    limit: Limit,
-    matched_by: MatchBy,
-) -> OutcomeKind {
-    match result {
-        CachedResult::Text(actual) => outcome_for_measurement(actual, limit, matched_by),
-        CachedResult::Binary => OutcomeKind::Binary,
-    }
+/// Inspects a file's contents, or returns the outcome of a file that could
+/// not be read.
+fn inspect_file(
+    path: &Path,
+    comments: Option<&comments::CommentSyntax>,
+    tokens: Tokens<'_>,
+    line_length: LineLength,
+) -> Result<FileInspection, OutcomeKind> {
+    count::inspect_file(path, comments, tokens, line_length).map_err(|error| match error {
+        count::CountError::Missing => OutcomeKind::Missing,
+        count::CountError::Unreadable(error) => OutcomeKind::Unreadable {
+            error: error.to_string(),
+        },
+    })
 }
 
-const fn measurement_for_limit(lines: usize, bytes: usize, limit: Limit) -> usize {
-    match limit.metric {
-        Metric::Lines => lines,
-        // Approximate tokens as four bytes, rounded up.
-        Metric::Tokens => bytes.div_ceil(4),
-    }
+/// Appends a token limit counted with a bundle's counter, so a file's bundle
+/// tokens are measured and cached along with its own limits.
+fn with_bundle(limits: &[Limit], bundle: Option<TokenCounter>) -> Vec<Limit> {
+    let bundle = bundle.map(|counter| Limit::tokens(0).counted_by(counter));
+    limits.iter().copied().chain(bundle).collect()
+}
+
+/// Hashes the limits a file is measured for, so a cache entry is only
+/// reused for the same limits, e.g. not for an import measured with its
+/// importer's.
+fn hash_limits(limits: &[Limit]) -> u64 {
+    let mut hasher = rustc_hash::FxHasher::default();
+    limits.hash(&mut hasher);
+    hasher.finish()
 }
 
-const fn outcome_for_measurement(actual: usize, limit: Limit, matched_by: MatchBy) -> OutcomeKind {
-    if actual > limit.max {
+/// Pairs each limit with its value; `long_lines` go with a line length limit.
+fn outcome_for_measurements(
+    actuals: &[usize],
+    long_lines: &LongLines,
+    limits: &[Limit],
+    matched_by: MatchBy,
+) -> OutcomeKind {
+    let measurements = limits
+        .iter()
+        .zip(actuals)
+        .map(|(limit, actual)| Measurement {
+            long_lines: match limit.metric {
+                Metric::LineLength => long_lines.clone(),
+                _ => LongLines::default(),
+            },
+            ..Measurement::new(*limit, *actual)
+        })
+        .collect::<Vec<_>>();
+    if measurements.iter().any(Measurement::is_over) {
         OutcomeKind::Violation {
-            limit,
-            actual,
+            measurements,
             matched_by,
+            severity: Severity::Error,
         }
     } else {
         OutcomeKind::Pass {
-            limit,
-            actual,
+            measurements,
             matched_by,
         }
     }
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-    use std::panic::AssertUnwindSafe;
-    use std::time::SystemTime;
-    use tempfile::NamedTempFile;
-
-    #[test]
-    fn text_file_outcome_uses_limit() {
-        let file = NamedTempFile::new().unwrap();
-        std::fs::write(file.path(), "a\nb\n").unwrap();
-        let inspector = Inspector::new(Cache::empty());
-
-        let outcome = inspector.inspect(file.path(), "a.rs", Limit::lines(1), MatchBy::Default);
-
-        assert!(matches!(
-            outcome,
-            OutcomeKind::Violation {
-                actual: 2,
-                limit: Limit {
-                    metric: Metric::Lines,
-                    max: 1
-                },
-                ..
-            }
-        ));
-    }
-
-    #[test]
-    fn token_file_outcome_uses_ceil_bytes_over_four() {
-        let file = NamedTempFile::new().unwrap();
-        std::fs::write(file.path(), "12345").unwrap();
-        let inspector = Inspector::new(Cache::empty());
-
-        let outcome = inspector.inspect(file.path(), "a.md", Limit::tokens(1), MatchBy::Default);
-
-        assert!(matches!(
-            outcome,
-            OutcomeKind::Violation {
-                actual: 2,
-                limit: Limit {
-                    metric: Metric::Tokens,
-                    max: 1
-                },
-                ..
-            }
-        ));
-    }
-
-    #[test]
-    fn token_file_outcome_handles_even_four_byte_chunks() {
-        let file = NamedTempFile::new().unwrap();
-        std::fs::write(file.path(), "1234").unwrap();
-        let inspector = Inspector::new(Cache::empty());
-
-        let outcome = inspector.inspect(file.path(), "a.md", Limit::tokens(1), MatchBy::Default);
-
-        assert!(matches!(
-            outcome,
-            OutcomeKind::Pass {
-                actual: 1,
-                limit: Limit {
-                    metric: Metric::Tokens,
-                    max: 1
-                },
-                ..
-            }
-        ));
-    }
-
-    #[test]
-    fn binary_file_outcome_is_cached() {
-        let file = NamedTempFile::new().unwrap();
-        std::fs::write(file.path(), b"\0binary").unwrap();
-        let inspector = Inspector::new(Cache::empty());
-
-        let outcome = inspector.inspect(file.path(), "bin.dat", Limit::lines(1), MatchBy::Default);
-
-        assert!(matches!(outcome, OutcomeKind::Binary));
-    }
-
-    #[test]
-    fn missing_file_is_not_cacheable() {
-        let inspector = Inspector::new(Cache::empty());
-
-        let outcome = inspector.inspect(
-            Path::new("missing.rs"),
-            "missing.rs",
-            Limit::lines(1),
-            MatchBy::Default,
-        );
-
-        assert!(matches!(outcome, OutcomeKind::Missing));
-    }
-
-    #[test]
-    fn cache_result_ignores_missing_mtime() {
-        let inspector = Inspector::new(Cache::empty());
-
-        inspector.cache_result("a.rs", None, CachedResult::Text(1));
-
-        let cache = inspector.into_cache().unwrap();
-        assert!(cache.get("a.rs", SystemTime::UNIX_EPOCH).is_none());
-    }
-
-    #[test]
-    fn cache_result_ignores_poisoned_cache_lock() {
-        let inspector = Inspector::new(Cache::empty());
-        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
-            let _guard = inspector.cache.lock().unwrap();
-            panic!("poison cache");
-        }));
-
-        assert!(result.is_err());
-        inspector.cache_result("a.rs", Some(SystemTime::UNIX_EPOCH), CachedResult::Text(1));
-        assert!(inspector.into_cache().is_none());
-    }
-}
+mod tests;
@@ -0,0 +1,279 @@
+use super::*;
+use std::panic::AssertUnwindSafe;
+use std::time::SystemTime;
+use tempfile::NamedTempFile;
+
+/// Returns each measured limit and value of a checked outcome.
+fn measured(outcome: &OutcomeKind) -> Vec<(Limit, usize)> {
+    match outcome {
+        OutcomeKind::Violation { measurements, .. } | OutcomeKind::Pass { measurements, .. } => {
+            measurements.iter().map(|m| (m.limit, m.actual)).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[test]
+fn text_file_outcome_uses_limit() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "a\nb\n").unwrap();
+    let inspector = Inspector::new(Cache::empty());
+
+    let Inspection {
+        kind: outcome,
+        lines,
+        ..
+    } = inspector.inspect(
+        file.path(),
+        "a.rs",
+        &[Limit::lines(1)],
+        None,
+        MatchBy::Default,
+    );
+
+    assert!(matches!(outcome, OutcomeKind::Violation { .. }));
+    assert_eq!(measured(&outcome), [(Limit::lines(1), 2)]);
+    assert_eq!(lines, Some(2));
+}
+
+#[test]
+fn token_file_outcome_uses_ceil_bytes_over_four() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "12345").unwrap();
+    let inspector = Inspector::new(Cache::empty());
+
+    let Inspection { kind: outcome, .. } = inspector.inspect(
+        file.path(),
+        "a.md",
+        &[Limit::tokens(1)],
+        None,
+        MatchBy::Default,
+    );
+
+    assert!(matches!(outcome, OutcomeKind::Violation { .. }));
+    assert_eq!(measured(&outcome), [(Limit::tokens(1), 2)]);
+}
+
+#[test]
+fn code_lines_outcome_skips_blank_lines_and_is_cached() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "a\n\n\nb\n").unwrap();
+    let inspector = Inspector::new(Cache::empty());
+    let limit = Limit::code_lines(2);
+
+    let Inspection { kind: first, .. } =
+        inspector.inspect(file.path(), "a.rs", &[limit], None, MatchBy::Default);
+    let mtime = std::fs::metadata(file.path()).unwrap().modified().unwrap();
+    let cached = inspector.into_cache().unwrap().get("a.rs", mtime);
+
+    assert!(matches!(first, OutcomeKind::Pass { .. }));
+    assert_eq!(measured(&first), [(limit, 2)]);
+    assert_eq!(
+        cached,
+        Some(CachedResult::Text {
+            actuals: vec![2],
+            long_lines: LongLines::default(),
+            lines: 4,
+            limits_hash: hash_limits(&[limit]),
+        })
+    );
+}
+
+#[test]
+fn every_limit_is_measured() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "a\nb\n").unwrap();
+    let inspector = Inspector::new(Cache::empty());
+    let limits = [Limit::lines(5), Limit::bytes(3)];
+
+    let Inspection { kind: outcome, .. } =
+        inspector.inspect(file.path(), "a.md", &limits, None, MatchBy::Default);
+
+    assert!(matches!(outcome, OutcomeKind::Violation { .. }));
+    assert_eq!(
+        measured(&outcome),
+        [(Limit::lines(5), 2), (Limit::bytes(3), 4)]
+    );
+}
+
+#[test]
+fn cached_measurements_for_other_limits_are_ignored() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "a\nb\n").unwrap();
+    let mtime = std::fs::metadata(file.path()).unwrap().modified().unwrap();
+    let mut cache = Cache::empty();
+    cache.insert("a.md".to_string(), mtime, CachedResult::text(vec![9]));
+    let inspector = Inspector::new(cache);
+    let limits = [Limit::lines(5), Limit::bytes(3)];
+
+    let Inspection { kind: outcome, .. } =
+        inspector.inspect(file.path(), "a.md", &limits, None, MatchBy::Default);
+
+    assert_eq!(
+        measured(&outcome),
+        [(Limit::lines(5), 2), (Limit::bytes(3), 4)]
+    );
+}
+
+#[test]
+fn bundle_tokens_are_counted_and_cached_with_their_own_counter() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "one two three four").unwrap();
+    let inspector = Inspector::new(Cache::empty());
+    let words = TokenCounter::Estimate(loq_core::TokenEstimate::TokensPerWord(1.0));
+    let limit = Limit::tokens(10).counted_by(words);
+
+    let inspection = inspector.inspect(
+        file.path(),
+        "a.md",
+        &[limit],
+        Some(TokenCounter::default()),
+        MatchBy::Default,
+    );
+    let mtime = std::fs::metadata(file.path()).unwrap().modified().unwrap();
+    let cached = inspector.into_cache().unwrap().get("a.md", mtime);
+
+    assert_eq!(measured(&inspection.kind), [(limit, 4)]);
+    assert_eq!(inspection.bundle_tokens, Some(5));
+    assert!(
+        matches!(&cached, Some(CachedResult::Text { actuals, .. }) if actuals == &[4, 5]),
+        "{cached:?}"
+    );
+}
+
+#[test]
+fn imports_are_measured_from_the_cache() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let main = temp.path().join("main.md");
+    let shared = temp.path().join("shared.md");
+    std::fs::write(&main, "@shared.md\n").unwrap();
+    std::fs::write(&shared, "a\nb\n").unwrap();
+    let limits = [Limit::lines(5)];
+    let mtime = std::fs::metadata(&shared).unwrap().modified().unwrap();
+    let mut cache = Cache::empty();
+    let cached = CachedResult::Text {
+        actuals: vec![7],
+        long_lines: LongLines::default(),
+        lines: 7,
+        limits_hash: hash_limits(&limits),
+    };
+    cache.insert("shared.md".to_string(), mtime, cached);
+    let inspector = Inspector::new(cache);
+    let identity = PathIdentity::new(&main, temp.path(), temp.path());
+    let imported = ImportedFile {
+        path: shared.clone(),
+        depth: 1,
+    };
+    let imports = [(
+        imported,
+        PathIdentity::new(&shared, temp.path(), temp.path()),
+    )];
+
+    let Inspection { kind: outcome, .. } =
+        inspector.inspect_with_imports(&main, &identity, &imports, &limits, None, MatchBy::Default);
+
+    assert_eq!(measured(&outcome), [(Limit::lines(5), 8)]);
+    let cache = inspector.into_cache().unwrap();
+    let mtime = std::fs::metadata(&main).unwrap().modified().unwrap();
+    assert!(cache.get("main.md", mtime).is_some());
+}
+
+#[test]
+fn byte_file_outcome_uses_file_size() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "12345").unwrap();
+    let inspector = Inspector::new(Cache::empty());
+
+    let Inspection { kind: outcome, .. } = inspector.inspect(
+        file.path(),
+        "a.md",
+        &[Limit::bytes(4)],
+        None,
+        MatchBy::Default,
+    );
+
+    assert!(matches!(outcome, OutcomeKind::Violation { .. }));
+    assert_eq!(measured(&outcome), [(Limit::bytes(4), 5)]);
+}
+
+#[test]
+fn token_file_outcome_handles_even_four_byte_chunks() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "1234").unwrap();
+    let inspector = Inspector::new(Cache::empty());
+
+    let Inspection { kind: outcome, .. } = inspector.inspect(
+        file.path(),
+        "a.md",
+        &[Limit::tokens(1)],
+        None,
+        MatchBy::Default,
+    );
+
+    assert!(matches!(outcome, OutcomeKind::Pass { .. }));
+    assert_eq!(measured(&outcome), [(Limit::tokens(1), 1)]);
+}
+
+#[test]
+fn binary_file_outcome_is_cached() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), b"\0binary").unwrap();
+    let inspector = Inspector::new(Cache::empty());
+
+    let Inspection {
+        kind: outcome,
+        lines,
+        ..
+    } = inspector.inspect(
+        file.path(),
+        "bin.dat",
+        &[Limit::lines(1)],
+        None,
+        MatchBy::Default,
+    );
+
+    assert!(matches!(outcome, OutcomeKind::Binary));
+    assert_eq!(lines, None);
+}
+
+#[test]
+fn missing_file_is_not_cacheable() {
+    let inspector = Inspector::new(Cache::empty());
+
+    let Inspection { kind: outcome, .. } = inspector.inspect(
+        Path::new("missing.rs"),
+        "missing.rs",
+        &[Limit::lines(1)],
+        None,
+        MatchBy::Default,
+    );
+
+    assert!(matches!(outcome, OutcomeKind::Missing));
+}
+
+#[test]
+fn cache_result_ignores_missing_mtime() {
+    let inspector = Inspector::new(Cache::empty());
+
+    inspector.cache_result("a.rs", None, CachedResult::text(vec![1]));
+
+    let cache = inspector.into_cache().unwrap();
+    assert!(cache.get("a.rs", SystemTime::UNIX_EPOCH).is_none());
+}
+
+#[test]
+fn cache_result_ignores_poisoned_cache_lock() {
+    let inspector = Inspector::new(Cache::empty());
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = inspector.cache.lock().unwrap();
+        panic!("poison cache");
+    }));
+
+    assert!(result.is_err());
+    inspector.cache_result(
+        "a.rs",
+        Some(SystemTime::UNIX_EPOCH),
+        CachedResult::text(vec![1]),
+    );
+    assert!(inspector.into_cache().is_none());
+}
@@ -7,24 +7,37 @@
 #![warn(missing_docs)]
 
 mod cache;
+pub mod comments;
 pub mod count;
 pub mod discover;
+pub mod explain;
+mod imports;
 mod inspection;
+pub mod line_length;
 pub mod path_identity;
 pub mod stdin;
+pub mod tokenizer;
 pub mod walk;
 
 pub use path_identity::PathIdentity;
 
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
-use loq_core::config::{compile_config, CompiledConfig, LoqConfig};
-use loq_core::decide::{decide, Decision};
+use loq_core::bundle::check_bundles;
+use loq_core::config::{compile_config, CompiledConfig, ConfigLayer, LoqConfig};
+use loq_core::decide::{decide, Decision, MatchBy};
+use loq_core::directory::check_directories;
 use loq_core::report::{FileOutcome, OutcomeKind};
+use loq_core::{render_guidance, GuidanceContext};
+use loq_core::{BundleViolation, DirectoryViolation};
 use rayon::prelude::*;
+use rustc_hash::FxHashSet;
 
-use inspection::Inspector;
+use inspection::{Inspection, Inspector};
 use thiserror::Error;
+use tokenizer::Tokenizer;
 
 /// Filesystem operation errors.
 #[derive(Debug, Error)]
@@ -35,6 +48,9 @@ pub enum FsError {
     /// General I/O error.
     #[error("{0}")]
     Io(std::io::Error),
+    /// Failed to load a config's tokenizer.
+    #[error("{0}")]
+    Tokenizer(#[from] tokenizer::TokenizerError),
     /// Failed to read a config file.
     #[error("failed to read config '{}': {}", path.display(), error)]
     ConfigRead {
@@ -51,8 +67,13 @@ pub enum CheckConfig {
     Discover,
     /// Load a specific `loq.toml` file.
     File(PathBuf),
+    /// Load a config named by the user, e.g. via `--config`.
+    ///
+    /// Unlike [`CheckConfig::File`], its paths are relative to the working
+    /// directory rather than the config file's directory.
+    Explicit(PathBuf),
     /// Use an already compiled configuration.
-    Compiled(CompiledConfig),
+    Compiled(Box<CompiledConfig>),
 }
 
 /// Options for running a check.
@@ -63,22 +84,45 @@ pub struct CheckOptions {
     pub cwd: PathBuf,
     /// Whether to use file caching (default: true).
     pub use_cache: bool,
+    /// Command-line overrides layered on every loaded config.
+    ///
+    /// Not applied to [`CheckConfig::Compiled`], which is already final.
+    pub overrides: ConfigLayer,
 }
 
 /// Output from a check run.
 pub struct CheckOutput {
     /// Results for each file checked.
     pub outcomes: Vec<FileOutcome>,
+    /// Directories over a `[[directory_rules]]` budget.
+    pub directory_violations: Vec<DirectoryViolation>,
+    /// Bundles over their `[[bundles]]` token budget.
+    pub bundle_violations: Vec<BundleViolation>,
     /// Errors encountered during directory walking.
     pub walk_errors: Vec<walk::WalkError>,
     /// Guidance text to show when violations exist.
+    ///
+    /// With discovered configs, this is the guidance of each config whose
+    /// files have violations.
     pub fix_guidance: Option<String>,
 }
 
-fn load_config_from_path(path: &Path, fallback_cwd: &Path) -> Result<CompiledConfig, FsError> {
+fn load_config_from_path(
+    path: &Path,
+    fallback_cwd: &Path,
+    overrides: &ConfigLayer,
+) -> Result<CompiledConfig, FsError> {
     let root_dir = path
         .parent()
         .map_or_else(|| fallback_cwd.to_path_buf(), Path::to_path_buf);
+    load_config_with_root(path, root_dir, overrides)
+}
+
+fn load_config_with_root(
+    path: &Path,
+    root_dir: PathBuf,
+    overrides: &ConfigLayer,
+) -> Result<CompiledConfig, FsError> {
     // Canonicalize root_dir so pathdiff works correctly with canonicalized file paths.
     // On Windows, canonicalize returns extended-length paths (\\?\C:\...).
     let root_dir = root_dir.canonicalize().unwrap_or(root_dir);
@@ -86,87 +130,266 @@ fn load_config_from_path(path: &Path, fallback_cwd: &Path) -> Result<CompiledCon
         path: path.to_path_buf(),
         error,
     })?;
-    let config = loq_core::parse_config(path, &text)?;
+    let config = loq_core::parse_config(path, &text)?.with_overrides(overrides);
     let compiled = compile_config(root_dir, config, Some(path))?;
     Ok(compiled)
 }
 
 /// Runs a check on the given paths.
 ///
-/// Loads a single config (explicit path, cwd discovery, or built-in defaults),
-/// then checks all files against that config in parallel.
+/// With an explicit or compiled config, every file is checked against that one
+/// config. With [`CheckConfig::Discover`], each file is governed by its nearest
+/// `loq.toml` (falling back to built-in defaults), and files are checked in
+/// parallel one config group at a time.
 ///
-/// Exclusion filtering (gitignore + exclude patterns) happens at the walk layer.
+/// Exclusion filtering (gitignore + include/exclude patterns) happens at the walk
+/// layer. A discovered config filters the files it governs with its own
+/// settings; the walk hands directories holding a nested config over to it.
 pub fn run_check(paths: Vec<PathBuf>, options: CheckOptions) -> Result<CheckOutput, FsError> {
-    let compiled = match options.config {
-        CheckConfig::File(path) => load_config_from_path(&path, &options.cwd)?,
-        CheckConfig::Compiled(config) => config,
-        CheckConfig::Discover => {
-            if let Some(path) = discover::find_config(&options.cwd) {
-                load_config_from_path(&path, &options.cwd)?
-            } else {
-                let config = LoqConfig::default();
-                let root_dir = options
-                    .cwd
-                    .canonicalize()
-                    .unwrap_or_else(|_| options.cwd.clone());
-                compile_config(root_dir, config, None)?
-            }
-        }
-    };
-
-    let config_hash = cache::hash_config(&compiled);
-    let file_cache = if options.use_cache {
-        cache::Cache::load(&compiled.root_dir, config_hash)
-    } else {
-        cache::Cache::empty()
-    };
-    let inspector = Inspector::new(file_cache);
-
     let cwd_abs = options
         .cwd
         .canonicalize()
         .unwrap_or_else(|_| options.cwd.clone());
 
-    let walk_options = walk::WalkOptions {
+    match options.config {
+        CheckConfig::File(path) => {
+            let compiled = load_config_from_path(&path, &options.cwd, &options.overrides)?;
+            check_single(&paths, compiled, &cwd_abs, options.use_cache)
+        }
+        CheckConfig::Explicit(path) => {
+            let compiled = load_config_with_root(&path, cwd_abs.clone(), &options.overrides)?;
+            check_single(&paths, compiled, &cwd_abs, options.use_cache)
+        }
+        CheckConfig::Compiled(compiled) => {
+            check_single(&paths, *compiled, &cwd_abs, options.use_cache)
+        }
+        CheckConfig::Discover => {
+            check_discovered(&paths, &cwd_abs, options.use_cache, &options.overrides)
+        }
+    }
+}
+
+fn default_config(cwd_abs: &Path, overrides: &ConfigLayer) -> Result<CompiledConfig, FsError> {
+    Ok(compile_config(
+        cwd_abs.to_path_buf(),
+        LoqConfig::default().with_overrides(overrides),
+        None,
+    )?)
+}
+
+fn walk_options<'a>(compiled: &'a CompiledConfig, cwd_abs: &'a Path) -> walk::WalkOptions<'a> {
+    walk::WalkOptions {
         respect_gitignore: compiled.respect_gitignore,
+        include: compiled.include_patterns(),
         exclude: compiled.exclude_patterns(),
-        cwd: &cwd_abs,
+        cwd: cwd_abs,
         root_dir: &compiled.root_dir,
-    };
-    let walk_result = walk::expand_paths(&paths, &walk_options);
-    let mut file_list = walk_result.paths;
-    let walk_errors = walk_result.errors;
-    file_list.sort();
-    file_list.dedup();
+    }
+}
 
-    let outcomes = check_group(&file_list, &compiled, &cwd_abs, &inspector);
+fn walk_files(paths: &[PathBuf], compiled: &CompiledConfig, cwd_abs: &Path) -> walk::WalkResult {
+    let mut walk_result = walk::expand_paths(paths, &walk_options(compiled, cwd_abs));
+    walk_result.paths.sort();
+    walk_result.paths.dedup();
+    walk_result
+}
 
-    if options.use_cache {
-        if let Some(cache) = inspector.into_cache() {
-            cache.save(&compiled.root_dir);
+fn check_single(
+    paths: &[PathBuf],
+    compiled: CompiledConfig,
+    cwd_abs: &Path,
+    use_cache: bool,
+) -> Result<CheckOutput, FsError> {
+    let walk_result = walk_files(paths, &compiled, cwd_abs);
+    let group = check_group(&walk_result.paths, &compiled, cwd_abs, use_cache, |_| true)?;
+
+    Ok(CheckOutput {
+        outcomes: group.outcomes,
+        directory_violations: group.directory_violations,
+        bundle_violations: group.bundle_violations,
+        walk_errors: walk_result.errors,
+        fix_guidance: compiled.fix_guidance,
+    })
+}
+
+fn check_discovered(
+    paths: &[PathBuf],
+    cwd_abs: &Path,
+    use_cache: bool,
+    overrides: &ConfigLayer,
+) -> Result<CheckOutput, FsError> {
+    let mut locator = discover::ConfigLocator::new();
+    let mut groups: BTreeMap<Option<PathBuf>, (CompiledConfig, Vec<PathBuf>)> = BTreeMap::new();
+    let mut walk_errors = Vec::new();
+
+    // Each path is walked with the config that governs it. A walk stops at
+    // directories with their own config and queues them, so every file is
+    // filtered by its own config's include, exclude and gitignore settings.
+    let mut pending = paths.to_vec();
+    while let Some(path) = pending.pop() {
+        let (compiled, files) = match groups.entry(governing_config(&path, cwd_abs, &mut locator)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let compiled = match entry.key() {
+                    Some(config_path) => load_config_from_path(config_path, cwd_abs, overrides)?,
+                    None => default_config(cwd_abs, overrides)?,
+                };
+                entry.insert((compiled, Vec::new()))
+            }
+        };
+        let walk_result = walk::expand_governed_paths(
+            std::slice::from_ref(&path),
+            &walk_options(compiled, cwd_abs),
+        );
+        files.extend(walk_result.paths);
+        walk_errors.extend(walk_result.errors);
+        pending.extend(walk_result.nested);
+    }
+
+    let mut outcomes = Vec::new();
+    let mut directory_violations = Vec::new();
+    let mut bundle_violations = Vec::new();
+    let mut fix_guidance: Vec<String> = Vec::new();
+    for (config_path, (compiled, mut files)) in groups {
+        files.sort();
+        files.dedup();
+        if files.is_empty() {
+            continue;
+        }
+        let governs = |path: &Path| governing_config(path, cwd_abs, &mut locator) == config_path;
+        let group = check_group(&files, &compiled, cwd_abs, use_cache, governs)?;
+        if group.has_violations() {
+            if let Some(guidance) = compiled.fix_guidance {
+                if !fix_guidance.contains(&guidance) {
+                    fix_guidance.push(guidance);
+                }
+            }
         }
+        directory_violations.extend(group.directory_violations);
+        bundle_violations.extend(group.bundle_violations);
+        outcomes.extend(group.outcomes);
     }
 
     Ok(CheckOutput {
         outcomes,
+        directory_violations,
+        bundle_violations,
         walk_errors,
-        fix_guidance: compiled.fix_guidance,
+        fix_guidance: (!fix_guidance.is_empty()).then(|| fix_guidance.join("\n\n")),
     })
 }
 
+/// Returns the config that governs `path`, if any: a directory's own or
+/// nearest enclosing config, or a file's nearest config.
+fn governing_config(
+    path: &Path,
+    cwd_abs: &Path,
+    locator: &mut discover::ConfigLocator,
+) -> Option<PathBuf> {
+    let absolute = cwd_abs.join(path);
+    let absolute = absolute.canonicalize().unwrap_or(absolute);
+    if absolute.is_dir() {
+        locator.find(&absolute)
+    } else {
+        absolute.parent().and_then(|dir| locator.find(dir))
+    }
+}
+
+/// Outcomes and budget violations for the files one config governs.
+struct GroupOutput {
+    outcomes: Vec<FileOutcome>,
+    directory_violations: Vec<DirectoryViolation>,
+    bundle_violations: Vec<BundleViolation>,
+}
+
+impl GroupOutput {
+    /// Returns true when a file or budget in the group is over its limit.
+    fn has_violations(&self) -> bool {
+        !self.directory_violations.is_empty()
+            || !self.bundle_violations.is_empty()
+            || self
+                .outcomes
+                .iter()
+                .any(|outcome| matches!(outcome.kind, OutcomeKind::Violation { .. }))
+    }
+}
+
+/// Checks files against one config, with that config's cache, along with the
+/// config's directory and bundle budgets.
+///
+/// Budget totals also count the files under the config's root that `governs`
+/// accepts but `paths` leaves out, so a partial run such as `--staged` sees
+/// whole directories and bundles. Only budgets holding a checked file are
+/// reported.
 fn check_group(
     paths: &[PathBuf],
     compiled: &CompiledConfig,
     cwd_abs: &Path,
-    inspector: &Inspector,
-) -> Vec<FileOutcome> {
-    paths
-        .par_iter()
-        .map(|path| check_file(path, compiled, cwd_abs, inspector))
+    use_cache: bool,
+    governs: impl FnMut(&Path) -> bool,
+) -> Result<GroupOutput, FsError> {
+    let unchecked = unchecked_paths(paths, compiled, cwd_abs, governs);
+    let tokenizer = load_tokenizer(compiled, cwd_abs)?;
+    let file_cache = if use_cache {
+        let tokenizer_hash = tokenizer.as_ref().map(Tokenizer::hash);
+        cache::Cache::load(
+            &compiled.root_dir,
+            cache::hash_config(compiled, tokenizer_hash),
+        )
+    } else {
+        cache::Cache::empty()
+    };
+    let inspector = Inspector::new(file_cache).with_tokenizer(tokenizer);
+
+    let check = |path: &PathBuf| check_file(path, compiled, cwd_abs, &inspector);
+    let outcomes = paths.par_iter().map(check).collect::<Vec<_>>();
+    let rest = unchecked.par_iter().map(check).collect::<Vec<_>>();
+
+    if use_cache {
+        if let Some(cache) = inspector.into_cache() {
+            cache.save(&compiled.root_dir);
+        }
+    }
+
+    Ok(GroupOutput {
+        directory_violations: check_directories(compiled.directory_rules(), &outcomes, &rest),
+        bundle_violations: check_bundles(compiled.bundles(), &outcomes, &rest),
+        outcomes,
+    })
+}
+
+/// Returns the files under `compiled`'s root that `governs` accepts and that
+/// are not among `paths`, when the config has directory or bundle budgets.
+fn unchecked_paths(
+    paths: &[PathBuf],
+    compiled: &CompiledConfig,
+    cwd_abs: &Path,
+    mut governs: impl FnMut(&Path) -> bool,
+) -> Vec<PathBuf> {
+    if compiled.directory_rules().is_empty() && compiled.bundles().is_empty() {
+        return Vec::new();
+    }
+    let key = |path: &Path| PathIdentity::new(path, cwd_abs, &compiled.root_dir).match_key;
+    let checked = paths.iter().map(|path| key(path)).collect::<FxHashSet<_>>();
+    walk_files(std::slice::from_ref(&compiled.root_dir), compiled, cwd_abs)
+        .paths
+        .into_iter()
+        .filter(|path| !checked.contains(&key(path)) && governs(path))
         .collect()
 }
 
+/// Loads the tokenizer a config counts tokens with, if it names one.
+///
+/// A relative path is resolved against `cwd`, like the config path it was
+/// declared relative to.
+fn load_tokenizer(compiled: &CompiledConfig, cwd: &Path) -> Result<Option<Tokenizer>, FsError> {
+    Ok(compiled
+        .tokenizer
+        .as_deref()
+        .map(|path| Tokenizer::load(&cwd.join(path)))
+        .transpose()?)
+}
+
 fn check_file(
     path: &Path,
     compiled: &CompiledConfig,
@@ -174,17 +397,93 @@ fn check_file(
     inspector: &Inspector,
 ) -> FileOutcome {
     let identity = PathIdentity::new(path, cwd_abs, &compiled.root_dir);
-    let kind = match decide(compiled, &identity.match_key) {
-        Decision::SkipNoLimit => OutcomeKind::NoLimit,
-        Decision::Check { limit, matched_by } => {
-            inspector.inspect(path, &identity.match_key, limit, matched_by)
+    let rule = compiled
+        .matching_rule(&identity.match_key)
+        .map(|(rule, _)| rule);
+    // A bundle counts each member's own tokens, without imports, with the
+    // bundle's counter rather than whatever the member's rule uses. Every
+    // bundle uses the config's counter, so one count serves each bundle the
+    // file is in.
+    let bundle = compiled
+        .bundles()
+        .iter()
+        .find(|bundle| bundle.matches(&identity.match_key))
+        .map(|bundle| bundle.limit.counter);
+    let inspection = match decide(compiled, &identity.match_key) {
+        Decision::SkipNoLimit if compiled.directory_rules().is_empty() && bundle.is_none() => {
+            Inspection::unmeasured(OutcomeKind::NoLimit)
+        }
+        // Directory and bundle budgets still count files without a limit.
+        Decision::SkipNoLimit => {
+            let inspection =
+                inspector.inspect(path, &identity.match_key, &[], bundle, MatchBy::Default);
+            match inspection.kind {
+                OutcomeKind::Missing => inspection,
+                _ => Inspection {
+                    kind: OutcomeKind::NoLimit,
+                    ..inspection
+                },
+            }
+        }
+        Decision::Check { limits, matched_by } => {
+            if rule.is_some_and(|rule| rule.follow_imports) {
+                let imports = imports::resolve_imports(path)
+                    .into_iter()
+                    .map(|import| {
+                        let import_identity =
+                            PathIdentity::new(&import.path, cwd_abs, &compiled.root_dir);
+                        (import, import_identity)
+                    })
+                    .collect::<Vec<_>>();
+                inspector
+                    .inspect_with_imports(path, &identity, &imports, &limits, bundle, matched_by)
+            } else {
+                inspector.inspect(path, &identity.match_key, &limits, bundle, matched_by)
+            }
         }
     };
+    let Inspection {
+        mut kind,
+        lines,
+        bundle_tokens,
+    } = inspection;
+
+    match &mut kind {
+        OutcomeKind::Violation {
+            measurements,
+            matched_by,
+            severity,
+        } => {
+            *severity = rule.map_or(compiled.default_severity, |rule| rule.severity);
+            if let Some(template) = rule.and_then(|rule| rule.fix_guidance.as_deref()) {
+                for measurement in measurements.iter_mut().filter(|m| m.is_over()) {
+                    let context = GuidanceContext {
+                        path: &identity.display,
+                        actual: measurement.actual,
+                        limit: measurement.limit,
+                        matched_by,
+                    };
+                    measurement.fix_guidance = Some(render_guidance(template, &context));
+                }
+            }
+        }
+        OutcomeKind::Pass { measurements, .. } => {
+            let warn_at = rule.and_then(|rule| rule.warn_at).or(compiled.warn_at);
+            for measurement in measurements {
+                measurement.near_limit = warn_at
+                    .is_some_and(|warn_at| measurement.limit.is_near(measurement.actual, warn_at));
+            }
+        }
+        _ => {}
+    }
 
     FileOutcome {
         display_path: identity.display,
         match_key: identity.match_key,
+        config_path: compiled.source_path.clone(),
         kind,
+        lines,
+        bundle_tokens,
     }
 }
 
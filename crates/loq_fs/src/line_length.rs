@@ -0,0 +1,257 @@
+//! Line length measurement.
+//!
+//! Tracks the length of each line as chunks of a file stream past, in bytes or
+//! display columns, counts the lines that run over a limit and remembers the
+//! first of them.
+//! Multi-byte characters split across chunks are carried to the next one, and
+//! so is a carriage return until it is known whether a newline follows it.
+
+use loq_core::{LineLengthUnit, LongLines};
+
+/// How many offending line numbers are kept for a finding.
+pub const LONG_LINES_SHOWN: usize = 5;
+
+/// How [`crate::count::inspect_file`] measures line length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineLength {
+    /// Unit lines are measured in.
+    pub unit: LineLengthUnit,
+    /// Lines longer than this are reported by number.
+    pub max: usize,
+}
+
+impl Default for LineLength {
+    /// Measures bytes and reports no lines.
+    fn default() -> Self {
+        Self {
+            unit: LineLengthUnit::Bytes,
+            max: usize::MAX,
+        }
+    }
+}
+
+/// Running line lengths across buffer boundaries.
+#[derive(Debug, Default)]
+pub(crate) struct LineLengths {
+    length: LineLength,
+    /// Zero-based number of the current line.
+    line: usize,
+    /// Length of the current line so far.
+    current: usize,
+    /// Length of the longest line seen.
+    pub(crate) longest: usize,
+    /// Lines longer than the limit.
+    pub(crate) long_lines: LongLines,
+    /// Leading bytes of a character cut off at the end of the last chunk.
+    pending: Vec<u8>,
+    /// Whether the last chunk ended in a carriage return, which is part of
+    /// the line only if something other than a newline follows it.
+    carriage_return: bool,
+}
+
+impl LineLengths {
+    pub(crate) fn new(length: LineLength) -> Self {
+        Self {
+            length,
+            ..Self::default()
+        }
+    }
+
+    /// Adds part of a line, without its newline.
+    pub(crate) fn extend(&mut self, mut segment: &[u8]) {
+        if segment.is_empty() {
+            return;
+        }
+        if std::mem::take(&mut self.carriage_return) {
+            self.add(b"\r");
+        }
+        if let Some(rest) = segment.strip_suffix(b"\r") {
+            self.carriage_return = true;
+            segment = rest;
+        }
+        self.add(segment);
+    }
+
+    fn add(&mut self, segment: &[u8]) {
+        self.current += match self.length.unit {
+            LineLengthUnit::Bytes => segment.len(),
+            LineLengthUnit::Width => self.width(segment),
+        };
+    }
+
+    /// Ends the current line, dropping the carriage return of a CRLF ending.
+    pub(crate) fn end_line(&mut self) {
+        self.carriage_return = false;
+        // A character cut off by the newline is invalid; count it as one column.
+        if !self.pending.is_empty() {
+            self.pending.clear();
+            self.current += 1;
+        }
+        self.longest = self.longest.max(self.current);
+        if self.current > self.length.max {
+            if self.long_lines.first.len() < LONG_LINES_SHOWN {
+                self.long_lines.first.push(self.line + 1);
+            }
+            self.long_lines.count += 1;
+        }
+        self.line += 1;
+        self.current = 0;
+    }
+
+    /// Returns the display width of `segment`, completing any character
+    /// carried from the previous chunk.
+    fn width(&mut self, mut segment: &[u8]) -> usize {
+        let mut width = 0;
+        if let Some(&lead) = self.pending.first() {
+            let missing = utf8_len(lead) - self.pending.len();
+            let take = missing.min(segment.len());
+            self.pending.extend_from_slice(&segment[..take]);
+            segment = &segment[take..];
+            if take < missing {
+                return 0;
+            }
+            width += std::str::from_utf8(&self.pending)
+                .ok()
+                .and_then(|text| text.chars().next())
+                .map_or(1, char_width);
+            self.pending.clear();
+        }
+        loop {
+            match std::str::from_utf8(segment) {
+                Ok(text) => return width + text.chars().map(char_width).sum::<usize>(),
+                Err(error) => {
+                    let (valid, rest) = segment.split_at(error.valid_up_to());
+                    width += std::str::from_utf8(valid)
+                        .unwrap_or_default()
+                        .chars()
+                        .map(char_width)
+                        .sum::<usize>();
+                    let Some(len) = error.error_len() else {
+                        self.pending.extend_from_slice(rest);
+                        return width;
+                    };
+                    width += 1;
+                    segment = &rest[len..];
+                }
+            }
+        }
+    }
+}
+
+/// Returns the byte length of the UTF-8 sequence `lead` starts.
+const fn utf8_len(lead: u8) -> usize {
+    match lead {
+        0xF0..=0xF7 => 4,
+        0xE0..=0xEF => 3,
+        0xC0..=0xDF => 2,
+        _ => 1,
+    }
+}
+
+/// Returns the columns a terminal gives `ch`: two for wide East Asian
+/// characters and emoji, none for combining marks and zero-width characters.
+const fn char_width(ch: char) -> usize {
+    match ch as u32 {
+        0x0300..=0x036F
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F
+        | 0xFEFF => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measure(unit: LineLengthUnit, max: usize, chunks: &[&[u8]]) -> LineLengths {
+        let mut lengths = LineLengths::new(LineLength { unit, max });
+        for chunk in chunks {
+            let mut start = 0;
+            for end in memchr::memchr_iter(b'\n', chunk) {
+                lengths.extend(&chunk[start..end]);
+                lengths.end_line();
+                start = end + 1;
+            }
+            lengths.extend(&chunk[start..]);
+        }
+        lengths.end_line();
+        lengths
+    }
+
+    #[test]
+    fn bytes_count_every_byte_but_the_newline() {
+        let lengths = measure(LineLengthUnit::Bytes, 3, &["ab\n你好\nabc".as_bytes()]);
+        assert_eq!(lengths.longest, 6);
+        assert_eq!(lengths.long_lines.first, [2]);
+    }
+
+    #[test]
+    fn width_counts_wide_characters_twice() {
+        let lengths = measure(
+            LineLengthUnit::Width,
+            3,
+            &["abc\n你好\ne\u{301}\n".as_bytes()],
+        );
+        assert_eq!(lengths.longest, 4);
+        assert_eq!(lengths.long_lines.first, [2]);
+    }
+
+    #[test]
+    fn characters_split_across_chunks_are_joined() {
+        let text = "你好".as_bytes();
+        let lengths = measure(
+            LineLengthUnit::Width,
+            10,
+            &[&text[..2], &text[2..4], &text[4..]],
+        );
+        assert_eq!(lengths.longest, 4);
+    }
+
+    #[test]
+    fn invalid_bytes_count_as_one_column() {
+        let lengths = measure(LineLengthUnit::Width, 10, &[b"a\xFFb\n\xE4\n"]);
+        assert_eq!(lengths.longest, 3);
+    }
+
+    #[test]
+    fn crlf_endings_are_not_counted() {
+        let lengths = measure(LineLengthUnit::Bytes, 4, &[b"abcd\r\nab\rc\r\n"]);
+        assert_eq!(lengths.longest, 4);
+        assert!(lengths.long_lines.is_empty());
+    }
+
+    #[test]
+    fn crlf_split_across_chunks_is_not_counted() {
+        let lengths = measure(LineLengthUnit::Width, 4, &[b"abcd\r", b"\nab\r", b"cd"]);
+        assert_eq!(lengths.longest, 5);
+        assert_eq!(lengths.long_lines.first, [2]);
+    }
+
+    #[test]
+    fn only_the_first_long_lines_are_kept_but_all_are_counted() {
+        let text = "xx\n".repeat(LONG_LINES_SHOWN + 2);
+        let lengths = measure(LineLengthUnit::Bytes, 1, &[text.as_bytes()]);
+        assert_eq!(lengths.long_lines.first, [1, 2, 3, 4, 5]);
+        assert_eq!(lengths.long_lines.count, LONG_LINES_SHOWN + 2);
+    }
+}
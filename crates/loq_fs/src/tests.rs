@@ -1,6 +1,8 @@
 use super::*;
 use tempfile::TempDir;
 
+mod discovery;
+
 fn write_file(dir: &TempDir, path: &str, contents: &str) -> PathBuf {
     let full = dir.path().join(path);
     if let Some(parent) = full.parent() {
@@ -26,6 +28,7 @@ fn excluded_files_are_filtered_out() {
             config: CheckConfig::File(temp.path().join("loq.toml")),
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -46,6 +49,7 @@ fn no_default_skips_files() {
             config: CheckConfig::File(temp.path().join("loq.toml")),
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -65,6 +69,7 @@ fn missing_files_reported() {
             config: CheckConfig::File(temp.path().join("loq.toml")),
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -81,6 +86,15 @@ fn binary_and_unreadable_are_reported() {
         exclude: vec![],
         rules: vec![],
         fix_guidance: None,
+        default_severity: loq_core::Severity::Error,
+        warn_at: None,
+        include: vec![],
+        pattern_syntax: loq_core::PatternSyntax::Glob,
+        tokenizer: None,
+        token_estimate: None,
+        line_length_unit: loq_core::LineLengthUnit::Width,
+        directory_rules: Vec::new(),
+        bundles: Vec::new(),
     };
     let compiled =
         loq_core::config::compile_config(temp.path().to_path_buf(), config, None).unwrap();
@@ -108,6 +122,7 @@ fn explicit_file_bypasses_gitignore() {
             config: CheckConfig::Discover,
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -131,6 +146,7 @@ fn directory_walk_respects_gitignore() {
             config: CheckConfig::Discover,
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -169,6 +185,7 @@ fn gitignore_can_be_disabled() {
             config: CheckConfig::File(temp.path().join("loq.toml")),
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -189,14 +206,15 @@ fn exactly_at_limit_passes() {
             config: CheckConfig::File(temp.path().join("loq.toml")),
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
 
     match &output.outcomes[0].kind {
-        OutcomeKind::Pass { limit, actual, .. } => {
-            assert_eq!(*limit, loq_core::Limit::lines(3));
-            assert_eq!(*actual, 3);
+        OutcomeKind::Pass { measurements, .. } => {
+            assert_eq!(measurements[0].limit, loq_core::Limit::lines(3));
+            assert_eq!(measurements[0].actual, 3);
         }
         other => panic!("expected Pass, got {other:?}"),
     }
@@ -215,14 +233,15 @@ fn one_over_limit_violates() {
             config: CheckConfig::File(temp.path().join("loq.toml")),
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
 
     match &output.outcomes[0].kind {
-        OutcomeKind::Violation { limit, actual, .. } => {
-            assert_eq!(*limit, loq_core::Limit::lines(3));
-            assert_eq!(*actual, 4);
+        OutcomeKind::Violation { measurements, .. } => {
+            assert_eq!(measurements[0].limit, loq_core::Limit::lines(3));
+            assert_eq!(measurements[0].actual, 4);
         }
         other => panic!("expected Violation, got {other:?}"),
     }
@@ -244,19 +263,64 @@ fn token_rule_uses_approximate_byte_budget() {
             config: CheckConfig::File(temp.path().join("loq.toml")),
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
 
     match &output.outcomes[0].kind {
-        OutcomeKind::Violation { limit, actual, .. } => {
-            assert_eq!(*limit, loq_core::Limit::tokens(4));
-            assert_eq!(*actual, 5);
+        OutcomeKind::Violation { measurements, .. } => {
+            assert_eq!(measurements[0].limit, loq_core::Limit::tokens(4));
+            assert_eq!(measurements[0].actual, 5);
         }
         other => panic!("expected Violation, got {other:?}"),
     }
 }
 
+#[test]
+fn rule_guidance_is_rendered_for_violations() {
+    let temp = TempDir::new().unwrap();
+    write_file(
+        &temp,
+        "loq.toml",
+        "default_max_lines = 1
+[[rules]]
+path = \"**/*.tsx\"
+max_lines = 2
+fix_guidance = \"Split {path}: {over} over ({rule})\"
+",
+    );
+    let tsx = write_file(&temp, "src/App.tsx", "a\nb\nc\n");
+    let txt = write_file(&temp, "notes.txt", "a\nb\n");
+
+    let output = run_check(
+        vec![tsx, txt],
+        CheckOptions {
+            config: CheckConfig::File(temp.path().join("loq.toml")),
+            cwd: temp.path().to_path_buf(),
+            use_cache: false,
+            overrides: ConfigLayer::default(),
+        },
+    )
+    .unwrap();
+
+    let guidance = |path: &str| match &output
+        .outcomes
+        .iter()
+        .find(|o| o.display_path == path)
+        .unwrap()
+        .kind
+    {
+        OutcomeKind::Violation { measurements, .. } => measurements[0].fix_guidance.clone(),
+        other => panic!("expected Violation, got {other:?}"),
+    };
+    assert_eq!(
+        guidance("src/App.tsx").as_deref(),
+        Some("Split src/App.tsx: 1 over (**/*.tsx)")
+    );
+    assert_eq!(guidance("notes.txt"), None);
+}
+
 #[test]
 fn gitignore_negation_works_in_directory_walk() {
     // Gitignore with negation pattern - tested via directory walk
@@ -274,6 +338,7 @@ fn gitignore_negation_works_in_directory_walk() {
             config: CheckConfig::Discover,
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -313,6 +378,7 @@ fn explicit_files_bypass_gitignore_even_with_negation() {
             config: CheckConfig::Discover,
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -343,6 +409,7 @@ fn subdir_check_uses_config_root_match_key() {
             config: CheckConfig::File(temp.path().join("loq.toml")),
             cwd,
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -350,7 +417,9 @@ fn subdir_check_uses_config_root_match_key() {
     assert_eq!(output.outcomes[0].display_path, "../src/big.rs");
     assert_eq!(output.outcomes[0].match_key, "src/big.rs");
     match &output.outcomes[0].kind {
-        OutcomeKind::Violation { limit, .. } => assert_eq!(*limit, loq_core::Limit::lines(1)),
+        OutcomeKind::Violation { measurements, .. } => {
+            assert_eq!(measurements[0].limit, loq_core::Limit::lines(1));
+        }
         other => panic!("expected Violation, got {other:?}"),
     }
 }
@@ -366,6 +435,7 @@ fn missing_config_file_returns_error() {
             config: CheckConfig::File(temp.path().join("nonexistent.toml")),
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     );
 
@@ -394,6 +464,7 @@ fn exclude_pattern_with_globstar() {
             config: CheckConfig::File(temp.path().join("loq.toml")),
             cwd: temp.path().to_path_buf(),
             use_cache: false,
+            overrides: ConfigLayer::default(),
         },
     )
     .unwrap();
@@ -0,0 +1,237 @@
+use super::*;
+
+#[test]
+fn discover_uses_nearest_config_per_file() {
+    let temp = TempDir::new().unwrap();
+    write_file(&temp, "loq.toml", "default_max_lines = 10\n");
+    write_file(
+        &temp,
+        "pkg/loq.toml",
+        "default_max_lines = 1\n\n[[rules]]\npath = \"src/*.txt\"\nmax_lines = 3\n",
+    );
+    write_file(&temp, "root.txt", "a\nb\n");
+    write_file(&temp, "pkg/top.txt", "a\nb\n");
+    write_file(&temp, "pkg/src/nested.txt", "a\nb\n");
+
+    let output = run_check(
+        vec![temp.path().to_path_buf()],
+        CheckOptions {
+            config: CheckConfig::Discover,
+            cwd: temp.path().to_path_buf(),
+            use_cache: false,
+            overrides: ConfigLayer::default(),
+        },
+    )
+    .unwrap();
+
+    let root = temp.path().canonicalize().unwrap();
+    let outcome = |name: &str| {
+        output
+            .outcomes
+            .iter()
+            .find(|o| o.display_path == name)
+            .unwrap_or_else(|| panic!("missing outcome for {name}"))
+    };
+
+    let root_file = outcome("root.txt");
+    assert!(matches!(root_file.kind, OutcomeKind::Pass { .. }));
+    assert_eq!(root_file.config_path, Some(root.join("loq.toml")));
+
+    let top = outcome("pkg/top.txt");
+    assert!(matches!(top.kind, OutcomeKind::Violation { .. }));
+    assert_eq!(top.match_key, "top.txt");
+    assert_eq!(top.config_path, Some(root.join("pkg/loq.toml")));
+
+    let nested = outcome("pkg/src/nested.txt");
+    assert_eq!(nested.match_key, "src/nested.txt");
+    match &nested.kind {
+        OutcomeKind::Pass {
+            measurements,
+            matched_by,
+        } => {
+            assert_eq!(measurements[0].limit, loq_core::Limit::lines(3));
+            assert_eq!(
+                *matched_by,
+                loq_core::MatchBy::Rule {
+                    pattern: "src/*.txt".to_string()
+                }
+            );
+        }
+        other => panic!("expected pass, got {other:?}"),
+    }
+}
+
+#[test]
+fn discover_applies_nested_config_excludes() {
+    let temp = TempDir::new().unwrap();
+    write_file(&temp, "loq.toml", "default_max_lines = 10\n");
+    write_file(
+        &temp,
+        "pkg/loq.toml",
+        "default_max_lines = 10\nexclude = [\"gen/**\"]\n",
+    );
+    write_file(&temp, "gen/kept.txt", "a\n");
+    write_file(&temp, "pkg/gen/dropped.txt", "a\n");
+
+    let output = run_check(
+        vec![temp.path().to_path_buf()],
+        CheckOptions {
+            config: CheckConfig::Discover,
+            cwd: temp.path().to_path_buf(),
+            use_cache: false,
+            overrides: ConfigLayer::default(),
+        },
+    )
+    .unwrap();
+
+    let file_names: Vec<_> = output
+        .outcomes
+        .iter()
+        .map(|o| o.display_path.as_str())
+        .collect();
+    assert_eq!(file_names, vec!["gen/kept.txt"]);
+}
+
+#[test]
+fn discover_walks_nested_configs_with_their_own_filters() {
+    let temp = TempDir::new().unwrap();
+    write_file(
+        &temp,
+        "loq.toml",
+        "default_max_lines = 10\ninclude = [\"src/**\"]\n",
+    );
+    write_file(&temp, "packages/a/loq.toml", "default_max_lines = 10\n");
+    write_file(&temp, "src/kept.txt", "a\n");
+    write_file(&temp, "docs/dropped.txt", "a\n");
+    write_file(&temp, "packages/a/lib.txt", "a\n");
+
+    let output = run_check(
+        vec![temp.path().to_path_buf()],
+        CheckOptions {
+            config: CheckConfig::Discover,
+            cwd: temp.path().to_path_buf(),
+            use_cache: false,
+            overrides: ConfigLayer::default(),
+        },
+    )
+    .unwrap();
+
+    let file_names: Vec<_> = output
+        .outcomes
+        .iter()
+        .map(|o| o.display_path.as_str())
+        .collect();
+    assert_eq!(file_names, vec!["src/kept.txt", "packages/a/lib.txt"]);
+}
+
+#[test]
+fn discover_shows_guidance_of_configs_with_violations() {
+    let temp = TempDir::new().unwrap();
+    write_file(
+        &temp,
+        "loq.toml",
+        "default_max_lines = 10\nfix_guidance = \"Split root files.\"\n",
+    );
+    write_file(
+        &temp,
+        "pkg/loq.toml",
+        "default_max_lines = 1\nfix_guidance = \"Split pkg files.\"\n",
+    );
+    write_file(&temp, "root.txt", "a\n");
+    write_file(&temp, "pkg/big.txt", "a\nb\n");
+
+    let output = run_check(
+        vec![temp.path().to_path_buf()],
+        CheckOptions {
+            config: CheckConfig::Discover,
+            cwd: temp.path().to_path_buf(),
+            use_cache: false,
+            overrides: ConfigLayer::default(),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(output.fix_guidance.as_deref(), Some("Split pkg files."));
+}
+
+#[test]
+fn discover_without_any_config_records_no_config_path() {
+    let temp = TempDir::new().unwrap();
+    let file = write_file(&temp, "a.txt", "a\n");
+
+    let output = run_check(
+        vec![file],
+        CheckOptions {
+            config: CheckConfig::Discover,
+            cwd: temp.path().to_path_buf(),
+            use_cache: false,
+            overrides: ConfigLayer::default(),
+        },
+    )
+    .unwrap();
+
+    assert!(output.outcomes[0].config_path.is_none());
+}
+
+#[test]
+fn explicit_config_governs_files_under_nested_configs() {
+    let temp = TempDir::new().unwrap();
+    write_file(&temp, "loq.toml", "default_max_lines = 10\n");
+    write_file(&temp, "pkg/loq.toml", "default_max_lines = 1\n");
+    let file = write_file(&temp, "pkg/a.txt", "a\nb\n");
+
+    let output = run_check(
+        vec![file],
+        CheckOptions {
+            config: CheckConfig::File(temp.path().join("loq.toml")),
+            cwd: temp.path().to_path_buf(),
+            use_cache: false,
+            overrides: ConfigLayer::default(),
+        },
+    )
+    .unwrap();
+
+    assert!(matches!(output.outcomes[0].kind, OutcomeKind::Pass { .. }));
+    assert_eq!(output.outcomes[0].match_key, "pkg/a.txt");
+}
+
+#[test]
+fn explicit_config_paths_are_relative_to_cwd() {
+    let temp = TempDir::new().unwrap();
+    write_file(
+        &temp,
+        "ci/strict.toml",
+        "default_max_lines = 1\nexclude = [\"vendor/**\"]\n\n[[rules]]\npath = \"src/*.txt\"\nmax_lines = 3\n",
+    );
+    write_file(&temp, "repo/loq.toml", "default_max_lines = 100\n");
+    write_file(&temp, "repo/src/a.txt", "a\nb\n");
+    write_file(&temp, "repo/top.txt", "a\nb\n");
+    write_file(&temp, "repo/vendor/lib.txt", "a\nb\n");
+
+    let output = run_check(
+        vec![temp.path().join("repo")],
+        CheckOptions {
+            config: CheckConfig::Explicit(temp.path().join("ci/strict.toml")),
+            cwd: temp.path().join("repo"),
+            use_cache: false,
+            overrides: ConfigLayer::default(),
+        },
+    )
+    .unwrap();
+
+    let root = temp.path().canonicalize().unwrap();
+    assert_eq!(output.outcomes.len(), 2);
+    for outcome in &output.outcomes {
+        assert_eq!(outcome.config_path, Some(root.join("ci/strict.toml")));
+    }
+    let kind = |name: &str| {
+        &output
+            .outcomes
+            .iter()
+            .find(|o| o.match_key == name)
+            .unwrap_or_else(|| panic!("missing outcome for {name}"))
+            .kind
+    };
+    assert!(matches!(kind("src/a.txt"), OutcomeKind::Pass { .. }));
+    assert!(matches!(kind("top.txt"), OutcomeKind::Violation { .. }));
+}
@@ -0,0 +1,256 @@
+//! Exact token counting with a byte-pair encoding vocabulary.
+//!
+//! Loads a tiktoken-format vocabulary (one base64 token and its merge rank per
+//! line), splits text the way `cl100k_base` does, and merges each piece's
+//! bytes by rank to count the tokens it encodes to.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+
+use rustc_hash::{FxHashMap, FxHasher};
+use thiserror::Error;
+
+/// Errors that can occur when loading a vocabulary file.
+#[derive(Debug, Error)]
+pub enum TokenizerError {
+    /// The file could not be read.
+    #[error("failed to read tokenizer '{}': {error}", path.display())]
+    Read {
+        /// Path to the vocabulary file.
+        path: PathBuf,
+        /// The underlying I/O error.
+        error: std::io::Error,
+    },
+    /// A line is not a base64 token followed by a rank.
+    #[error("invalid tokenizer '{}': line {line} is not a base64 token and a rank", path.display())]
+    Invalid {
+        /// Path to the vocabulary file.
+        path: PathBuf,
+        /// One-based line number.
+        line: usize,
+    },
+}
+
+/// A byte-pair encoding vocabulary.
+#[derive(Debug)]
+pub struct Tokenizer {
+    ranks: FxHashMap<Vec<u8>, u32>,
+    hash: u64,
+}
+
+impl Tokenizer {
+    /// Loads a tiktoken-format vocabulary file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or a line is malformed.
+    pub fn load(path: &Path) -> Result<Self, TokenizerError> {
+        let bytes = std::fs::read(path).map_err(|error| TokenizerError::Read {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        let invalid = |line| TokenizerError::Invalid {
+            path: path.to_path_buf(),
+            line,
+        };
+        let text = std::str::from_utf8(&bytes).map_err(|_| invalid(1))?;
+        let mut ranks = FxHashMap::default();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (token, rank) = line
+                .split_once(' ')
+                .and_then(|(token, rank)| Some((decode_base64(token)?, rank.trim().parse().ok()?)))
+                .ok_or_else(|| invalid(index + 1))?;
+            ranks.insert(token, rank);
+        }
+        let mut hasher = FxHasher::default();
+        hasher.write(&bytes);
+        Ok(Self {
+            ranks,
+            hash: hasher.finish(),
+        })
+    }
+
+    /// Returns a hash of the vocabulary file's contents.
+    #[must_use]
+    pub const fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Counts the tokens `text` encodes to.
+    #[must_use]
+    pub fn count(&self, text: &[u8]) -> usize {
+        let text = String::from_utf8_lossy(text);
+        let mut rest = text.as_ref();
+        let mut tokens = 0;
+        while !rest.is_empty() {
+            let (piece, tail) = rest.split_at(piece_len(rest));
+            tokens += self.count_piece(piece.as_bytes());
+            rest = tail;
+        }
+        tokens
+    }
+
+    /// Counts the tokens of one piece by merging its lowest-ranked byte pairs.
+    ///
+    /// Parts form a linked list over the piece's byte offsets, and candidate
+    /// pairs wait in a heap, so long pieces such as minified lines merge in
+    /// `O(n log n)` rather than rescanning every pair after each merge.
+    fn count_piece(&self, piece: &[u8]) -> usize {
+        if self.ranks.contains_key(piece) {
+            return 1;
+        }
+        let len = piece.len();
+        // `next[start]` is the end of the part beginning at `start`.
+        let mut next = (1..=len).collect::<Vec<_>>();
+        // `prev[start]` is the start of the part before it; unused for 0.
+        let mut prev = (0..len)
+            .map(|start| start.saturating_sub(1))
+            .collect::<Vec<_>>();
+        let mut alive = vec![true; len];
+        let mut parts = len;
+
+        let mut heap = BinaryHeap::new();
+        let pair = |next: &[usize], start: usize| {
+            let end = *next.get(next[start])?;
+            let rank = self.ranks.get(&piece[start..end])?;
+            Some(Reverse((*rank, start, end)))
+        };
+        heap.extend((0..len).filter_map(|start| pair(&next, start)));
+
+        while let Some(Reverse((_, start, end))) = heap.pop() {
+            // Skip pairs whose parts have merged since they were queued.
+            if !alive[start] || next[start] >= len || next[next[start]] != end {
+                continue;
+            }
+            alive[next[start]] = false;
+            next[start] = end;
+            if end < len {
+                prev[end] = start;
+            }
+            parts -= 1;
+            heap.extend(pair(&next, start));
+            if start > 0 {
+                heap.extend(pair(&next, prev[start]));
+            }
+        }
+        parts
+    }
+}
+
+/// Returns the byte length of the piece at the start of `text`, following the
+/// `cl100k_base` split pattern:
+///
+/// ```text
+/// (?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}
+/// | ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+
+/// ```
+fn piece_len(text: &str) -> usize {
+    let mut chars = text.chars();
+    let Some(first) = chars.next() else {
+        return 0;
+    };
+    let second = chars.next();
+
+    if first == '\'' {
+        let rest = &text.as_bytes()[1..];
+        for suffix in ["s", "t", "re", "ve", "m", "ll", "d"] {
+            if rest
+                .get(..suffix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(suffix.as_bytes()))
+            {
+                return 1 + suffix.len();
+            }
+        }
+    }
+
+    if !is_newline(first) && !is_word(first) && second.is_some_and(char::is_alphabetic) {
+        let start = first.len_utf8();
+        return start + run_len(&text[start..], char::is_alphabetic);
+    }
+    if first.is_alphabetic() {
+        return run_len(text, char::is_alphabetic);
+    }
+    if first.is_numeric() {
+        return text
+            .chars()
+            .take(3)
+            .take_while(|ch| ch.is_numeric())
+            .map(char::len_utf8)
+            .sum();
+    }
+
+    let offset = usize::from(first == ' ' && second.is_some_and(is_symbol));
+    if text[offset..].chars().next().is_some_and(is_symbol) {
+        let symbols = offset + run_len(&text[offset..], is_symbol);
+        return symbols + run_len(&text[symbols..], is_newline);
+    }
+
+    let spaces = run_len(text, char::is_whitespace);
+    if let Some(last_newline) = text[..spaces].rfind(['\r', '\n']) {
+        return last_newline + 1;
+    }
+    if spaces == text.len() {
+        return spaces;
+    }
+    // Leave the last space to start the next piece, e.g. " word".
+    let last = text[..spaces].chars().next_back().map_or(0, char::len_utf8);
+    if spaces > last {
+        spaces - last
+    } else {
+        spaces
+    }
+}
+
+fn run_len(text: &str, class: impl Fn(char) -> bool) -> usize {
+    text.find(|ch: char| !class(ch)).unwrap_or(text.len())
+}
+
+const fn is_newline(ch: char) -> bool {
+    matches!(ch, '\r' | '\n')
+}
+
+fn is_word(ch: char) -> bool {
+    ch.is_alphabetic() || ch.is_numeric()
+}
+
+fn is_symbol(ch: char) -> bool {
+    !ch.is_whitespace() && !is_word(ch)
+}
+
+/// Decodes standard padded base64.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    fn value(byte: u8) -> Option<u32> {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        Some(u32::from(value))
+    }
+
+    let text = text.trim_end_matches('=').as_bytes();
+    if text.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        let mut bits = 0;
+        for (index, byte) in chunk.iter().enumerate() {
+            bits |= value(*byte)? << (18 - 6 * index);
+        }
+        let bytes = bits.to_be_bytes();
+        out.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests;
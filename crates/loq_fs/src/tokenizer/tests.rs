@@ -0,0 +1,161 @@
+use std::io::Write;
+
+use tempfile::NamedTempFile;
+
+use super::*;
+
+fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let mut bits = 0u32;
+        for (index, byte) in chunk.iter().enumerate() {
+            bits |= u32::from(*byte) << (16 - 8 * index);
+        }
+        for index in 0..4 {
+            if index <= chunk.len() {
+                out.push(ALPHABET[(bits >> (18 - 6 * index) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Writes a vocabulary with every single byte, then `merges` in rank order.
+fn vocabulary(merges: &[&str]) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    let singles = (0..=255u8).map(|byte| vec![byte]);
+    let pairs = merges.iter().map(|merge| merge.as_bytes().to_vec());
+    for (rank, token) in singles.chain(pairs).enumerate() {
+        writeln!(file, "{} {rank}", encode_base64(&token)).unwrap();
+    }
+    file
+}
+
+fn pieces(text: &str) -> Vec<&str> {
+    let mut rest = text;
+    let mut pieces = Vec::new();
+    while !rest.is_empty() {
+        let (piece, tail) = rest.split_at(piece_len(rest));
+        pieces.push(piece);
+        rest = tail;
+    }
+    pieces
+}
+
+#[test]
+fn base64_roundtrips() {
+    for bytes in [
+        &b""[..],
+        b"a",
+        b"ab",
+        b"abc",
+        b"hello world",
+        &[0, 255, 128],
+    ] {
+        assert_eq!(decode_base64(&encode_base64(bytes)).as_deref(), Some(bytes));
+    }
+    assert_eq!(decode_base64("a!=="), None);
+}
+
+#[test]
+fn text_is_split_like_cl100k() {
+    assert_eq!(
+        pieces("Hello world's 12345!!\n\n  x"),
+        ["Hello", " world", "'s", " ", "123", "45", "!!\n\n", " ", " x"]
+    );
+    assert_eq!(pieces("fn main() {}"), ["fn", " main", "()", " {}"]);
+    assert_eq!(pieces("a  \n\tb"), ["a", "  \n", "\tb"]);
+    assert_eq!(pieces("end   "), ["end", "   "]);
+    assert_eq!(pieces("I'LL"), ["I", "'LL"]);
+}
+
+#[test]
+fn pieces_merge_by_rank() {
+    let file = vocabulary(&["ab", "abc", " a", " ab", " abc"]);
+    let tokenizer = Tokenizer::load(file.path()).unwrap();
+
+    assert_eq!(tokenizer.count(b"abc abc"), 2);
+    assert_eq!(tokenizer.count(b"abcd"), 2);
+    assert_eq!(tokenizer.count(b"xyz"), 3);
+    assert_eq!(tokenizer.count(b""), 0);
+}
+
+#[test]
+fn lower_ranked_merges_win() {
+    // "bc" outranks "ab", so "abc" becomes "a" + "bc".
+    let file = vocabulary(&["bc", "ab"]);
+    let tokenizer = Tokenizer::load(file.path()).unwrap();
+    assert_eq!(tokenizer.count(b"abc"), 2);
+}
+
+#[test]
+fn long_pieces_merge_quickly() {
+    // A minified line can be one huge piece; rescanning every pair after
+    // each merge would take minutes here.
+    let file = vocabulary(&["aa", "aaaa", "aaaaaaaa"]);
+    let tokenizer = Tokenizer::load(file.path()).unwrap();
+    let text = "a".repeat(200_000);
+    assert_eq!(pieces(&text).len(), 1);
+    assert_eq!(tokenizer.count(text.as_bytes()), 25_000);
+    assert_eq!(tokenizer.count(b"aaaaaaaaaa"), 2);
+}
+
+#[test]
+fn hash_follows_file_contents() {
+    let first = Tokenizer::load(vocabulary(&["ab"]).path()).unwrap();
+    let same = Tokenizer::load(vocabulary(&["ab"]).path()).unwrap();
+    let other = Tokenizer::load(vocabulary(&["bc"]).path()).unwrap();
+    assert_eq!(first.hash(), same.hash());
+    assert_ne!(first.hash(), other.hash());
+}
+
+#[test]
+fn malformed_lines_are_reported() {
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(file, "YQ== 0\nnot-base64!\n").unwrap();
+    let err = Tokenizer::load(file.path()).unwrap_err();
+    assert!(
+        matches!(err, TokenizerError::Invalid { line: 2, .. }),
+        "{err}"
+    );
+}
+
+#[test]
+fn missing_file_is_reported() {
+    let err = Tokenizer::load(Path::new("does-not-exist.tiktoken")).unwrap_err();
+    assert!(matches!(err, TokenizerError::Read { .. }));
+}
+
+#[test]
+fn relative_path_resolves_against_the_check_cwd() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::copy(
+        vocabulary(&["ab"]).path(),
+        temp.path().join("vocab.tiktoken"),
+    )
+    .unwrap();
+    let file = temp.path().join("a.txt");
+    std::fs::write(&file, "ab").unwrap();
+    let text = "default_max_tokens = 1\ntokenizer = \"vocab.tiktoken\"\n";
+    let config = loq_core::parse_config(Path::new("loq.toml"), text).unwrap();
+    let compiled = loq_core::config::compile_config(temp.path().to_path_buf(), config, None);
+
+    let output = crate::run_check(
+        vec![file],
+        crate::CheckOptions {
+            config: crate::CheckConfig::Compiled(Box::new(compiled.unwrap())),
+            cwd: temp.path().to_path_buf(),
+            use_cache: false,
+            overrides: loq_core::ConfigLayer::default(),
+        },
+    )
+    .unwrap();
+
+    assert!(matches!(
+        output.outcomes[0].kind,
+        crate::OutcomeKind::Pass { .. }
+    ));
+}
@@ -7,10 +7,12 @@ use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 
-use ignore::WalkBuilder;
+use ignore::gitignore::Gitignore;
+use ignore::{Match, WalkBuilder};
 use loq_core::PatternList;
 use thiserror::Error;
 
+use crate::discover::config_in_dir;
 use crate::PathIdentity;
 
 /// Files/directories that are always excluded regardless of configuration.
@@ -23,6 +25,52 @@ fn is_hardcoded_exclude(path: &Path) -> bool {
         .is_some_and(|name| HARDCODED_EXCLUDES.contains(&name))
 }
 
+/// Returns the hardcoded exclude naming `path` or one of its directories.
+pub(crate) fn hardcoded_exclude(path: &Path) -> Option<&'static str> {
+    path.components().find_map(|component| {
+        let name = component.as_os_str().to_str()?;
+        HARDCODED_EXCLUDES
+            .iter()
+            .copied()
+            .find(|exclude| *exclude == name)
+    })
+}
+
+/// A `.gitignore` pattern that ignores a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitignoreMatch {
+    /// The pattern as written.
+    pub pattern: String,
+    /// The `.gitignore` file containing the pattern.
+    pub source: Option<PathBuf>,
+}
+
+/// Finds the `.gitignore` pattern that ignores `path` during a directory walk.
+///
+/// `path` must be absolute. Like the walker, nearer `.gitignore` files take
+/// precedence, and a negated (`!`) pattern un-ignores the path.
+pub(crate) fn gitignore_match(path: &Path) -> Option<GitignoreMatch> {
+    let is_dir = path.is_dir();
+    for dir in path.ancestors().skip(1) {
+        let file = dir.join(".gitignore");
+        if !file.is_file() {
+            continue;
+        }
+        let (gitignore, _) = Gitignore::new(&file);
+        match gitignore.matched_path_or_any_parents(path, is_dir) {
+            Match::Ignore(glob) => {
+                return Some(GitignoreMatch {
+                    pattern: glob.original().to_string(),
+                    source: glob.from().map(Path::to_path_buf),
+                });
+            }
+            Match::Whitelist(_) => return None,
+            Match::None => {}
+        }
+    }
+    None
+}
+
 /// Error encountered while walking a directory.
 #[derive(Debug, Error)]
 #[error("{message}")]
@@ -37,12 +85,17 @@ pub struct WalkResult {
     pub paths: Vec<PathBuf>,
     /// Errors encountered during walking.
     pub errors: Vec<WalkError>,
+    /// Directories holding their own config that the walk left for the
+    /// caller, when walking with [`expand_governed_paths`].
+    pub nested: Vec<PathBuf>,
 }
 
 /// Options for directory walking and filtering.
 pub struct WalkOptions<'a> {
     /// Whether to respect `.gitignore` files during walking.
     pub respect_gitignore: bool,
+    /// Include patterns from config; when non-empty, only matching files are kept.
+    pub include: &'a PatternList,
     /// Exclude patterns from config.
     pub exclude: &'a PatternList,
     /// Canonical working directory for resolving relative paths.
@@ -59,18 +112,37 @@ pub struct WalkOptions<'a> {
 /// **Gitignore behavior (matches ruff):**
 /// - Explicit file paths bypass gitignore (if you name a file, you want it checked)
 /// - Directory walks respect gitignore via the `ignore` crate
-/// - Exclude patterns from config always apply to both
+/// - Include and exclude patterns from config always apply to both
 #[must_use]
 pub fn expand_paths(paths: &[PathBuf], options: &WalkOptions) -> WalkResult {
+    expand(paths, options, false)
+}
+
+/// Expands paths like [`expand_paths`], but stops at directories below a
+/// walked one that hold their own config.
+///
+/// Those directories are returned in [`WalkResult::nested`] instead, so the
+/// caller can walk them with the options of the config that governs them.
+/// A directory whose config file matches an exclude pattern is skipped.
+/// Include patterns don't prune the walk, since they only cover the files
+/// of the walked config.
+#[must_use]
+pub fn expand_governed_paths(paths: &[PathBuf], options: &WalkOptions) -> WalkResult {
+    expand(paths, options, true)
+}
+
+fn expand(paths: &[PathBuf], options: &WalkOptions, stop_at_configs: bool) -> WalkResult {
     let mut files = Vec::new();
     let mut errors = Vec::new();
+    let mut nested = Vec::new();
 
     for path in paths {
         if path.exists() {
             if path.is_dir() {
-                let result = walk_directory(path, options);
+                let result = walk_directory(path, options, stop_at_configs);
                 files.extend(result.paths);
                 errors.extend(result.errors);
+                nested.extend(result.nested);
             } else {
                 // Explicit file path - bypass gitignore (like ruff), but respect exclude patterns
                 if should_skip_explicit_path(path, options) {
@@ -87,20 +159,42 @@ pub fn expand_paths(paths: &[PathBuf], options: &WalkOptions) -> WalkResult {
     WalkResult {
         paths: files,
         errors,
+        nested,
     }
 }
 
-/// Checks if an explicit file path should be skipped (hardcoded or exclude pattern).
+/// Checks if an explicit file path should be skipped (hardcoded, include or exclude pattern).
 ///
 /// Explicit paths bypass gitignore (following ruff's model: if you name a file, you want it checked).
 fn should_skip_explicit_path(path: &Path, options: &WalkOptions) -> bool {
+    is_hardcoded_exclude(path) || is_filtered_out(path, options)
+}
+
+/// Checks if a path falls outside every include pattern or matches an exclude pattern.
+fn is_filtered_out(path: &Path, options: &WalkOptions) -> bool {
+    let identity = PathIdentity::new(path, options.cwd, options.root_dir);
+    let included =
+        options.include.is_empty() || options.include.matches(&identity.match_key).is_some();
+    !included || options.exclude.matches(&identity.match_key).is_some()
+}
+
+/// Checks if a path matches an exclude pattern.
+fn is_excluded(path: &Path, options: &WalkOptions) -> bool {
     let identity = PathIdentity::new(path, options.cwd, options.root_dir);
-    is_hardcoded_exclude(path) || options.exclude.matches(&identity.match_key).is_some()
+    options.exclude.matches(&identity.match_key).is_some()
+}
+
+/// Checks if files below the directory `path` could match an include pattern.
+fn may_hold_included(path: &Path, include: &PatternList, cwd: &Path, root_dir: &Path) -> bool {
+    let key = PathIdentity::new(path, cwd, root_dir).match_key;
+    // Directories above the root lead back into it.
+    key.split('/').all(|name| name == "..") || include.could_match_under(&key)
 }
 
-fn walk_directory(path: &PathBuf, options: &WalkOptions) -> WalkResult {
+fn walk_directory(path: &PathBuf, options: &WalkOptions, stop_at_configs: bool) -> WalkResult {
     let (path_tx, path_rx) = mpsc::channel();
     let (error_tx, error_rx) = mpsc::channel();
+    let (nested_tx, nested_rx) = mpsc::channel();
 
     let mut builder = WalkBuilder::new(path);
     builder
@@ -113,11 +207,26 @@ fn walk_directory(path: &PathBuf, options: &WalkOptions) -> WalkResult {
         builder.add_custom_ignore_filename(".gitignore");
     }
 
+    // Skip directories no include pattern reaches, so their contents are
+    // never read. Files are still matched against the includes below.
+    // A governed walk can't prune: a directory no include reaches may still
+    // hold a nested config, whose files answer to its own includes.
+    if !options.include.is_empty() && !stop_at_configs {
+        let include = options.include.clone();
+        let cwd = options.cwd.to_path_buf();
+        let root_dir = options.root_dir.to_path_buf();
+        builder.filter_entry(move |entry| {
+            !entry.file_type().is_some_and(|t| t.is_dir())
+                || may_hold_included(entry.path(), &include, &cwd, &root_dir)
+        });
+    }
+
     let walker = builder.build_parallel();
 
     walker.run(|| {
         let path_tx = path_tx.clone();
         let error_tx = error_tx.clone();
+        let nested_tx = nested_tx.clone();
         Box::new(move |entry| {
             match entry {
                 Ok(e) => {
@@ -130,6 +239,17 @@ fn walk_directory(path: &PathBuf, options: &WalkOptions) -> WalkResult {
                             ignore::WalkState::Continue
                         };
                     }
+                    if stop_at_configs && e.depth() > 0 && e.file_type().is_some_and(|t| t.is_dir())
+                    {
+                        if let Some(config) = config_in_dir(entry_path) {
+                            // A nested config the walk's own excludes match is
+                            // skipped along with its directory.
+                            if !is_excluded(&config, options) {
+                                let _ = nested_tx.send(e.into_path());
+                            }
+                            return ignore::WalkState::Skip;
+                        }
+                    }
                     if e.file_type().is_some_and(|t| t.is_file()) {
                         let _ = path_tx.send(e.into_path());
                     }
@@ -146,20 +266,19 @@ fn walk_directory(path: &PathBuf, options: &WalkOptions) -> WalkResult {
 
     drop(path_tx);
     drop(error_tx);
+    drop(nested_tx);
 
-    // Filter walked paths through exclude patterns
+    // Filter walked paths through include and exclude patterns
     // (gitignore is already handled by the walker)
     let paths: Vec<PathBuf> = path_rx
         .into_iter()
-        .filter(|p| {
-            let identity = PathIdentity::new(p, options.cwd, options.root_dir);
-            options.exclude.matches(&identity.match_key).is_none()
-        })
+        .filter(|p| !is_filtered_out(p, options))
         .collect();
 
     WalkResult {
         paths,
         errors: error_rx.into_iter().collect(),
+        nested: nested_rx.into_iter().collect(),
     }
 }
 
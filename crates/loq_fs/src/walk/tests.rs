@@ -2,6 +2,8 @@ use super::*;
 use loq_core::config::{compile_config, LoqConfig};
 use tempfile::TempDir;
 
+mod governed;
+
 #[cfg(unix)]
 struct PermissionGuard {
     path: PathBuf,
@@ -46,6 +48,7 @@ fn expands_directory() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -65,6 +68,7 @@ fn expands_file_and_missing() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -90,6 +94,7 @@ fn respects_gitignore_when_enabled() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: true,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -119,6 +124,7 @@ fn includes_gitignored_when_disabled() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -142,6 +148,7 @@ fn exclude_pattern_filters_walked_files() {
     let exclude = exclude_pattern("**/*.txt");
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -164,6 +171,7 @@ fn exclude_pattern_filters_explicit_files() {
     let exclude = exclude_pattern("**/*.txt");
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -196,6 +204,7 @@ fn walk_errors_are_reported_for_unreadable_dirs() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -221,6 +230,7 @@ fn exclude_dotdir_pattern_without_leading_globstar() {
     let exclude = exclude_pattern(".git/**");
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -247,6 +257,7 @@ fn symlink_to_file_not_followed_by_default() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -274,6 +285,7 @@ fn symlink_to_parent_dir_does_not_loop() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -297,6 +309,7 @@ fn hardcoded_excludes_filter_loq_cache_dir() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -320,6 +333,7 @@ fn hardcoded_excludes_filter_loq_toml() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -342,6 +356,7 @@ fn hardcoded_excludes_filter_explicit_loq_toml() {
     let exclude = empty_exclude();
     let options = WalkOptions {
         respect_gitignore: false,
+        include: &PatternList::default(),
         exclude: &exclude,
         cwd: &root,
         root_dir: &root,
@@ -351,3 +366,118 @@ fn hardcoded_excludes_filter_explicit_loq_toml() {
     assert_eq!(result.paths.len(), 1);
     assert!(result.paths.iter().any(|p| p.ends_with("keep.rs")));
 }
+
+fn include_patterns(patterns: &[&str]) -> loq_core::PatternList {
+    let config = LoqConfig {
+        include: patterns.iter().map(ToString::to_string).collect(),
+        ..LoqConfig::default()
+    };
+    let compiled = compile_config(PathBuf::from("."), config, None).unwrap();
+    compiled.include_patterns().clone()
+}
+
+#[test]
+fn include_patterns_limit_directory_walk() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().canonicalize().unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("prompts")).unwrap();
+    std::fs::create_dir_all(root.join("docs")).unwrap();
+    std::fs::write(root.join("src/lib.rs"), "a").unwrap();
+    std::fs::write(root.join("prompts/agent.md"), "b").unwrap();
+    std::fs::write(root.join("docs/guide.md"), "c").unwrap();
+
+    let include = include_patterns(&["src/**", "prompts/**"]);
+    let exclude = exclude_pattern("**/*.md");
+    let options = WalkOptions {
+        respect_gitignore: false,
+        include: &include,
+        exclude: &exclude,
+        cwd: &root,
+        root_dir: &root,
+    };
+    let result = expand_paths(std::slice::from_ref(&root), &options);
+    assert_eq!(result.paths, vec![root.join("src/lib.rs")]);
+}
+
+#[test]
+fn include_patterns_filter_explicit_files() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().canonicalize().unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    let inside = root.join("src/lib.rs");
+    let outside = root.join("build.rs");
+    std::fs::write(&inside, "a").unwrap();
+    std::fs::write(&outside, "b").unwrap();
+
+    let include = include_patterns(&["src/**"]);
+    let exclude = empty_exclude();
+    let options = WalkOptions {
+        respect_gitignore: false,
+        include: &include,
+        exclude: &exclude,
+        cwd: &root,
+        root_dir: &root,
+    };
+    let result = expand_paths(&[inside.clone(), outside], &options);
+    assert_eq!(result.paths, vec![inside]);
+}
+
+#[cfg(unix)]
+#[test]
+fn unreadable_dirs_outside_includes_are_skipped() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().canonicalize().unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/lib.rs"), "a").unwrap();
+    let blocked = root.join("blocked");
+    std::fs::create_dir(&blocked).unwrap();
+
+    let original_mode = std::fs::metadata(&blocked).unwrap().permissions().mode();
+    let _guard = PermissionGuard {
+        path: blocked.clone(),
+        mode: original_mode,
+    };
+
+    let mut perms = std::fs::metadata(&blocked).unwrap().permissions();
+    perms.set_mode(0o000);
+    std::fs::set_permissions(&blocked, perms).unwrap();
+
+    let include = include_patterns(&["src/**"]);
+    let exclude = empty_exclude();
+    let options = WalkOptions {
+        respect_gitignore: false,
+        include: &include,
+        exclude: &exclude,
+        cwd: &root,
+        root_dir: &root,
+    };
+    let result = expand_paths(std::slice::from_ref(&root), &options);
+    assert_eq!(result.paths, vec![root.join("src/lib.rs")]);
+    assert!(result.errors.is_empty());
+}
+
+#[test]
+fn include_patterns_apply_when_walking_above_the_root() {
+    let temp = TempDir::new().unwrap();
+    let cwd = temp.path().canonicalize().unwrap();
+    let root = cwd.join("project");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(cwd.join("other/src")).unwrap();
+    std::fs::write(root.join("src/lib.rs"), "a").unwrap();
+    std::fs::write(cwd.join("other/src/lib.rs"), "b").unwrap();
+
+    let include = include_patterns(&["src/**"]);
+    let exclude = empty_exclude();
+    let options = WalkOptions {
+        respect_gitignore: false,
+        include: &include,
+        exclude: &exclude,
+        cwd: &cwd,
+        root_dir: &root,
+    };
+    let result = expand_paths(std::slice::from_ref(&cwd), &options);
+    assert_eq!(result.paths, vec![root.join("src/lib.rs")]);
+}
@@ -0,0 +1,45 @@
+use super::*;
+
+#[test]
+fn governed_walk_stops_at_nested_configs() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().canonicalize().unwrap();
+    std::fs::write(root.join("loq.toml"), "").unwrap();
+    std::fs::write(root.join("a.txt"), "a").unwrap();
+    std::fs::create_dir_all(root.join("pkg/sub")).unwrap();
+    std::fs::write(root.join("pkg/loq.toml"), "").unwrap();
+    std::fs::write(root.join("pkg/sub/b.txt"), "b").unwrap();
+
+    let exclude = empty_exclude();
+    let options = WalkOptions {
+        respect_gitignore: false,
+        include: &PatternList::default(),
+        exclude: &exclude,
+        cwd: &root,
+        root_dir: &root,
+    };
+    let result = expand_governed_paths(std::slice::from_ref(&root), &options);
+    assert_eq!(result.paths, vec![root.join("a.txt")]);
+    assert_eq!(result.nested, vec![root.join("pkg")]);
+}
+
+#[test]
+fn governed_walk_skips_excluded_nested_configs() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().canonicalize().unwrap();
+    std::fs::create_dir_all(root.join("pkg")).unwrap();
+    std::fs::write(root.join("pkg/loq.toml"), "").unwrap();
+    std::fs::write(root.join("pkg/b.txt"), "b").unwrap();
+
+    let exclude = exclude_pattern("pkg/**");
+    let options = WalkOptions {
+        respect_gitignore: false,
+        include: &PatternList::default(),
+        exclude: &exclude,
+        cwd: &root,
+        root_dir: &root,
+    };
+    let result = expand_governed_paths(std::slice::from_ref(&root), &options);
+    assert!(result.paths.is_empty());
+    assert!(result.nested.is_empty());
+}